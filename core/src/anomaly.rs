@@ -0,0 +1,147 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+
+const MIN_STDDEV: f64 = 1.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anomaly {
+    pub account_id: String,
+    pub day: NaiveDate,
+    pub count: usize,
+    pub mean: f64,
+    pub stddev: f64,
+    pub z_score: f64,
+}
+
+impl Anomaly {
+    pub fn is_spike(&self) -> bool {
+        self.z_score > 0.0
+    }
+}
+
+pub fn z_score(history: &[f64], value: f64) -> f64 {
+    let (mean, stddev) = mean_stddev(history);
+
+    (value - mean) / stddev
+}
+
+fn mean_stddev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, MIN_STDDEV);
+    }
+
+    let size = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / size;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / size;
+
+    (mean, variance.sqrt().max(MIN_STDDEV))
+}
+
+pub fn detect_anomalies(
+    timestamps: &[(String, NaiveDateTime)],
+    day: NaiveDate,
+    window: usize,
+    threshold: f64,
+) -> Vec<Anomaly> {
+    let start = day - Duration::days(window as i64);
+
+    let mut daily_counts: HashMap<&str, HashMap<NaiveDate, usize>> = HashMap::new();
+
+    timestamps
+        .iter()
+        .filter(|(_, timestamp)| timestamp.date() >= start && timestamp.date() <= day)
+        .for_each(|(account_id, timestamp)| {
+            *daily_counts
+                .entry(account_id)
+                .or_default()
+                .entry(timestamp.date())
+                .or_default() += 1;
+        });
+
+    let mut anomalies = daily_counts
+        .into_iter()
+        .filter_map(|(account_id, counts)| {
+            let history = (0..window)
+                .map(|offset| start + Duration::days(offset as i64))
+                .map(|history_day| *counts.get(&history_day).unwrap_or(&0) as f64)
+                .collect::<Vec<f64>>();
+            let count = *counts.get(&day).unwrap_or(&0);
+
+            let (mean, stddev) = mean_stddev(&history);
+            let score = z_score(&history, count as f64);
+
+            if score.abs() >= threshold {
+                Some(Anomaly {
+                    account_id: account_id.to_string(),
+                    day,
+                    count,
+                    mean,
+                    stddev,
+                    z_score: score,
+                })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<Anomaly>>();
+
+    anomalies.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::{detect_anomalies, z_score, MIN_STDDEV};
+
+    #[test]
+    fn z_score_should_work() {
+        assert_eq!(z_score(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], 9.0), 2.0);
+        assert_eq!(z_score(&[0.0, 0.0, 0.0], 3.0), 3.0);
+        assert_eq!(z_score(&[], 0.0), 0.0);
+    }
+
+    #[test]
+    fn detect_anomalies_should_work() {
+        let day = NaiveDate::from_ymd(2020, 5, 15);
+        let at = |offset: i64| (day - Duration::days(offset)).and_hms(12, 0, 0);
+
+        let mut timestamps = (1..=7)
+            .map(|offset| ("quiet".to_string(), at(offset)))
+            .chain((1..=7).map(|offset| ("busy".to_string(), at(offset))))
+            .collect::<Vec<_>>();
+        timestamps.extend((0..10).map(|_| ("busy".to_string(), at(0))));
+        timestamps.push(("quiet".to_string(), at(0)));
+
+        let anomalies = detect_anomalies(&timestamps, day, 7, 3.0);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].account_id, "busy");
+        assert_eq!(anomalies[0].count, 10);
+        assert!(anomalies[0].is_spike());
+    }
+
+    #[test]
+    fn detect_anomalies_should_report_clamped_stddev() {
+        let day = NaiveDate::from_ymd(2020, 5, 15);
+        let timestamps = (0..5)
+            .map(|_| ("flat".to_string(), day.and_hms(12, 0, 0)))
+            .collect::<Vec<_>>();
+
+        let anomalies = detect_anomalies(&timestamps, day, 7, 3.0);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].mean, 0.0);
+        assert_eq!(anomalies[0].stddev, MIN_STDDEV);
+        assert_eq!(
+            anomalies[0].z_score,
+            (anomalies[0].count as f64 - anomalies[0].mean) / anomalies[0].stddev
+        );
+    }
+}
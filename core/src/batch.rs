@@ -0,0 +1,213 @@
+use futures::prelude::Future;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::oneshot, time::delay_for};
+
+use crate::ApiError;
+
+type PendingRequest = (Value, oneshot::Sender<Option<Value>>);
+
+#[derive(Clone, Debug)]
+pub struct RequestBatcher {
+    max_size: usize,
+    window: Duration,
+    pending: Arc<Mutex<Vec<PendingRequest>>>,
+}
+
+impl Default for RequestBatcher {
+    fn default() -> Self {
+        Self::new(1, Duration::from_millis(0))
+    }
+}
+
+impl RequestBatcher {
+    pub fn new(max_size: usize, window: Duration) -> Self {
+        Self {
+            max_size: max_size.max(1),
+            window,
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_size > 1
+    }
+
+    pub(crate) async fn submit<F, Fut>(&self, payload: Value, send: F) -> Option<Value>
+    where
+        F: FnOnce(Value) -> Fut,
+        Fut: Future<Output = Result<Vec<Value>, ApiError>>,
+    {
+        let (sender, receiver) = oneshot::channel();
+
+        let (is_leader, is_full) = {
+            let mut pending = self.pending.lock().expect("Unable to get lock");
+
+            pending.push((payload, sender));
+
+            (pending.len() == 1, pending.len() >= self.max_size)
+        };
+
+        if is_full {
+            self.flush(send).await;
+        } else if is_leader {
+            delay_for(self.window).await;
+
+            self.flush(send).await;
+        }
+
+        receiver.await.ok().flatten()
+    }
+
+    async fn flush<F, Fut>(&self, send: F)
+    where
+        F: FnOnce(Value) -> Fut,
+        Fut: Future<Output = Result<Vec<Value>, ApiError>>,
+    {
+        let batch = self
+            .pending
+            .lock()
+            .expect("Unable to get lock")
+            .drain(..)
+            .collect::<Vec<PendingRequest>>();
+
+        if batch.is_empty() {
+            return;
+        }
+
+        let batch_size = batch.len();
+        let (payloads, senders): (Vec<Value>, Vec<oneshot::Sender<Option<Value>>>) = batch
+            .into_iter()
+            .enumerate()
+            .map(|(id, (mut payload, sender))| {
+                payload["jsonrpc"] = json!("2.0");
+                payload["id"] = json!(id);
+
+                (payload, sender)
+            })
+            .unzip();
+
+        debug!("Sending batch of {} requests", batch_size);
+
+        match send(Value::Array(payloads)).await {
+            Ok(responses) => {
+                let mut responses = responses
+                    .into_iter()
+                    .filter_map(|response| {
+                        let id = response.get("id").and_then(|id| id.as_u64())?;
+
+                        Some((id as usize, response))
+                    })
+                    .collect::<HashMap<usize, Value>>();
+
+                senders.into_iter().enumerate().for_each(|(id, sender)| {
+                    sender.send(responses.remove(&id)).ok();
+                });
+            }
+            Err(err) => {
+                warn!(
+                    "Batch of {} requests failed, sending them one by one: {}",
+                    batch_size, err
+                );
+
+                if let ApiError::RateLimited {
+                    retry_after: Some(retry_after),
+                } = err
+                {
+                    delay_for(retry_after).await;
+                }
+
+                drop(senders);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use serde_json::{json, Value};
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use super::RequestBatcher;
+    use crate::ApiError;
+
+    #[tokio::test]
+    async fn submit_should_batch_concurrent_requests() {
+        let batcher = RequestBatcher::new(2, Duration::from_millis(10));
+        let batches = Arc::new(Mutex::new(Vec::new()));
+
+        let submit = |page: usize, fails: bool| {
+            let batches = batches.clone();
+
+            batcher.submit(
+                json!({ "method": "comment_list", "page": page }),
+                move |batch| {
+                    batches.lock().unwrap().push(batch.clone());
+
+                    let responses = batch
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .rev()
+                        .map(|payload| json!({ "id": payload["id"], "result": payload["page"] }))
+                        .collect::<Vec<Value>>();
+
+                    future::ready(if fails {
+                        Err(ApiError::InvalidResponse)
+                    } else {
+                        Ok(responses)
+                    })
+                },
+            )
+        };
+
+        let results = future::join3(submit(1, false), submit(2, false), submit(3, false)).await;
+
+        assert_eq!(
+            results.0.map(|response| response["result"].clone()),
+            Some(json!(1))
+        );
+        assert_eq!(
+            results.1.map(|response| response["result"].clone()),
+            Some(json!(2))
+        );
+        assert_eq!(
+            results.2.map(|response| response["result"].clone()),
+            Some(json!(3))
+        );
+
+        let batches = batches.lock().unwrap().clone();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0][1]["jsonrpc"], json!("2.0"));
+        assert_eq!(batches[0][1]["id"], json!(1));
+        assert_eq!(batches[1].as_array().map(Vec::len), Some(1));
+
+        assert_eq!(submit(4, true).await, None);
+        assert!(!RequestBatcher::default().is_enabled());
+    }
+
+    #[tokio::test]
+    async fn submit_should_wait_out_rate_limits_before_falling_back() {
+        let batcher = RequestBatcher::new(1, Duration::from_millis(0));
+        let started_at = Instant::now();
+
+        let response = batcher
+            .submit(json!({ "method": "comment_list" }), |_| {
+                future::ready(Err::<Vec<Value>, ApiError>(ApiError::RateLimited {
+                    retry_after: Some(Duration::from_millis(50)),
+                }))
+            })
+            .await;
+
+        assert_eq!(response, None);
+        assert!(started_at.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{ClaimSetting, SavedSearch, Snooze};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConflictStrategy {
+    Keep,
+    Overwrite,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keep" => Ok(Self::Keep),
+            "overwrite" => Ok(Self::Overwrite),
+            _ => Err(format!("Unknown conflict strategy {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RuleBundle {
+    #[serde(default)]
+    pub filters: Vec<SavedSearch>,
+    #[serde(default)]
+    pub blocklist: Vec<Snooze>,
+    #[serde(default)]
+    pub claims: Vec<ClaimSetting>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BundleMerge {
+    pub bundle: RuleBundle,
+    pub conflicts: Vec<String>,
+}
+
+fn merge_entries<T: Clone + PartialEq>(
+    existing: &[T],
+    incoming: Vec<T>,
+    key: impl Fn(&T) -> String,
+    strategy: ConflictStrategy,
+    conflicts: &mut Vec<String>,
+) -> Vec<T> {
+    incoming
+        .into_iter()
+        .filter(
+            |entry| match existing.iter().find(|current| key(current) == key(entry)) {
+                Some(current) if current == entry => false,
+                Some(_) => {
+                    conflicts.push(key(entry));
+
+                    strategy == ConflictStrategy::Overwrite
+                }
+                None => true,
+            },
+        )
+        .collect()
+}
+
+impl RuleBundle {
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(value: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(value)
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty() && self.blocklist.is_empty() && self.claims.is_empty()
+    }
+
+    pub fn merge(&self, incoming: RuleBundle, strategy: ConflictStrategy) -> BundleMerge {
+        let mut conflicts = Vec::new();
+
+        let filters = merge_entries(
+            &self.filters,
+            incoming.filters,
+            |search| format!("filter {}", search.name),
+            strategy,
+            &mut conflicts,
+        );
+        let blocklist = merge_entries(
+            &self.blocklist,
+            incoming.blocklist,
+            |snooze| format!("blocklist {} {}", snooze.kind, snooze.target),
+            strategy,
+            &mut conflicts,
+        );
+        let claims = merge_entries(
+            &self.claims,
+            incoming.claims,
+            |setting| format!("claim {}", setting.claim_id),
+            strategy,
+            &mut conflicts,
+        );
+
+        BundleMerge {
+            bundle: RuleBundle {
+                filters,
+                blocklist,
+                claims,
+            },
+            conflicts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{ConflictStrategy, RuleBundle};
+    use crate::{ClaimSetting, SavedSearch, Snooze};
+
+    fn bundle(query: &str, retention_days: Option<i32>) -> RuleBundle {
+        RuleBundle {
+            filters: vec![SavedSearch {
+                name: "spam".to_string(),
+                query: query.to_string(),
+                recipient: None,
+            }],
+            blocklist: vec![Snooze {
+                kind: "commenter".to_string(),
+                target: "@spammer".to_string(),
+                until: NaiveDate::from_ymd(2030, 1, 1).and_hms(0, 0, 0),
+            }],
+            claims: vec![ClaimSetting {
+                claim_id: "claim_id".to_string(),
+                retention_days,
+            }],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn rule_bundle_should_roundtrip_yaml() {
+        let original = bundle("text:crypto", Some(30));
+        let yaml = original.to_yaml().expect("Unable to export bundle");
+
+        assert_eq!(RuleBundle::from_yaml(&yaml).ok(), Some(original));
+        assert_eq!(
+            RuleBundle::from_yaml("filters: []").ok(),
+            Some(RuleBundle::default())
+        );
+    }
+
+    #[test]
+    fn rule_bundle_should_merge() {
+        let existing = bundle("text:crypto", Some(30));
+        let incoming = bundle("text:giveaway", Some(30));
+
+        let kept = existing.merge(incoming.clone(), ConflictStrategy::Keep);
+        assert!(kept.bundle.is_empty());
+        assert_eq!(kept.conflicts, vec!["filter spam".to_string()]);
+
+        let overwritten = existing.merge(incoming.clone(), ConflictStrategy::Overwrite);
+        assert_eq!(overwritten.bundle.filters, incoming.filters);
+        assert!(overwritten.bundle.blocklist.is_empty());
+        assert!(overwritten.bundle.claims.is_empty());
+
+        let fresh = RuleBundle::default().merge(incoming.clone(), ConflictStrategy::Keep);
+        assert_eq!(fresh.bundle, incoming);
+        assert!(fresh.conflicts.is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+use std::env;
+
+use crate::{NotificationChannel, Recipient, Webhook};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChannelTarget {
+    Email(Recipient),
+    Webhook(Webhook),
+}
+
+impl NotificationChannel {
+    pub fn target(&self) -> Result<ChannelTarget, String> {
+        match self.kind.as_str() {
+            "email" => self.target.parse::<Recipient>().map(ChannelTarget::Email),
+            "webhook" => {
+                let webhook = self.target.parse::<Webhook>()?;
+
+                Ok(ChannelTarget::Webhook(Webhook {
+                    bearer_token: self
+                        .token_env
+                        .as_ref()
+                        .and_then(|token_env| env::var(token_env).ok()),
+                    ..webhook
+                }))
+            }
+            _ => Err(format!("Unknown channel kind {}", self.kind)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::ChannelTarget;
+    use crate::{NotificationChannel, PayloadVersion, Recipient, Webhook};
+
+    fn channel(kind: &str, target: &str, token_env: Option<&str>) -> NotificationChannel {
+        NotificationChannel {
+            name: "channel".to_string(),
+            kind: kind.to_string(),
+            target: target.to_string(),
+            token_env: token_env.map(String::from),
+        }
+    }
+
+    #[test]
+    fn target_should_parse_channel() {
+        assert_eq!(
+            channel("email", "me@example.com", None).target(),
+            Ok(ChannelTarget::Email(Recipient::new(
+                "me@example.com".to_string()
+            )))
+        );
+
+        env::set_var("CHANNEL_TEST_TOKEN", "secret");
+        assert_eq!(
+            channel(
+                "webhook",
+                "https://example.com/hook|v2",
+                Some("CHANNEL_TEST_TOKEN")
+            )
+            .target(),
+            Ok(ChannelTarget::Webhook(Webhook {
+                url: "https://example.com/hook".to_string(),
+                version: PayloadVersion::V2,
+                bearer_token: Some("secret".to_string()),
+            }))
+        );
+
+        assert!(channel("sms", "+15550100", None).target().is_err());
+        assert!(channel("email", "", None).target().is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use reqwest::{Certificate, Client, Proxy};
+
+use crate::ApiTimeouts;
+
+pub const DEFAULT_USER_AGENT: &str = concat!("lbry-comment-notifier/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientConfig {
+    pub max_idle_per_host: Option<usize>,
+    pub http2_prior_knowledge: bool,
+    pub proxy: Option<String>,
+    pub root_certificate: Option<Vec<u8>>,
+    pub accept_invalid_certs: bool,
+    pub compression: bool,
+    pub user_agent: String,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: None,
+            http2_prior_knowledge: false,
+            proxy: None,
+            root_certificate: None,
+            accept_invalid_certs: false,
+            compression: true,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+}
+
+impl ClientConfig {
+    pub fn build_client(&self, timeouts: &ApiTimeouts) -> reqwest::Result<Client> {
+        let builder = Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .connect_timeout(timeouts.connect_timeout)
+            .timeout(timeouts.request_timeout);
+        let builder = match self.max_idle_per_host {
+            Some(max_idle_per_host) => builder.max_idle_per_host(max_idle_per_host),
+            None => builder,
+        };
+        let builder = if self.http2_prior_knowledge {
+            builder.http2_prior_knowledge()
+        } else {
+            builder
+        };
+        let builder = match &self.proxy {
+            Some(proxy) => builder.proxy(Proxy::all(proxy.as_str())?),
+            None => builder,
+        };
+
+        let builder = match &self.root_certificate {
+            Some(pem) => builder.add_root_certificate(Certificate::from_pem(pem)?),
+            None => builder,
+        };
+
+        builder
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .gzip(self.compression)
+            .brotli(self.compression)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientConfig;
+    use crate::ApiTimeouts;
+
+    #[test]
+    fn client_config_should_apply_proxy() {
+        let config = ClientConfig {
+            proxy: Some("http://127.0.0.1:8080".to_string()),
+            ..ClientConfig::default()
+        };
+        assert!(config.build_client(&ApiTimeouts::default()).is_ok());
+
+        let invalid = ClientConfig {
+            proxy: Some("not a proxy".to_string()),
+            ..ClientConfig::default()
+        };
+        assert!(invalid.build_client(&ApiTimeouts::default()).is_err());
+    }
+
+    #[test]
+    fn client_config_should_apply_user_agent() {
+        assert!(ClientConfig::default()
+            .user_agent
+            .starts_with("lbry-comment-notifier/"));
+
+        let invalid = ClientConfig {
+            user_agent: "notifier\n".to_string(),
+            ..ClientConfig::default()
+        };
+        assert!(invalid.build_client(&ApiTimeouts::default()).is_err());
+    }
+
+    #[test]
+    fn client_config_should_apply_certificates() {
+        let insecure = ClientConfig {
+            accept_invalid_certs: true,
+            ..ClientConfig::default()
+        };
+        assert!(insecure.build_client(&ApiTimeouts::default()).is_ok());
+
+        let uncompressed = ClientConfig {
+            compression: false,
+            ..ClientConfig::default()
+        };
+        assert!(uncompressed.build_client(&ApiTimeouts::default()).is_ok());
+
+        let invalid = ClientConfig {
+            root_certificate: Some(b"not a certificate".to_vec()),
+            ..ClientConfig::default()
+        };
+        assert!(invalid.build_client(&ApiTimeouts::default()).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use crate::CommentEntity;
+
+const SHINGLE_SIZE: usize = 4;
+const NUM_HASHES: usize = 64;
+
+fn shingles(text: &str) -> HashSet<String> {
+    let normalized = text
+        .to_lowercase()
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let chars = normalized.chars().collect::<Vec<char>>();
+
+    if chars.len() <= SHINGLE_SIZE {
+        return vec![normalized].into_iter().collect();
+    }
+
+    chars
+        .windows(SHINGLE_SIZE)
+        .map(|window| window.iter().collect::<String>())
+        .collect()
+}
+
+fn seeded_hash(seed: usize, shingle: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    shingle.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn minhash(text: &str) -> Vec<u64> {
+    let shingles = shingles(text);
+
+    (0..NUM_HASHES)
+        .map(|seed| {
+            shingles
+                .iter()
+                .map(|shingle| seeded_hash(seed, shingle))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+pub fn similarity(a: &[u64], b: &[u64]) -> f64 {
+    let matches = a.iter().zip(b).filter(|(x, y)| x == y).count();
+
+    matches as f64 / NUM_HASHES as f64
+}
+
+pub fn cluster_comments(comments: &[CommentEntity], threshold: f64) -> Vec<Vec<&CommentEntity>> {
+    let mut clusters: Vec<(Vec<u64>, Vec<&CommentEntity>)> = Vec::new();
+
+    for comment in comments {
+        let signature = minhash(&comment.comment);
+
+        let cluster = clusters.iter_mut().find(|(cluster_signature, members)| {
+            members[0].claim_id == comment.claim_id
+                && similarity(cluster_signature, &signature) >= threshold
+        });
+
+        match cluster {
+            Some((_, members)) => members.push(comment),
+            None => clusters.push((signature, vec![comment])),
+        }
+    }
+
+    clusters.into_iter().map(|(_, members)| members).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{cluster_comments, minhash, similarity};
+    use crate::{testing, CommentEntity};
+
+    fn comment(id: &str, claim_id: &str, text: &str) -> CommentEntity {
+        CommentEntity {
+            claim_name: claim_id.to_string(),
+            commenter_name: format!("@{}", id),
+            commenter_url: format!("lbry://@{}", id),
+            comment: text.to_string(),
+            timestamp: NaiveDate::from_ymd(2020, 5, 1).and_hms(0, 0, 0),
+            ..testing::comment_entity(id, claim_id)
+        }
+    }
+
+    #[test]
+    fn minhash_should_estimate_similarity() {
+        let signature = minhash("Great video!");
+
+        assert_eq!(similarity(&signature, &minhash("great   VIDEO")), 1.0);
+        assert!(similarity(&signature, &minhash("Where can I buy LBC?")) < 0.2);
+    }
+
+    #[test]
+    fn cluster_comments_should_group_similar_comments() {
+        let comments = vec![
+            comment("a", "claim", "Get free LBC at example dot com now"),
+            comment("b", "claim", "Thanks for the tutorial"),
+            comment("c", "claim", "Get free LBC at example dot com today"),
+            comment("d", "other", "Get free LBC at example dot com now"),
+            comment("e", "claim", "get free lbc at example dot com NOW!!"),
+        ];
+
+        let ids = cluster_comments(&comments, 0.5)
+            .into_iter()
+            .map(|cluster| {
+                cluster
+                    .into_iter()
+                    .map(|comment| comment.id.as_str())
+                    .collect::<Vec<&str>>()
+            })
+            .collect::<Vec<Vec<&str>>>();
+
+        assert_eq!(ids, vec![vec!["a", "c", "e"], vec!["b"], vec!["d"]]);
+    }
+}
@@ -0,0 +1,91 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CollectionValue {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub claims: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Collection {
+    #[serde(rename(deserialize = "claim_id"))]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub value: CollectionValue,
+}
+
+impl Collection {
+    pub fn display_name(&self) -> &str {
+        self.value
+            .title
+            .as_deref()
+            .filter(|title| !title.is_empty())
+            .unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClaimCollections {
+    collections: HashMap<String, String>,
+}
+
+impl ClaimCollections {
+    pub fn new(collections: &[Collection]) -> Self {
+        let mut claim_collections = HashMap::new();
+
+        for collection in collections {
+            for claim_id in &collection.value.claims {
+                claim_collections
+                    .entry(claim_id.clone())
+                    .or_insert_with(|| collection.display_name().to_string());
+            }
+        }
+
+        Self {
+            collections: claim_collections,
+        }
+    }
+
+    pub fn get(&self, claim_id: &str) -> Option<&str> {
+        self.collections.get(claim_id).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.collections.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ClaimCollections, Collection};
+
+    #[test]
+    fn claim_collections_should_map_claims() {
+        let collections = serde_json::from_value::<Vec<Collection>>(json!([
+            {
+                "claim_id": "season_1",
+                "name": "season-1",
+                "value": { "title": "Season 1", "claims": ["episode_1", "episode_2"] },
+            },
+            {
+                "claim_id": "favorites",
+                "name": "favorites",
+                "value": { "claims": ["episode_2", "trailer"] },
+            },
+        ]))
+        .expect("Invalid collections");
+
+        let claim_collections = ClaimCollections::new(&collections);
+        assert_eq!(claim_collections.get("episode_1"), Some("Season 1"));
+        assert_eq!(claim_collections.get("episode_2"), Some("Season 1"));
+        assert_eq!(claim_collections.get("trailer"), Some("favorites"));
+        assert_eq!(claim_collections.get("other"), None);
+        assert!(ClaimCollections::default().is_empty());
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::{DateTime, Utc};
+use futures::{
+    future::TryFutureExt,
+    prelude::Future,
+    stream::{Stream, StreamExt, TryStreamExt},
+};
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::{
+    cursor::{try_stream_until, CommentPaging},
+    skip_errors, try_stream_paginated, try_stream_resumable, Api, ApiError, ApiMetrics,
+    ApiTimeouts, ApiTracer, ClientConfig, Comment, CommentCursors, CommentSort, CommentStop,
+    CommentVisibility, PaginatedApiResult, RetryPolicy,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommentBackend {
+    Sdk,
+    Commentron,
+}
+
+impl FromStr for CommentBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sdk" => Ok(Self::Sdk),
+            "commentron" => Ok(Self::Commentron),
+            _ => Err(format!("Unknown comment backend {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Commentron {
+    api: Api,
+}
+
+impl Commentron {
+    pub fn new(url: String) -> Self {
+        Self { api: Api::new(url) }
+    }
+
+    pub fn with_timeouts(url: String, timeouts: ApiTimeouts) -> Self {
+        Self {
+            api: Api::with_timeouts(url, timeouts),
+        }
+    }
+
+    pub fn with_client_config(url: String, timeouts: ApiTimeouts, config: ClientConfig) -> Self {
+        Self {
+            api: Api::with_client_config(url, timeouts, config),
+        }
+    }
+
+    pub fn with_header(self, name: String, value: String) -> Self {
+        Self {
+            api: self.api.with_header(name, value),
+        }
+    }
+
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            api: self.api.with_retry_policy(retry_policy),
+        }
+    }
+
+    pub fn with_page_concurrency(self, page_concurrency: usize) -> Self {
+        Self {
+            api: self.api.with_page_concurrency(page_concurrency),
+        }
+    }
+
+    pub fn with_ordered_pages(self, ordered_pages: bool) -> Self {
+        Self {
+            api: self.api.with_ordered_pages(ordered_pages),
+        }
+    }
+
+    pub fn with_comment_visibility(self, comment_visibility: CommentVisibility) -> Self {
+        Self {
+            api: self.api.with_comment_visibility(comment_visibility),
+        }
+    }
+
+    pub fn with_comment_sort(self, comment_sort: CommentSort) -> Self {
+        Self {
+            api: self.api.with_comment_sort(comment_sort),
+        }
+    }
+
+    pub fn with_tracer(self, tracer: ApiTracer) -> Self {
+        Self {
+            api: self.api.with_tracer(tracer),
+        }
+    }
+
+    pub fn metrics(&self) -> &ApiMetrics {
+        self.api.metrics()
+    }
+
+    pub fn list_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r {
+        self.list_comments_by_claim_id_sorted(claim_id, page, page_size, self.api.comment_sort)
+    }
+
+    pub fn count_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+    ) -> impl Future<Output = Result<usize, ApiError>> + 'r {
+        self.list_comments_by_claim_id(claim_id, 1, 1)
+            .map_ok(|paginated| paginated.total_items)
+    }
+
+    pub fn list_comments_by_claim_id_sorted<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+        page: usize,
+        page_size: usize,
+        sort: CommentSort,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r {
+        let mut payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "comment.List",
+            "params": {
+                "claim_id": claim_id,
+                "page": page,
+                "page_size": page_size,
+            }
+        });
+        let visibility = self.api.comment_visibility;
+        visibility.apply_commentron(&mut payload);
+        sort.apply(&mut payload);
+
+        self.api
+            .request_data::<Comment>(&payload)
+            .map_ok(move |mut result| {
+                if visibility == CommentVisibility::Visible {
+                    result.items.retain(|comment| !comment.is_hidden);
+                }
+
+                result
+                    .items
+                    .iter_mut()
+                    .for_each(|comment| comment.source = "odysee".to_string());
+
+                result
+            })
+    }
+
+    fn try_stream_comment_pages<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        paging: CommentPaging,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        let commentron = self.clone();
+        let cursor_claim_id = claim_id.clone();
+        let sort = paging.sort(self.api.comment_sort);
+        let f = move |page| {
+            debug!("Fetching comment of claim {} in page {}", &claim_id, page);
+
+            let inner_claim_id = claim_id.clone();
+
+            commentron
+                .list_comments_by_claim_id_sorted(&claim_id, page, page_size, sort)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching comments for claim {} in page {}",
+                        inner_claim_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching comments: {}", err);
+                })
+        };
+
+        match paging {
+            CommentPaging::All => {
+                try_stream_paginated(self.api.page_concurrency, self.api.ordered_pages, f)
+                    .left_stream()
+            }
+            CommentPaging::From(cursors) => {
+                try_stream_resumable(self.api.page_concurrency, cursor_claim_id, cursors, f)
+                    .left_stream()
+                    .right_stream()
+            }
+            CommentPaging::Until(stop) => try_stream_until(stop, f).right_stream().right_stream(),
+        }
+    }
+
+    pub fn try_stream_comments_by_claim_id<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::All)
+    }
+
+    pub fn try_stream_comments_by_claim_id_from<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        cursors: CommentCursors,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::From(cursors))
+    }
+
+    pub fn try_stream_comments_by_claim_id_since<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        after: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(
+            claim_id,
+            page_size,
+            CommentPaging::Until(CommentStop::Since(after)),
+        )
+    }
+
+    pub fn try_stream_comments_by_claim_id_until<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        stop: CommentStop,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::Until(stop))
+    }
+
+    pub fn list_comments_by_claim_id_since<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        after: DateTime<Utc>,
+        page_size: usize,
+    ) -> impl Future<Output = Result<Vec<Comment>, ApiError>> + 'r {
+        self.try_stream_comments_by_claim_id_since(claim_id, page_size, after)
+            .try_collect()
+    }
+
+    pub fn stream_comments_by_claim_id<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Comment> + 'r {
+        skip_errors(self.try_stream_comments_by_claim_id(claim_id, page_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn list_should_send_json_rpc_request() {
+        use serde_json::json;
+
+        use super::Commentron;
+        use crate::{CommentSort, CommentVisibility};
+
+        let server = crate::testing::MockSdk::new()
+            .with_comment("claim", "a", "Hello")
+            .with_comment("claim", "b", "Nice")
+            .start();
+        let commentron = Commentron::new(server.url());
+
+        let comments = commentron
+            .list_comments_by_claim_id("claim", 1, 50)
+            .await
+            .expect("Unable to list comments");
+        assert_eq!(comments.total_items, 2);
+        assert!(comments
+            .items
+            .iter()
+            .all(|comment| comment.source == "odysee"));
+
+        commentron
+            .clone()
+            .with_comment_visibility(CommentVisibility::Hidden)
+            .list_comments_by_claim_id_sorted("claim", 2, 10, CommentSort::Top)
+            .await
+            .expect("Unable to list comments");
+        commentron
+            .with_comment_visibility(CommentVisibility::Visible)
+            .list_comments_by_claim_id("claim", 1, 10)
+            .await
+            .expect("Unable to list comments");
+
+        let payloads = server.payloads();
+        assert_eq!(
+            payloads[0],
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "comment.List",
+                "params": { "claim_id": "claim", "page": 1, "page_size": 50 },
+            })
+        );
+        assert_eq!(
+            payloads[1]["params"],
+            json!({
+                "claim_id": "claim",
+                "page": 2,
+                "page_size": 10,
+                "hidden": true,
+                "sort_by": 3,
+            })
+        );
+        assert_eq!(payloads[2]["params"].get("visible"), None);
+    }
+}
@@ -0,0 +1,340 @@
+use chrono::{DateTime, Utc};
+use futures::{
+    future::{self, FutureExt},
+    prelude::Future,
+    stream::{self, Stream, StreamExt},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use crate::{ApiError, Comment, CommentCursor, CommentSort, PaginatedApiResult};
+
+pub(crate) enum CommentPaging {
+    All,
+    From(CommentCursors),
+    Until(CommentStop),
+}
+
+impl CommentPaging {
+    pub(crate) fn sort(&self, sort: CommentSort) -> CommentSort {
+        match self {
+            Self::Until(_) => CommentSort::Newest,
+            Self::All | Self::From(_) => sort,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum CommentStop {
+    Since(DateTime<Utc>),
+    Known(HashSet<String>),
+}
+
+impl CommentStop {
+    pub(crate) fn is_reached(&self, comment: &Comment) -> bool {
+        match self {
+            Self::Since(after) => comment.timestamp <= *after,
+            Self::Known(known_ids) => known_ids.contains(&comment.id),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CommentCursors {
+    cursors: Arc<Mutex<HashMap<String, CommentCursor>>>,
+    updated: Arc<Mutex<HashSet<String>>>,
+}
+
+impl CommentCursors {
+    pub fn new(cursors: Vec<CommentCursor>) -> Self {
+        Self {
+            cursors: Arc::new(Mutex::new(
+                cursors
+                    .into_iter()
+                    .map(|cursor| (cursor.claim_id.clone(), cursor))
+                    .collect(),
+            )),
+            updated: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    pub fn start_page(&self, claim_id: &str) -> usize {
+        self.cursors
+            .lock()
+            .expect("Unable to get lock")
+            .get(claim_id)
+            .map(|cursor| cursor.next_page.max(1) as usize)
+            .unwrap_or(1)
+    }
+
+    pub fn advance(&self, claim_id: &str, page: usize, total_pages: usize) {
+        let mut cursors = self.cursors.lock().expect("Unable to get lock");
+        let cursor = cursors
+            .entry(claim_id.to_string())
+            .or_insert_with(|| CommentCursor {
+                claim_id: claim_id.to_string(),
+                next_page: 1,
+                total_pages: total_pages as i32,
+                updated_at: Utc::now().naive_utc(),
+            });
+
+        if cursor.next_page as usize != page {
+            return;
+        }
+
+        cursor.next_page = page as i32 + 1;
+        cursor.total_pages = total_pages as i32;
+        cursor.updated_at = Utc::now().naive_utc();
+
+        self.updated
+            .lock()
+            .expect("Unable to get lock")
+            .insert(claim_id.to_string());
+    }
+
+    pub fn take_updates(&self) -> Vec<CommentCursor> {
+        let mut cursors = self.cursors.lock().expect("Unable to get lock");
+
+        self.updated
+            .lock()
+            .expect("Unable to get lock")
+            .drain()
+            .filter_map(|claim_id| {
+                let cursor = cursors.get(&claim_id).cloned()?;
+
+                if cursor.is_complete() {
+                    cursors.remove(&claim_id);
+                }
+
+                Some(cursor)
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn try_stream_resumable<'r, F, Fut>(
+    page_concurrency: usize,
+    claim_id: String,
+    cursors: CommentCursors,
+    mut f: F,
+) -> impl Stream<Item = Result<Comment, ApiError>> + 'r
+where
+    F: FnMut(usize) -> Fut + 'r,
+    Fut: Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r,
+{
+    let start_page = cursors.start_page(&claim_id).max(2);
+
+    if start_page > 2 {
+        debug!(
+            "Resuming comments of claim {} at page {}",
+            &claim_id, start_page
+        );
+    }
+
+    let page_comments = move |page: usize, total_pages: usize, result| match result {
+        Ok(PaginatedApiResult { items, .. }) => {
+            let claim_id = claim_id.clone();
+            let cursors = cursors.clone();
+
+            stream::iter(items.into_iter().map(|comment| Some(Ok(comment))))
+                .chain(stream::once(future::lazy(move |_| {
+                    cursors.advance(&claim_id, page, total_pages);
+
+                    None
+                })))
+                .left_stream()
+        }
+        Err(err) => stream::iter(vec![Some(Err(err))]).right_stream(),
+    };
+
+    f(1).map(move |initial| match initial {
+        Ok(paginated) => {
+            let total_pages = paginated.total_pages;
+            let rest_stream = stream::iter(start_page..=total_pages)
+                .map(move |page| f(page).map(move |result| (page, result)))
+                .buffered(page_concurrency.max(1));
+
+            stream::once(future::ready((1, Ok(paginated))))
+                .chain(rest_stream)
+                .map(move |(page, result)| page_comments(page, total_pages, result))
+                .flatten()
+                .left_stream()
+        }
+        Err(err) => stream::iter(vec![Some(Err(err))]).right_stream(),
+    })
+    .flatten_stream()
+    .filter_map(future::ready)
+}
+
+pub(crate) fn try_stream_until<'r, F, Fut>(
+    stop: CommentStop,
+    f: F,
+) -> impl Stream<Item = Result<Comment, ApiError>> + 'r
+where
+    F: Fn(usize) -> Fut + 'r,
+    Fut: Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r,
+{
+    stream::unfold((f, stop, Some(1)), |(f, stop, page)| async move {
+        let page = page?;
+
+        let (items, next_page) = match f(page).await {
+            Ok(PaginatedApiResult {
+                items, total_pages, ..
+            }) => {
+                let is_caught_up =
+                    page >= total_pages || items.iter().any(|comment| stop.is_reached(comment));
+                let items = items
+                    .into_iter()
+                    .filter(|comment| !stop.is_reached(comment))
+                    .map(Ok)
+                    .collect::<Vec<_>>();
+
+                (items, if is_caught_up { None } else { Some(page + 1) })
+            }
+            Err(err) => (vec![Err(err)], None),
+        };
+
+        Some((stream::iter(items), (f, stop, next_page)))
+    })
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use futures::{future, stream::StreamExt};
+    use std::sync::{Arc, Mutex};
+
+    use super::{try_stream_resumable, try_stream_until, CommentCursors, CommentStop};
+    use crate::{testing, ApiError, Comment, CommentCursor, PaginatedApiResult};
+
+    fn comment(page: usize) -> Comment {
+        testing::comment(&format!("comment_{}", page), "claim_id")
+    }
+
+    fn fetch_pages(cursors: CommentCursors, failing_page: usize) -> (Vec<String>, Vec<usize>) {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let fetched_ref = fetched.clone();
+
+        let comments = futures::executor::block_on(
+            try_stream_resumable(2, "claim_id".to_string(), cursors, move |page| {
+                fetched_ref.lock().unwrap().push(page);
+
+                future::ready(if page == failing_page {
+                    Err(ApiError::InvalidResponse)
+                } else {
+                    Ok(PaginatedApiResult {
+                        items: vec![comment(page)],
+                        page,
+                        page_size: 1,
+                        total_items: 5,
+                        total_pages: 5,
+                    })
+                })
+            })
+            .filter_map(|result| future::ready(result.ok().map(|comment| comment.id)))
+            .collect::<Vec<String>>(),
+        );
+
+        let fetched = fetched.lock().unwrap().clone();
+
+        (comments, fetched)
+    }
+
+    #[test]
+    fn try_stream_resumable_should_resume_from_cursor() {
+        let cursors = CommentCursors::default();
+        let (comments, _) = fetch_pages(cursors.clone(), 3);
+
+        assert_eq!(comments.len(), 4);
+
+        let updates = cursors.take_updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].next_page, 3);
+        assert!(!updates[0].is_complete());
+
+        let cursors = CommentCursors::new(vec![CommentCursor {
+            claim_id: "claim_id".to_string(),
+            next_page: 3,
+            total_pages: 5,
+            updated_at: NaiveDate::from_ymd(2020, 8, 1).and_hms(0, 0, 0),
+        }]);
+        let (comments, fetched) = fetch_pages(cursors.clone(), 0);
+
+        assert_eq!(fetched, vec![1, 3, 4, 5]);
+        assert_eq!(
+            comments,
+            vec!["comment_1", "comment_3", "comment_4", "comment_5"]
+        );
+
+        let updates = cursors.take_updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].next_page, 6);
+        assert!(updates[0].is_complete());
+        assert_eq!(cursors.start_page("claim_id"), 1);
+    }
+
+    fn fetch_until(stop: CommentStop) -> (Vec<usize>, Vec<String>) {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let fetched_ref = fetched.clone();
+
+        let comments = futures::executor::block_on(
+            try_stream_until(stop, move |page| {
+                fetched_ref.lock().unwrap().push(page);
+
+                let items = (0..2)
+                    .map(|index| {
+                        let day = 10 - (page as u32 - 1) * 2 - index;
+
+                        Comment {
+                            id: format!("comment_{}", day),
+                            timestamp: Utc.ymd(2020, 8, day).and_hms(0, 0, 0),
+                            ..comment(page)
+                        }
+                    })
+                    .collect();
+
+                future::ready(Ok(PaginatedApiResult {
+                    items,
+                    page,
+                    page_size: 2,
+                    total_items: 8,
+                    total_pages: 4,
+                }))
+            })
+            .filter_map(|result| future::ready(result.ok().map(|comment| comment.id)))
+            .collect::<Vec<String>>(),
+        );
+
+        let fetched = fetched.lock().unwrap().clone();
+
+        (fetched, comments)
+    }
+
+    #[test]
+    fn try_stream_until_should_stop_at_older_comments() {
+        let (fetched, comments) =
+            fetch_until(CommentStop::Since(Utc.ymd(2020, 8, 7).and_hms(0, 0, 0)));
+
+        assert_eq!(fetched, vec![1, 2]);
+        assert_eq!(comments, vec!["comment_10", "comment_9", "comment_8"]);
+
+        let (fetched, comments) = fetch_until(CommentStop::Known(
+            vec!["comment_5".to_string()].into_iter().collect(),
+        ));
+
+        assert_eq!(fetched, vec![1, 2, 3]);
+        assert_eq!(
+            comments,
+            vec![
+                "comment_10",
+                "comment_9",
+                "comment_8",
+                "comment_7",
+                "comment_6"
+            ]
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use crate::{cluster::cluster_comments, ClaimCollections, CommentEntity};
+
+const UNCOLLECTED_HEADING: &str = "Other claims";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DigestOrder {
+    Chronological,
+    Claim,
+    Collection,
+    Tip,
+}
+
+impl FromStr for DigestOrder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "chronological" => Ok(Self::Chronological),
+            "claim" => Ok(Self::Claim),
+            "collection" => Ok(Self::Collection),
+            "tip" => Ok(Self::Tip),
+            _ => Err(format!("Unknown digest order {}", value)),
+        }
+    }
+}
+
+pub fn order_comments(mut comments: Vec<CommentEntity>, order: DigestOrder) -> Vec<CommentEntity> {
+    match order {
+        DigestOrder::Chronological => {
+            comments.sort_by_key(|comment| comment.timestamp);
+        }
+        DigestOrder::Claim | DigestOrder::Collection => {
+            comments.sort_by(|a, b| {
+                (&a.claim_name, &a.claim_id, a.timestamp).cmp(&(
+                    &b.claim_name,
+                    &b.claim_id,
+                    b.timestamp,
+                ))
+            });
+        }
+        DigestOrder::Tip => {
+            comments.sort_by(|a, b| {
+                b.support_amount
+                    .partial_cmp(&a.support_amount)
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.timestamp.cmp(&b.timestamp))
+            });
+        }
+    }
+
+    comments
+}
+
+fn render_comment(comment: &CommentEntity) -> String {
+    let tip = if comment.support_amount > 0.0 {
+        format!(" [{} LBC]", comment.support_amount)
+    } else {
+        "".to_string()
+    };
+
+    format!(
+        "
+      {} ({}){}
+      {} | {}
+      ===
+      {}
+",
+        comment.commenter_name,
+        comment.commenter_url,
+        tip,
+        comment.timestamp,
+        comment.id,
+        comment.comment
+    )
+}
+
+fn render_cluster(comments: &[&CommentEntity]) -> String {
+    let first = comments[0];
+
+    if comments.len() == 1 {
+        return render_comment(first);
+    }
+
+    let commenter_names = comments
+        .iter()
+        .map(|comment| comment.commenter_name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    format!(
+        "
+      {} similar comments from {}
+      {} | {}
+      ===
+      {}
+",
+        comments.len(),
+        commenter_names,
+        first.timestamp,
+        first.id,
+        first.comment
+    )
+}
+
+fn render_claim(claim_name: &str) -> String {
+    format!(
+        "
+      {}
+      ---
+",
+        claim_name
+    )
+}
+
+fn render_collection(collection: &str) -> String {
+    format!(
+        "
+      {}
+      ===
+",
+        collection
+    )
+}
+
+fn render_source(source: &str) -> String {
+    format!(
+        "
+      From {}
+      ===
+",
+        source
+    )
+}
+
+pub fn split_digest(
+    comments: &[CommentEntity],
+    order: DigestOrder,
+    max_bytes: usize,
+) -> Vec<String> {
+    split_clustered_digest(comments, order, max_bytes, None)
+}
+
+pub fn split_clustered_digest(
+    comments: &[CommentEntity],
+    order: DigestOrder,
+    max_bytes: usize,
+    similarity: Option<f64>,
+) -> Vec<String> {
+    split_collection_digest(
+        comments,
+        order,
+        max_bytes,
+        similarity,
+        &ClaimCollections::default(),
+    )
+}
+
+pub fn split_collection_digest(
+    comments: &[CommentEntity],
+    order: DigestOrder,
+    max_bytes: usize,
+    similarity: Option<f64>,
+    collections: &ClaimCollections,
+) -> Vec<String> {
+    let mut clusters = match similarity {
+        Some(threshold) => cluster_comments(comments, threshold),
+        None => comments.iter().map(|comment| vec![comment]).collect(),
+    };
+
+    let is_by_collection = order == DigestOrder::Collection
+        && comments
+            .iter()
+            .any(|comment| collections.get(&comment.claim_id).is_some());
+    let group_of = |comment: &CommentEntity| -> String {
+        if is_by_collection {
+            collections
+                .get(&comment.claim_id)
+                .unwrap_or(UNCOLLECTED_HEADING)
+                .to_string()
+        } else {
+            comment.source.clone()
+        }
+    };
+
+    let mut groups: Vec<String> = Vec::new();
+    for comment in comments {
+        let group = group_of(comment);
+
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+
+    if is_by_collection {
+        groups.sort_by_key(|group| (group == UNCOLLECTED_HEADING, group.clone()));
+    }
+
+    let is_grouped = is_by_collection || groups.len() > 1;
+    if is_grouped {
+        clusters.sort_by_key(|cluster| {
+            let group = group_of(cluster[0]);
+
+            groups
+                .iter()
+                .position(|other| *other == group)
+                .unwrap_or_default()
+        });
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut part = String::new();
+    let mut previous_claim_id: Option<&str> = None;
+    let mut previous_group: Option<String> = None;
+
+    for cluster in clusters {
+        let comment = cluster[0];
+        let group = group_of(comment);
+        let entry = render_cluster(&cluster);
+        let full_entry = format!("{}{}", render_claim(comment.claim_display_name()), entry);
+        let heading = if is_by_collection {
+            render_collection(&group)
+        } else if is_grouped {
+            render_source(&group)
+        } else {
+            "".to_string()
+        };
+
+        let is_new_group = is_grouped && previous_group.as_ref() != Some(&group);
+        let is_same_claim = previous_claim_id == Some(comment.claim_id.as_str());
+        let section = if is_new_group {
+            format!("{}{}", heading, full_entry)
+        } else if (order == DigestOrder::Claim || order == DigestOrder::Collection) && is_same_claim
+        {
+            entry
+        } else {
+            full_entry.clone()
+        };
+
+        if !part.is_empty() && part.len() + section.len() > max_bytes {
+            parts.push(std::mem::replace(
+                &mut part,
+                format!("{}{}", heading, full_entry),
+            ));
+        } else {
+            part.push_str(&section);
+        }
+
+        previous_claim_id = Some(comment.claim_id.as_str());
+        previous_group = Some(group);
+    }
+
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    parts
+}
+
+pub fn render_digest(comments: &[CommentEntity], order: DigestOrder) -> String {
+    split_digest(comments, order, usize::MAX).concat()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimSummary {
+    pub claim_id: String,
+    pub claim_name: String,
+    pub web_url: String,
+    pub comment_count: usize,
+}
+
+pub fn summarize_claims(comments: &[CommentEntity]) -> Vec<ClaimSummary> {
+    let mut summaries: Vec<ClaimSummary> = Vec::new();
+
+    for comment in comments {
+        match summaries
+            .iter_mut()
+            .find(|summary| summary.claim_id == comment.claim_id)
+        {
+            Some(summary) => summary.comment_count += 1,
+            None => summaries.push(ClaimSummary {
+                claim_id: comment.claim_id.clone(),
+                claim_name: comment.claim_display_name().to_string(),
+                web_url: comment.claim_web_url(),
+                comment_count: 1,
+            }),
+        }
+    }
+
+    summaries.sort_by(|a, b| {
+        b.comment_count
+            .cmp(&a.comment_count)
+            .then(a.claim_name.cmp(&b.claim_name))
+    });
+
+    summaries
+}
+
+pub fn render_claim_summaries(summaries: &[ClaimSummary]) -> String {
+    summaries
+        .iter()
+        .map(|summary| {
+            format!(
+                "
+      {} ({})
+      {}
+",
+                summary.claim_name, summary.comment_count, summary.web_url
+            )
+        })
+        .collect::<String>()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use serde_json::json;
+
+    use super::{
+        order_comments, render_claim_summaries, render_digest, split_clustered_digest,
+        split_collection_digest, split_digest, summarize_claims, DigestOrder,
+    };
+    use crate::{testing, ClaimCollections, Collection, CommentEntity};
+
+    fn comment(id: &str, claim_name: &str, hour: u32, support_amount: f64) -> CommentEntity {
+        CommentEntity {
+            claim_name: claim_name.to_string(),
+            commenter_name: "commenter_name".to_string(),
+            commenter_url: "commenter_url".to_string(),
+            comment: format!("comment {}", id),
+            timestamp: NaiveDate::from_ymd(2020, 5, 1).and_hms(hour, 0, 0),
+            support_amount,
+            ..testing::comment_entity(id, &format!("{}_id", claim_name))
+        }
+    }
+
+    fn ids(comments: &[CommentEntity]) -> Vec<&str> {
+        comments
+            .iter()
+            .map(|comment| comment.id.as_str())
+            .collect::<Vec<&str>>()
+    }
+
+    #[test]
+    fn order_comments_should_work() {
+        let comments = vec![
+            comment("a", "second", 1, 0.0),
+            comment("b", "first", 2, 5.0),
+            comment("c", "second", 3, 10.0),
+            comment("d", "first", 0, 0.0),
+        ];
+
+        let chronological = order_comments(comments.clone(), DigestOrder::Chronological);
+        assert_eq!(ids(&chronological), vec!["d", "a", "b", "c"]);
+
+        let claim = order_comments(comments.clone(), DigestOrder::Claim);
+        assert_eq!(ids(&claim), vec!["d", "b", "a", "c"]);
+
+        let tip = order_comments(comments, DigestOrder::Tip);
+        assert_eq!(ids(&tip), vec!["c", "b", "d", "a"]);
+    }
+
+    #[test]
+    fn render_digest_should_group_claims() {
+        let comments = order_comments(
+            vec![
+                comment("a", "second", 1, 0.0),
+                comment("b", "second", 2, 0.0),
+            ],
+            DigestOrder::Claim,
+        );
+
+        let grouped = render_digest(&comments, DigestOrder::Claim);
+        let flat = render_digest(&comments, DigestOrder::Chronological);
+
+        assert_eq!(grouped.matches("second").count(), 1);
+        assert_eq!(flat.matches("second").count(), 2);
+    }
+
+    #[test]
+    fn split_digest_should_respect_max_bytes() {
+        let comments = order_comments(
+            vec![
+                comment("a", "first", 1, 0.0),
+                comment("b", "first", 2, 0.0),
+                comment("c", "second", 3, 0.0),
+            ],
+            DigestOrder::Claim,
+        );
+
+        let whole = render_digest(&comments, DigestOrder::Claim);
+        assert_eq!(
+            split_digest(&comments, DigestOrder::Claim, whole.len()),
+            vec![whole.clone()]
+        );
+
+        let parts = split_digest(&comments, DigestOrder::Claim, whole.len() / 2);
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|part| part.contains("---")));
+
+        assert_eq!(split_digest(&comments, DigestOrder::Claim, 1).len(), 3);
+        assert!(split_digest(&[], DigestOrder::Claim, 1).is_empty());
+    }
+
+    #[test]
+    fn render_digest_should_section_sources() {
+        let mut comments = vec![
+            comment("a", "first", 1, 0.0),
+            comment("b", "second", 2, 0.0),
+            comment("c", "third", 3, 0.0),
+        ];
+        comments[1].source = "odysee".to_string();
+
+        let digest = render_digest(&comments, DigestOrder::Chronological);
+        let lbry = digest.find("From lbry").expect("Missing lbry section");
+        let odysee = digest.find("From odysee").expect("Missing odysee section");
+
+        assert!(lbry < digest.find("comment c").unwrap_or_default());
+        assert!(digest.find("comment c").unwrap_or_default() < odysee);
+
+        assert!(!render_digest(&comments[..1], DigestOrder::Chronological).contains("From"));
+    }
+
+    #[test]
+    fn split_clustered_digest_should_group_similar_comments() {
+        let mut comments = vec![
+            comment("a", "first", 1, 0.0),
+            comment("b", "first", 2, 0.0),
+            comment("c", "first", 3, 0.0),
+        ];
+        comments[2].comment = "something else entirely".to_string();
+
+        let parts =
+            split_clustered_digest(&comments, DigestOrder::Chronological, usize::MAX, Some(0.5));
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].contains("2 similar comments from commenter_name, commenter_name"));
+        assert!(parts[0].contains("something else entirely"));
+
+        assert_eq!(
+            split_clustered_digest(&comments, DigestOrder::Chronological, usize::MAX, None),
+            split_digest(&comments, DigestOrder::Chronological, usize::MAX)
+        );
+    }
+
+    #[test]
+    fn split_collection_digest_should_section_collections() {
+        let comments = order_comments(
+            vec![
+                comment("a", "trailer", 1, 0.0),
+                comment("b", "episode_2", 2, 0.0),
+                comment("c", "episode_1", 3, 0.0),
+                comment("d", "episode_2", 4, 0.0),
+            ],
+            DigestOrder::Collection,
+        );
+        let collections = ClaimCollections::new(
+            &serde_json::from_value::<Vec<Collection>>(json!([{
+                "claim_id": "season_1",
+                "name": "season-1",
+                "value": { "title": "Season 1", "claims": ["episode_1_id", "episode_2_id"] },
+            }]))
+            .expect("Invalid collections"),
+        );
+
+        let digest = split_collection_digest(
+            &comments,
+            DigestOrder::Collection,
+            usize::MAX,
+            None,
+            &collections,
+        )
+        .concat();
+        let season = digest.find("Season 1").expect("Missing collection section");
+        let other = digest.find("Other claims").expect("Missing other section");
+
+        assert!(season < digest.find("comment c").unwrap_or_default());
+        assert!(digest.find("comment d").unwrap_or_default() < other);
+        assert!(other < digest.find("comment a").unwrap_or_default());
+        assert_eq!(digest.matches("episode_2").count(), 1);
+
+        assert_eq!(
+            split_collection_digest(
+                &comments,
+                DigestOrder::Collection,
+                usize::MAX,
+                None,
+                &ClaimCollections::default(),
+            ),
+            split_digest(&comments, DigestOrder::Claim, usize::MAX)
+        );
+    }
+
+    #[test]
+    fn summarize_claims_should_count_comments_per_claim() {
+        let comments = vec![
+            comment("a", "first", 1, 0.0),
+            comment("b", "second", 2, 0.0),
+            comment("c", "second", 3, 0.0),
+        ];
+
+        let summaries = summarize_claims(&comments);
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (summary.claim_name.as_str(), summary.comment_count))
+                .collect::<Vec<(&str, usize)>>(),
+            vec![("second", 2), ("first", 1)]
+        );
+
+        let rendered = render_claim_summaries(&summaries);
+        assert!(
+            rendered.find("second (2)").unwrap_or_default()
+                < rendered.find("first (1)").unwrap_or_default()
+        );
+        assert!(rendered.contains(&summaries[0].web_url));
+        assert!(summarize_claims(&[]).is_empty());
+    }
+}
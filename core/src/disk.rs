@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct DiskGuard {
+    paths: Vec<PathBuf>,
+    min_free_mb: u64,
+}
+
+fn directory_of(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        return path.to_path_buf();
+    }
+
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+impl DiskGuard {
+    pub fn new(paths: Vec<PathBuf>, min_free_mb: u64) -> Self {
+        Self { paths, min_free_mb }
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        self.paths
+            .iter()
+            .filter_map(|path| self.check(path, fs2::available_space(directory_of(path))))
+            .collect()
+    }
+
+    fn check(&self, path: &Path, available: std::io::Result<u64>) -> Option<String> {
+        match available {
+            Ok(available) if available < self.min_free_mb * BYTES_PER_MB => Some(format!(
+                "Only {} MB free for {}, below {} MB",
+                available / BYTES_PER_MB,
+                path.display(),
+                self.min_free_mb
+            )),
+            Ok(_) => None,
+            Err(err) => Some(format!(
+                "Unable to check free space for {}: {}",
+                path.display(),
+                err
+            )),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.problems().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, path::PathBuf};
+
+    use super::{directory_of, DiskGuard};
+
+    #[test]
+    fn check_should_report_low_disk_space() {
+        let guard = DiskGuard::new(vec![PathBuf::from("data.db")], 100);
+        let path = PathBuf::from("data.db");
+
+        assert_eq!(guard.check(&path, Ok(200 * 1024 * 1024)), None);
+        assert_eq!(
+            guard.check(&path, Ok(50 * 1024 * 1024)),
+            Some("Only 50 MB free for data.db, below 100 MB".to_string())
+        );
+        assert!(guard
+            .check(&path, Err(io::Error::from(io::ErrorKind::NotFound)))
+            .is_some());
+
+        assert_eq!(directory_of(&path), PathBuf::from("."));
+        assert_eq!(
+            directory_of(&PathBuf::from("/var/lib/notifier/data.db")),
+            PathBuf::from("/var/lib/notifier")
+        );
+        assert!(DiskGuard::new(vec![PathBuf::from(".")], 0).is_ok());
+    }
+}
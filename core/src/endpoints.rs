@@ -0,0 +1,131 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::ApiError;
+
+const DEFAULT_FAILOVER_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    urls: Vec<String>,
+    cooldown: Duration,
+    failed_until: Arc<Mutex<Vec<Option<Instant>>>>,
+}
+
+impl Endpoints {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            failed_until: Arc::new(Mutex::new(vec![None; urls.len()])),
+            urls,
+            cooldown: DEFAULT_FAILOVER_COOLDOWN,
+        }
+    }
+
+    pub fn with_cooldown(self, cooldown: Duration) -> Self {
+        Self { cooldown, ..self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub(crate) fn current(&self) -> (usize, &str) {
+        self.current_at(Instant::now())
+    }
+
+    fn current_at(&self, now: Instant) -> (usize, &str) {
+        let failed_until = self.failed_until.lock().expect("Unable to get lock");
+
+        let index = failed_until
+            .iter()
+            .position(|until| until.map(|until| until <= now).unwrap_or(true))
+            .or_else(|| {
+                failed_until
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, until)| *until)
+                    .map(|(index, _)| index)
+            })
+            .unwrap_or(0);
+
+        (index, &self.urls[index])
+    }
+
+    pub(crate) fn mark_failed(&self, index: usize, err: &ApiError) {
+        self.mark_failed_at(index, err, Instant::now())
+    }
+
+    fn mark_failed_at(&self, index: usize, err: &ApiError, now: Instant) {
+        let mut failed_until = self.failed_until.lock().expect("Unable to get lock");
+
+        if self.urls.len() > 1 && failed_until[index].is_none() {
+            warn!(
+                "SDK at {} failed, failing over for {:?}: {}",
+                &self.urls[index], self.cooldown, err
+            );
+        }
+
+        failed_until[index] = Some(now + self.cooldown);
+    }
+
+    pub(crate) fn mark_ok(&self, index: usize) {
+        let mut failed_until = self.failed_until.lock().expect("Unable to get lock");
+
+        if failed_until[index].take().is_some() && self.urls.len() > 1 {
+            info!("SDK at {} recovered", &self.urls[index]);
+        }
+    }
+}
+
+pub(crate) fn is_unavailable(err: &ApiError) -> bool {
+    match err {
+        ApiError::NetworkError(reqwest_error) => reqwest_error
+            .status()
+            .map(|status| status.is_server_error())
+            .unwrap_or(true),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Endpoints;
+    use crate::ApiError;
+
+    #[test]
+    fn current_should_fail_over_and_recover() {
+        let endpoints = Endpoints::new(vec![
+            "http://primary:5279".to_string(),
+            "http://replica:5279".to_string(),
+        ])
+        .with_cooldown(Duration::from_secs(30));
+        let now = Instant::now();
+
+        assert_eq!(endpoints.current_at(now), (0, "http://primary:5279"));
+
+        endpoints.mark_failed_at(0, &ApiError::InvalidResponse, now);
+        assert_eq!(endpoints.current_at(now), (1, "http://replica:5279"));
+
+        endpoints.mark_failed_at(1, &ApiError::InvalidResponse, now + Duration::from_secs(5));
+        assert_eq!(endpoints.current_at(now + Duration::from_secs(10)).0, 0);
+
+        endpoints.mark_failed_at(0, &ApiError::InvalidResponse, now + Duration::from_secs(10));
+        assert_eq!(endpoints.current_at(now + Duration::from_secs(20)).0, 1);
+        assert_eq!(endpoints.current_at(now + Duration::from_secs(40)).0, 0);
+
+        endpoints.mark_ok(1);
+        assert_eq!(endpoints.current_at(now + Duration::from_secs(20)).0, 1);
+    }
+}
@@ -0,0 +1,255 @@
+use futures::{
+    future,
+    sink::SinkExt,
+    stream::{self, Stream, StreamExt},
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as EventError, Message},
+};
+
+use crate::{Account, Claim, Comment, CommentStop, LbryApi};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdkEvent {
+    pub module: String,
+    pub name: String,
+    pub data: Value,
+}
+
+impl SdkEvent {
+    pub fn parse(text: &str) -> Option<Self> {
+        let value = serde_json::from_str::<Value>(text).ok()?;
+        let field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| value.get(*key).and_then(|field| field.as_str()))
+                .map(String::from)
+        };
+
+        Some(Self {
+            module: field(&["module", "component"])?,
+            name: field(&["event", "name", "stream"])?,
+            data: ["payload", "data"]
+                .iter()
+                .find_map(|key| value.get(*key))
+                .cloned()
+                .unwrap_or(Value::Null),
+        })
+    }
+
+    pub fn claim_ids(&self) -> Vec<String> {
+        fn collect(value: &Value, claim_ids: &mut Vec<String>) {
+            match value {
+                Value::Object(fields) => {
+                    fields
+                        .iter()
+                        .for_each(|(key, field)| match (key.as_str(), field.as_str()) {
+                            ("claim_id", Some(claim_id)) => {
+                                if !claim_ids.iter().any(|id| id == claim_id) {
+                                    claim_ids.push(claim_id.to_string());
+                                }
+                            }
+                            _ => collect(field, claim_ids),
+                        })
+                }
+                Value::Array(items) => items.iter().for_each(|item| collect(item, claim_ids)),
+                _ => {}
+            }
+        }
+
+        let mut claim_ids = Vec::new();
+        collect(&self.data, &mut claim_ids);
+
+        claim_ids
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EventSubscription {
+    url: String,
+    events: Vec<String>,
+}
+
+impl EventSubscription {
+    pub fn new(url: String, events: Vec<String>) -> Self {
+        Self { url, events }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn subscribe_message(&self) -> Message {
+        let mut modules = Map::new();
+
+        for event in &self.events {
+            let (module, name) = match event.find('.') {
+                Some(index) => (&event[..index], &event[index + 1..]),
+                None => (event.as_str(), ""),
+            };
+            let names = modules
+                .entry(module.to_string())
+                .or_insert_with(|| json!([]));
+
+            if let (Some(names), false) = (names.as_array_mut(), name.is_empty()) {
+                names.push(json!(name));
+            }
+        }
+
+        Message::Text(
+            json!({
+                "action": "subscribe",
+                "data": modules,
+            })
+            .to_string(),
+        )
+    }
+
+    pub async fn connect(&self) -> Result<impl Stream<Item = SdkEvent>, EventError> {
+        let (mut socket, _) = connect_async(self.url.as_str()).await?;
+
+        socket.send(self.subscribe_message()).await?;
+
+        info!("Subscribed to {} events", &self.url);
+
+        Ok(socket
+            .take_while(|message| {
+                if let Err(err) = message {
+                    error!("Event subscription failed: {}", err);
+                }
+
+                future::ready(message.is_ok())
+            })
+            .filter_map(|message| {
+                future::ready(match message {
+                    Ok(Message::Text(text)) => SdkEvent::parse(&text),
+                    _ => None,
+                })
+            }))
+    }
+}
+
+pub fn stream_event_comments<'a, A: LbryApi, S>(
+    api: &'a A,
+    events: impl Stream<Item = SdkEvent> + 'a,
+    page_size: usize,
+    comment_stops: S,
+) -> impl Stream<Item = (Account, Claim, Comment)> + 'a
+where
+    S: Fn() -> HashMap<String, CommentStop> + 'a,
+{
+    events
+        .map(move |event| {
+            let claim_ids = event.claim_ids();
+            let stops = comment_stops();
+
+            debug!(
+                "Received {}.{} event for {} claims",
+                &event.module,
+                &event.name,
+                claim_ids.len()
+            );
+
+            api.stream_accounts(page_size)
+                .map(move |account| {
+                    api.stream_claims_by_account_id(account.id.clone(), page_size)
+                        .zip(stream::repeat(account))
+                })
+                .flatten()
+                .filter(move |(claim, _)| {
+                    future::ready(claim_ids.is_empty() || claim_ids.contains(&claim.id))
+                })
+                .map(move |(claim, account)| {
+                    let comments = match stops.get(&claim.id) {
+                        Some(stop) => api
+                            .try_stream_comments_by_claim_id_until(
+                                claim.id.clone(),
+                                page_size,
+                                stop.clone(),
+                            )
+                            .left_stream(),
+                        None => api
+                            .stream_comments_by_claim_id(claim.id.clone(), page_size)
+                            .map(Ok)
+                            .right_stream(),
+                    };
+
+                    comments
+                        .filter_map(|res| {
+                            future::ready(
+                                res.map_err(|err| {
+                                    error!("Unable to fetch event comments: {}", err)
+                                })
+                                .ok(),
+                            )
+                        })
+                        .zip(stream::repeat((account, claim)))
+                        .map(|(comment, (account, claim))| (account, claim, comment))
+                })
+                .flatten()
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::{self, StreamExt};
+    use std::collections::HashMap;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use super::{stream_event_comments, EventSubscription, SdkEvent};
+    use crate::{
+        testing::{self, claim, comment, FakeApi},
+        CommentStop,
+    };
+
+    #[test]
+    fn stream_event_comments_should_scan_event_claims() {
+        let subscription = EventSubscription::new(
+            "ws://localhost:5279/ws".to_string(),
+            vec!["blockchain.block".to_string(), "wallet".to_string()],
+        );
+        assert_eq!(
+            subscription.subscribe_message(),
+            Message::Text(
+                r#"{"action":"subscribe","data":{"blockchain":["block"],"wallet":[]}}"#.to_string()
+            )
+        );
+
+        let block = SdkEvent::parse(r#"{"module":"blockchain","event":"block","payload":1}"#)
+            .expect("Invalid event");
+        let claimed = SdkEvent::parse(
+            r#"{"module":"wallet","name":"tx","data":{"outputs":[{"claim_id":"claim_b"}]}}"#,
+        )
+        .expect("Invalid event");
+        assert!(block.claim_ids().is_empty());
+        assert_eq!(claimed.claim_ids(), vec!["claim_b"]);
+        assert!(SdkEvent::parse("not an event").is_none());
+
+        let api = FakeApi::new()
+            .with_account(testing::account("account"))
+            .with_claim("account", claim("claim_a"))
+            .with_claim("account", claim("claim_b"))
+            .with_comment(comment("comment_new", "claim_a"))
+            .with_comment(comment("comment_a", "claim_a"))
+            .with_comment(comment("comment_b", "claim_b"));
+        let comment_stops = || {
+            vec![(
+                "claim_a".to_string(),
+                CommentStop::Known(vec!["comment_a".to_string()].into_iter().collect()),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, CommentStop>>()
+        };
+
+        let comment_ids = futures::executor::block_on(
+            stream_event_comments(&api, stream::iter(vec![claimed, block]), 10, comment_stops)
+                .map(|(_, _, comment)| comment.id)
+                .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(comment_ids, vec!["comment_b", "comment_new", "comment_b"]);
+    }
+}
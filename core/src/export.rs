@@ -0,0 +1,293 @@
+use chrono::NaiveDateTime;
+#[cfg(feature = "schemas")]
+use schemars::{schema::RootSchema, schema_for};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::OpenOptions,
+    io::{self, Write},
+};
+
+use crate::CommentEntity;
+#[cfg(feature = "schemas")]
+use crate::{WebhookPayloadV1, WebhookPayloadV2};
+
+#[derive(Clone, Debug)]
+pub struct Exporter {
+    path: String,
+}
+
+impl Exporter {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+
+    pub fn append_comment(&self, comment: &CommentEntity) -> io::Result<()> {
+        let mut line = serde_json::to_string(comment)?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+#[cfg(feature = "schemas")]
+pub fn export_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("comment", schema_for!(CommentEntity)),
+        ("webhook-v1", schema_for!(WebhookPayloadV1)),
+        ("webhook-v2", schema_for!(WebhookPayloadV2)),
+    ]
+}
+
+fn render_markdown_comment(
+    comment: &CommentEntity,
+    replies: &HashMap<&str, Vec<&CommentEntity>>,
+    depth: usize,
+) -> String {
+    let indent = "  ".repeat(depth);
+    let text = comment
+        .comment
+        .lines()
+        .collect::<Vec<&str>>()
+        .join(&format!("\n{}  ", indent));
+
+    let rendered_replies = replies
+        .get(comment.id.as_str())
+        .map(|children| {
+            children
+                .iter()
+                .map(|child| render_markdown_comment(child, replies, depth + 1))
+                .collect::<Vec<String>>()
+                .concat()
+        })
+        .unwrap_or_default();
+
+    format!(
+        "{}- **[{}]({})** _{}_\n{}  {}\n{}",
+        indent,
+        comment.commenter_name,
+        comment.commenter_url,
+        comment.timestamp,
+        indent,
+        text,
+        rendered_replies
+    )
+}
+
+pub fn render_markdown_thread(claim_name: &str, comments: &[CommentEntity]) -> String {
+    let mut ordered_comments = comments.iter().collect::<Vec<&CommentEntity>>();
+    ordered_comments.sort_by_key(|comment| comment.timestamp);
+
+    let ids = ordered_comments
+        .iter()
+        .map(|comment| comment.id.as_str())
+        .collect::<HashSet<&str>>();
+
+    let mut roots = Vec::new();
+    let mut replies: HashMap<&str, Vec<&CommentEntity>> = HashMap::new();
+
+    for comment in ordered_comments {
+        match comment.parent_id.as_deref() {
+            Some(parent_id) if ids.contains(parent_id) => {
+                replies.entry(parent_id).or_default().push(comment);
+            }
+            _ => roots.push(comment),
+        }
+    }
+
+    let thread = roots
+        .iter()
+        .map(|comment| render_markdown_comment(comment, &replies, 0))
+        .collect::<Vec<String>>()
+        .concat();
+
+    format!("# {}\n\n{}", claim_name, thread)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CommenterContact {
+    pub commenter_id: String,
+    pub commenter_name: String,
+    pub commenter_url: String,
+    pub first_seen: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    pub comment_count: usize,
+}
+
+pub fn commenter_contacts(comments: &[CommentEntity]) -> Vec<CommenterContact> {
+    let mut contacts: HashMap<&str, CommenterContact> = HashMap::new();
+
+    for comment in comments {
+        let contact = contacts
+            .entry(comment.commenter_id.as_str())
+            .or_insert_with(|| CommenterContact {
+                commenter_id: comment.commenter_id.clone(),
+                commenter_name: comment.commenter_name.clone(),
+                commenter_url: comment.commenter_url.clone(),
+                first_seen: comment.timestamp,
+                last_seen: comment.timestamp,
+                comment_count: 0,
+            });
+
+        if comment.timestamp >= contact.last_seen {
+            contact.commenter_name = comment.commenter_name.clone();
+            contact.commenter_url = comment.commenter_url.clone();
+            contact.last_seen = comment.timestamp;
+        }
+
+        contact.first_seen = contact.first_seen.min(comment.timestamp);
+        contact.comment_count += 1;
+    }
+
+    let mut contacts = contacts.into_values().collect::<Vec<_>>();
+    contacts.sort_by(|a, b| {
+        b.comment_count
+            .cmp(&a.comment_count)
+            .then_with(|| a.commenter_name.cmp(&b.commenter_name))
+    });
+
+    contacts
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render_commenters_csv(contacts: &[CommenterContact]) -> String {
+    let rows = contacts.iter().map(|contact| {
+        [
+            csv_field(&contact.commenter_id),
+            csv_field(&contact.commenter_name),
+            csv_field(&contact.commenter_url),
+            contact.first_seen.to_string(),
+            contact.last_seen.to_string(),
+            contact.comment_count.to_string(),
+        ]
+        .join(",")
+    });
+
+    std::iter::once(
+        "commenter_id,commenter_name,commenter_url,first_seen,last_seen,comment_count".to_string(),
+    )
+    .chain(rows)
+    .map(|row| format!("{}\n", row))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    #[cfg(feature = "schemas")]
+    use super::export_schemas;
+    use super::{commenter_contacts, render_commenters_csv, render_markdown_thread};
+    use crate::{parse_csv, testing, CommentEntity};
+
+    #[test]
+    #[cfg(feature = "schemas")]
+    fn export_schemas_should_match_artifacts() {
+        let artifacts = vec![
+            ("comment", include_str!("../../schemas/comment.schema.json")),
+            (
+                "webhook-v1",
+                include_str!("../../schemas/webhook-v1.schema.json"),
+            ),
+            (
+                "webhook-v2",
+                include_str!("../../schemas/webhook-v2.schema.json"),
+            ),
+        ];
+
+        export_schemas().into_iter().zip(artifacts).for_each(
+            |((name, schema), (artifact_name, artifact))| {
+                assert_eq!(name, artifact_name);
+                assert_eq!(
+                    serde_json::to_value(schema).expect("Unable to serialize schema"),
+                    serde_json::from_str::<serde_json::Value>(artifact)
+                        .expect("Unable to parse artifact"),
+                    "schemas/{}.schema.json is outdated",
+                    name
+                );
+            },
+        );
+    }
+
+    fn comment(id: &str, parent_id: Option<&str>, minute: u32, text: &str) -> CommentEntity {
+        CommentEntity {
+            commenter_name: format!("@{}", id),
+            commenter_url: format!("lbry://@{}", id),
+            comment: text.to_string(),
+            timestamp: NaiveDate::from_ymd(2020, 5, 1).and_hms(0, minute, 0),
+            parent_id: parent_id.map(String::from),
+            ..testing::comment_entity(id, "claim_id")
+        }
+    }
+
+    #[test]
+    fn render_markdown_thread_should_nest_replies() {
+        let comments = vec![
+            comment("reply", Some("first"), 2, "Thanks"),
+            comment("second", None, 1, "Multi\nline"),
+            comment("first", None, 0, "Hello"),
+            comment("orphan", Some("missing"), 3, "Hi"),
+        ];
+
+        assert_eq!(
+            render_markdown_thread("My Video", &comments),
+            "# My Video
+
+- **[@first](lbry://@first)** _2020-05-01 00:00:00_
+  Hello
+  - **[@reply](lbry://@reply)** _2020-05-01 00:02:00_
+    Thanks
+- **[@second](lbry://@second)** _2020-05-01 00:01:00_
+  Multi
+  line
+- **[@orphan](lbry://@orphan)** _2020-05-01 00:03:00_
+  Hi
+"
+        );
+    }
+
+    #[test]
+    fn commenter_contacts_should_aggregate_commenters() {
+        let mut renamed = comment("b", None, 5, "Again");
+        renamed.commenter_id = "a_id".to_string();
+        renamed.commenter_name = "@a, renamed".to_string();
+
+        let mut first = comment("a", None, 1, "Hello");
+        first.commenter_id = "a_id".to_string();
+
+        let mut other = comment("c", None, 3, "Hi");
+        other.commenter_id = "c_id".to_string();
+
+        let contacts = commenter_contacts(&[renamed, other, first]);
+
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].commenter_name, "@a, renamed");
+        assert_eq!(contacts[0].comment_count, 2);
+        assert_eq!(
+            contacts[0].first_seen,
+            NaiveDate::from_ymd(2020, 5, 1).and_hms(0, 1, 0)
+        );
+        assert_eq!(
+            contacts[0].last_seen,
+            NaiveDate::from_ymd(2020, 5, 1).and_hms(0, 5, 0)
+        );
+        assert_eq!(contacts[1].commenter_id, "c_id");
+
+        let rows = parse_csv(&render_commenters_csv(&contacts));
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][5], "comment_count");
+        assert_eq!(rows[1][1], "@a, renamed");
+        assert_eq!(rows[1][5], "2");
+    }
+}
@@ -0,0 +1,329 @@
+use chrono::{DateTime, Utc};
+use futures::{future::TryFutureExt, prelude::Future};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::{Account, ApiError, Claim, ClaimMetadata, LbryUrl};
+
+const CLAIM_ID_LENGTH: usize = 40;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedItem {
+    pub url: LbryUrl,
+    pub title: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl FeedItem {
+    fn new(urls: Vec<String>, title: Option<String>, date: Option<String>) -> Option<Self> {
+        let url = urls
+            .iter()
+            .filter_map(|url| url.parse::<LbryUrl>().ok())
+            .filter(|url| url.stream.is_some())
+            .max_by_key(|url| url.claim_id().map(str::len).unwrap_or_default())?;
+        let timestamp = date.as_deref().and_then(parse_date)?;
+
+        Some(Self {
+            url,
+            title: title.filter(|title| !title.is_empty()),
+            timestamp,
+        })
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.url
+            .claim_id()
+            .map(|claim_id| claim_id.len() == CLAIM_ID_LENGTH)
+            .unwrap_or(false)
+    }
+
+    pub fn account(&self) -> Account {
+        match &self.url.channel {
+            Some(channel) => Account {
+                id: channel
+                    .claim_id
+                    .clone()
+                    .unwrap_or_else(|| channel.name.clone()),
+                name: channel.name.clone(),
+                is_default: false,
+            },
+            None => Account {
+                id: "feed".to_string(),
+                name: "feed".to_string(),
+                is_default: false,
+            },
+        }
+    }
+
+    pub fn claim(&self) -> Option<Claim> {
+        if !self.is_complete() {
+            return None;
+        }
+
+        let stream = self.url.stream.as_ref()?;
+
+        Some(Claim {
+            id: stream.claim_id.clone()?,
+            name: stream.name.clone(),
+            timestamp: self.timestamp,
+            metadata: ClaimMetadata {
+                title: self.title.clone(),
+                ..ClaimMetadata::default()
+            },
+        })
+    }
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_rfc2822(value))
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok()
+}
+
+fn unescape(value: &str) -> String {
+    let value = value.trim();
+
+    match value
+        .strip_prefix("<![CDATA[")
+        .and_then(|data| data.strip_suffix("]]>"))
+    {
+        Some(data) => data.trim().to_string(),
+        None => value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&"),
+    }
+}
+
+fn elements<'a>(text: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut elements = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+
+        if !after.starts_with(|ch: char| ch == '>' || ch == '/' || ch.is_whitespace()) {
+            rest = after;
+            continue;
+        }
+
+        let end = match after.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let attributes = &after[..end];
+
+        if attributes.ends_with('/') {
+            elements.push((attributes.trim_end_matches('/'), ""));
+            rest = &after[end + 1..];
+            continue;
+        }
+
+        let body = &after[end + 1..];
+
+        match body.find(&close) {
+            Some(close_start) => {
+                elements.push((attributes, &body[..close_start]));
+                rest = &body[close_start + close.len()..];
+            }
+            None => break,
+        }
+    }
+
+    elements
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    ['"', '\''].iter().find_map(|quote| {
+        let key = format!("{}={}", name, quote);
+        let start = attributes.find(&key)? + key.len();
+        let end = attributes[start..].find(*quote)?;
+
+        Some(unescape(&attributes[start..start + end]))
+    })
+}
+
+fn element_text(text: &str, tags: &[&str]) -> Option<String> {
+    tags.iter()
+        .find_map(|tag| elements(text, tag).into_iter().next())
+        .map(|(_, body)| unescape(body))
+}
+
+fn parse_xml_feed(text: &str) -> Vec<FeedItem> {
+    let items = elements(text, "item");
+    let entries = if items.is_empty() {
+        elements(text, "entry")
+    } else {
+        items
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|(_, body)| {
+            let mut urls = elements(body, "link")
+                .into_iter()
+                .map(|(attributes, link)| attribute(attributes, "href").unwrap_or(unescape(link)))
+                .collect::<Vec<String>>();
+            urls.extend(element_text(body, &["guid", "id"]));
+
+            FeedItem::new(
+                urls,
+                element_text(body, &["title"]),
+                element_text(body, &["pubDate", "published", "updated", "dc:date"]),
+            )
+        })
+        .collect()
+}
+
+fn parse_json_feed(feed: Value) -> Vec<FeedItem> {
+    feed.get("items")
+        .and_then(|items| items.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let text = |key: &str| item.get(key).and_then(|value| value.as_str());
+
+                    FeedItem::new(
+                        vec![text("url"), text("external_url"), text("id")]
+                            .into_iter()
+                            .flatten()
+                            .map(String::from)
+                            .collect(),
+                        text("title").map(String::from),
+                        text("date_published")
+                            .or(text("date_modified"))
+                            .map(String::from),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn parse_feed(text: &str) -> Result<Vec<FeedItem>, String> {
+    let text = text.trim_start_matches('\u{feff}').trim();
+
+    if text.starts_with('{') {
+        return serde_json::from_str::<Value>(text)
+            .map(parse_json_feed)
+            .map_err(|err| format!("Invalid JSON feed: {}", err));
+    }
+
+    if text.starts_with('<') {
+        return Ok(parse_xml_feed(text));
+    }
+
+    Err("Unknown feed format".to_string())
+}
+
+#[derive(Clone, Debug)]
+pub struct ClaimFeeds {
+    client: Client,
+    urls: Vec<String>,
+}
+
+impl ClaimFeeds {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            client: Client::new(),
+            urls,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub fn fetch<'a, 'b, 'r: 'a>(
+        &'a self,
+        url: &'b str,
+    ) -> impl Future<Output = Result<Vec<FeedItem>, ApiError>> + 'r {
+        let url = url.to_string();
+
+        self.client
+            .get(&url)
+            .send()
+            .and_then(|resp| async { resp.error_for_status()?.text().await })
+            .map_err(ApiError::NetworkError)
+            .and_then(move |text| async move {
+                parse_feed(&text).map_err(|err| {
+                    error!("Unable to parse feed {}: {}", url, err);
+
+                    ApiError::InvalidResponse
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_feed;
+
+    const CLAIM_ID: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn parse_feed_should_read_rss_atom_and_json() {
+        let rss = format!(
+            r#"<?xml version="1.0"?><rss><channel><title>@me</title>
+            <item><title><![CDATA[First & best]]></title>
+            <link>https://odysee.com/@me:1/first:a</link>
+            <guid isPermaLink="false">lbry://@me#1/first#{}</guid>
+            <pubDate>Sat, 01 Aug 2020 10:00:00 GMT</pubDate></item>
+            <item><title>Second &amp; last</title>
+            <link>https://odysee.com/@me:1/second:b?r=x</link>
+            <pubDate>Sun, 02 Aug 2020 10:00:00 +0000</pubDate></item>
+            <item><title>Undated</title><link>https://odysee.com/@me:1/third:c</link></item>
+            </channel></rss>"#,
+            CLAIM_ID
+        );
+
+        let items = parse_feed(&rss).expect("Unable to parse RSS");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title.as_deref(), Some("First & best"));
+        assert!(items[0].is_complete());
+        assert_eq!(
+            items[0].claim().map(|claim| (claim.id, claim.name)),
+            Some((CLAIM_ID.to_string(), "first".to_string()))
+        );
+        assert_eq!(items[0].account().id, "1");
+        assert_eq!(items[1].title.as_deref(), Some("Second & last"));
+        assert!(!items[1].is_complete());
+        assert!(items[1].claim().is_none());
+
+        let atom = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><entry>
+            <title>Video</title><link rel="alternate" href="https://odysee.com/video:{}"/>
+            <id>urn:uuid:1</id><updated>2020-08-01T10:00:00Z</updated></entry></feed>"#,
+            CLAIM_ID
+        );
+
+        let items = parse_feed(&atom).expect("Unable to parse Atom");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].account().id, "feed");
+        assert!(items[0].is_complete());
+
+        let json = format!(
+            r#"{{"version":"https://jsonfeed.org/version/1.1","items":[{{"id":"x","url":"lbry://video#{}","title":"Video","date_published":"2020-08-01T10:00:00+00:00"}}]}}"#,
+            CLAIM_ID
+        );
+
+        let items = parse_feed(&json).expect("Unable to parse JSON feed");
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_complete());
+
+        assert!(parse_feed("not a feed").is_err());
+    }
+}
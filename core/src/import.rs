@@ -0,0 +1,261 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::{Comment, CommentEntity};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportFormat {
+    Youtube,
+    Odysee,
+}
+
+impl ImportFormat {
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::Youtube => "youtube",
+            Self::Odysee => "odysee",
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "youtube" => Ok(Self::Youtube),
+            "odysee" => Ok(Self::Odysee),
+            _ => Err(format!("Unknown import format {}", value)),
+        }
+    }
+}
+
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if is_quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => is_quoted = !is_quoted,
+            ',' if !is_quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !is_quoted => {}
+            '\n' if !is_quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(ch),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.into_iter()
+        .filter(|row| row.iter().any(|field| !field.is_empty()))
+        .collect()
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
+
+fn collect_text(value: &Value, text: &mut String) {
+    match value {
+        Value::Object(object) => match object.get("text") {
+            Some(Value::String(segment)) => text.push_str(segment),
+            _ => object.values().for_each(|value| collect_text(value, text)),
+        },
+        Value::Array(values) => values.iter().for_each(|value| collect_text(value, text)),
+        _ => {}
+    }
+}
+
+fn youtube_text(value: &str) -> String {
+    if !value.starts_with('{') {
+        return value.to_string();
+    }
+
+    match serde_json::from_str::<Value>(&format!("[{}]", value)) {
+        Ok(segments) => {
+            let mut text = String::new();
+            collect_text(&segments, &mut text);
+            text
+        }
+        Err(_) => value.to_string(),
+    }
+}
+
+pub fn import_youtube_csv(text: &str) -> Result<Vec<CommentEntity>, String> {
+    let mut rows = parse_csv(text).into_iter();
+    let header = rows
+        .next()
+        .ok_or_else(|| "Missing CSV header".to_string())?
+        .into_iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect::<Vec<String>>();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|column| column == name))
+    };
+    let required =
+        |names: &[&str]| column(names).ok_or_else(|| format!("Missing CSV column {}", names[0]));
+
+    let id_column = required(&["comment id"])?;
+    let channel_column = required(&["channel id", "author channel id"])?;
+    let timestamp_column = required(&["comment create timestamp", "published at"])?;
+    let video_column = required(&["video id", "content id"])?;
+    let text_column = required(&["comment text", "text"])?;
+    let name_column = column(&["author", "channel name", "author display name"]);
+    let parent_column = column(&["parent comment id"]);
+
+    rows.map(|row| {
+        let field = |index: usize| row.get(index).map(|value| value.trim()).unwrap_or_default();
+        let optional = |index: Option<usize>| {
+            index
+                .map(field)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+
+        let id = field(id_column).to_string();
+        let channel_id = field(channel_column).to_string();
+        let video_id = field(video_column).to_string();
+        let timestamp = parse_timestamp(field(timestamp_column))
+            .ok_or_else(|| format!("Invalid timestamp of comment {}", id))?;
+
+        Ok(CommentEntity {
+            account_id: ImportFormat::Youtube.source().to_string(),
+            claim_id: video_id.clone(),
+            claim_name: video_id.clone(),
+            commenter_name: optional(name_column).unwrap_or_else(|| channel_id.clone()),
+            commenter_url: format!("https://www.youtube.com/channel/{}", channel_id),
+            commenter_id: channel_id,
+            comment: youtube_text(field(text_column)),
+            is_hidden: false,
+            timestamp,
+            support_amount: 0.0,
+            is_read: true,
+            parent_id: optional(parent_column),
+            claim_title: None,
+            claim_thumbnail_url: None,
+            claim_canonical_url: Some(format!("https://www.youtube.com/watch?v={}", video_id)),
+            source: ImportFormat::Youtube.source().to_string(),
+            id,
+            is_signature_valid: None,
+        })
+    })
+    .collect()
+}
+
+pub fn import_odysee_export(text: &str) -> Result<Vec<CommentEntity>, String> {
+    let values = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(_) => text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str::<Value>(line).map_err(|err| err.to_string()))
+            .collect::<Result<Vec<Value>, String>>()?,
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            let claim_name = value
+                .get("claim_name")
+                .and_then(|name| name.as_str())
+                .map(String::from);
+            let comment = serde_json::from_value::<Comment>(value)
+                .map_err(|err| format!("Invalid Odysee comment: {}", err))?;
+
+            Ok(CommentEntity {
+                account_id: ImportFormat::Odysee.source().to_string(),
+                claim_name: claim_name.unwrap_or_else(|| comment.claim_id.clone()),
+                claim_id: comment.claim_id,
+                commenter_id: comment.commenter_id,
+                commenter_name: comment.commenter_name,
+                commenter_url: comment.commenter_url,
+                comment: comment.comment,
+                is_hidden: comment.is_hidden,
+                timestamp: comment.timestamp.naive_utc(),
+                support_amount: comment.support_amount,
+                is_read: true,
+                parent_id: comment.parent_id,
+                claim_title: None,
+                claim_thumbnail_url: None,
+                claim_canonical_url: None,
+                source: ImportFormat::Odysee.source().to_string(),
+                id: comment.id,
+                is_signature_valid: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import_odysee_export, import_youtube_csv, parse_csv};
+
+    #[test]
+    fn parse_csv_should_handle_quotes() {
+        assert_eq!(
+            parse_csv("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n\n"),
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["x, \"y\"".to_string(), "multi\nline".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn import_youtube_csv_should_map_columns() {
+        let csv = "Comment ID,Channel ID,Comment Create Timestamp,Price,Parent Comment ID,Video ID,Comment Text\n\
+                   Ugx1,UCfan,2020-08-01T10:00:00+00:00,0,,vid1,\"{\"\"text\"\":\"\"Great \"\"},{\"\"text\"\":\"\"video\"\"}\"\n\
+                   Ugx2,UCme,2020-08-01T11:00:00Z,0,Ugx1,vid1,Thanks\n";
+
+        let comments = import_youtube_csv(csv).expect("Unable to import");
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].comment, "Great video");
+        assert_eq!(comments[0].commenter_name, "UCfan");
+        assert_eq!(comments[0].source, "youtube");
+        assert_eq!(
+            comments[0].claim_canonical_url.as_deref(),
+            Some("https://www.youtube.com/watch?v=vid1")
+        );
+        assert_eq!(comments[1].parent_id.as_deref(), Some("Ugx1"));
+        assert_eq!(comments[1].comment, "Thanks");
+
+        assert!(import_youtube_csv("Comment ID,Video ID\nUgx1,vid1\n").is_err());
+    }
+
+    #[test]
+    fn import_odysee_export_should_read_json() {
+        let comment = r#"{"comment_id":"abc","claim_id":"claim","comment":"Hello","channel_id":"chan","channel_name":"@fan","channel_url":"lbry://@fan#chan","is_hidden":false,"timestamp":1596276000,"support_amount":1.5}"#;
+
+        let comments = import_odysee_export(&format!("[{}]", comment)).expect("Unable to import");
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].claim_name, "claim");
+        assert_eq!(comments[0].source, "odysee");
+
+        let lines = format!("{}\n{}\n", comment, comment);
+        assert_eq!(
+            import_odysee_export(&lines).map(|comments| comments.len()),
+            Ok(2)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use futures::{
+    stream::Stream,
+    task::{Context, Poll},
+};
+use std::pin::Pin;
+
+pub struct Interleave<St: Stream> {
+    streams: Pin<Box<St>>,
+    is_exhausted: bool,
+    active: Vec<Pin<Box<St::Item>>>,
+    next_index: usize,
+    limit: usize,
+}
+
+pub fn interleave_streams<St>(streams: St, limit: usize) -> Interleave<St>
+where
+    St: Stream,
+    St::Item: Stream,
+{
+    Interleave {
+        streams: Box::pin(streams),
+        is_exhausted: false,
+        active: Vec::new(),
+        next_index: 0,
+        limit: limit.max(1),
+    }
+}
+
+impl<St> Interleave<St>
+where
+    St: Stream,
+    St::Item: Stream,
+{
+    fn fill(&mut self, cx: &mut Context<'_>) {
+        while !self.is_exhausted && self.active.len() < self.limit {
+            match self.streams.as_mut().poll_next(cx) {
+                Poll::Ready(Some(stream)) => self.active.push(Box::pin(stream)),
+                Poll::Ready(None) => self.is_exhausted = true,
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+impl<St> Stream for Interleave<St>
+where
+    St: Stream,
+    St::Item: Stream,
+{
+    type Item = <St::Item as Stream>::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = Pin::into_inner(self);
+
+        loop {
+            this.fill(cx);
+
+            if this.active.is_empty() {
+                return if this.is_exhausted {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            }
+
+            let mut polled = 0;
+            let mut has_finished = false;
+
+            while polled < this.active.len() {
+                let index = (this.next_index + polled) % this.active.len();
+
+                match this.active[index].as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => {
+                        this.next_index = index + 1;
+
+                        return Poll::Ready(Some(item));
+                    }
+                    Poll::Ready(None) => {
+                        this.active.remove(index);
+                        this.next_index = index;
+                        has_finished = true;
+
+                        break;
+                    }
+                    Poll::Pending => polled += 1,
+                }
+            }
+
+            if !has_finished {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::{self, StreamExt};
+
+    use super::interleave_streams;
+
+    #[test]
+    fn interleave_streams_should_take_turns() {
+        let streams = stream::iter(vec![
+            stream::iter(vec!["a1", "a2", "a3", "a4"]),
+            stream::iter(vec!["b1"]),
+            stream::iter(vec!["c1", "c2"]),
+        ]);
+
+        let items =
+            futures::executor::block_on(interleave_streams(streams, 2).collect::<Vec<&str>>());
+        assert_eq!(items, vec!["a1", "b1", "a2", "c1", "a3", "c2", "a4"]);
+
+        let streams = stream::iter(vec![stream::iter(vec![1, 2]), stream::iter(vec![3])]);
+        let items =
+            futures::executor::block_on(interleave_streams(streams, 1).collect::<Vec<i32>>());
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,99 @@
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnvInterlock {
+    api_patterns: Vec<String>,
+    target_patterns: Vec<String>,
+}
+
+fn matches_any(patterns: &[String], value: &str) -> bool {
+    let value = value.to_lowercase();
+
+    patterns.iter().any(|pattern| value.contains(pattern))
+}
+
+impl EnvInterlock {
+    pub fn new(api_patterns: Vec<String>, target_patterns: Vec<String>) -> Self {
+        let lowercase = |patterns: Vec<String>| {
+            patterns
+                .into_iter()
+                .filter(|pattern| !pattern.is_empty())
+                .map(|pattern| pattern.to_lowercase())
+                .collect::<Vec<String>>()
+        };
+
+        Self {
+            api_patterns: lowercase(api_patterns),
+            target_patterns: lowercase(target_patterns),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.api_patterns.is_empty() && !self.target_patterns.is_empty()
+    }
+
+    pub fn check(&self, api_url: &str, targets: &[String]) -> Result<(), String> {
+        if !self.is_enabled() || targets.is_empty() {
+            return Ok(());
+        }
+
+        let production_targets = targets
+            .iter()
+            .filter(|target| !matches_any(&self.target_patterns, target))
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+
+        match (
+            matches_any(&self.api_patterns, api_url),
+            production_targets.is_empty(),
+        ) {
+            (true, false) => Err(format!(
+                "{} looks like a test API but notifications go to {}",
+                api_url,
+                production_targets.join(", ")
+            )),
+            (false, true) => Err(format!(
+                "{} looks like a production API but notifications only go to test targets {}",
+                api_url,
+                targets.join(", ")
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvInterlock;
+
+    #[test]
+    fn check_should_refuse_mismatched_targets() {
+        let interlock = EnvInterlock::new(
+            vec!["staging".to_string(), "Sandbox".to_string()],
+            vec!["@staging.local".to_string(), "mailcatcher".to_string()],
+        );
+        let test_targets = vec!["qa@staging.local".to_string()];
+        let mixed_targets = vec!["qa@staging.local".to_string(), "me@example.com".to_string()];
+
+        assert!(interlock
+            .check("http://staging.local:5279", &test_targets)
+            .is_ok());
+        assert!(interlock
+            .check("http://127.0.0.1:5279", &mixed_targets)
+            .is_ok());
+        assert_eq!(
+            interlock.check("http://sandbox:5279", &mixed_targets),
+            Err(
+                "http://sandbox:5279 looks like a test API but notifications go to me@example.com"
+                    .to_string()
+            )
+        );
+        assert!(interlock
+            .check("http://127.0.0.1:5279", &test_targets)
+            .is_err());
+
+        let disabled = EnvInterlock::new(vec!["staging".to_string()], vec![]);
+        assert!(!disabled.is_enabled());
+        assert!(disabled
+            .check("http://staging.local:5279", &mixed_targets)
+            .is_ok());
+    }
+}
@@ -0,0 +1,169 @@
+use futures::{
+    future::{BoxFuture, FutureExt},
+    stream::{self, BoxStream, LocalBoxStream, Stream, StreamExt},
+};
+use std::collections::HashMap;
+
+use crate::{
+    Account, Api, ApiError, Channel, Claim, ClaimMetadata, Comment, CommentCursors, CommentStop,
+};
+
+pub trait LbryApi {
+    fn stream_accounts(&self, page_size: usize) -> LocalBoxStream<'_, Account>;
+
+    fn stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> LocalBoxStream<'_, Claim>;
+
+    fn stream_comments_by_claim_id(
+        &self,
+        claim_id: String,
+        page_size: usize,
+    ) -> LocalBoxStream<'_, Comment>;
+
+    fn try_stream_accounts(
+        &self,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Account, ApiError>>;
+
+    fn try_stream_channels(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Channel, ApiError>>;
+
+    fn try_stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>>;
+
+    fn try_stream_claims_by_channel_id(
+        &self,
+        channel_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>>;
+
+    fn try_stream_comments_by_claim_id_from(
+        &self,
+        claim_id: String,
+        page_size: usize,
+        cursors: CommentCursors,
+    ) -> BoxStream<'static, Result<Comment, ApiError>>;
+
+    fn try_stream_comments_by_claim_id_until(
+        &self,
+        claim_id: String,
+        page_size: usize,
+        stop: CommentStop,
+    ) -> BoxStream<'static, Result<Comment, ApiError>>;
+
+    fn resolve_channel(&self, url: &str) -> BoxFuture<'static, Result<Channel, ApiError>>;
+
+    fn resolve_claims(
+        &self,
+        urls: Vec<String>,
+    ) -> BoxFuture<'static, Result<HashMap<String, ClaimMetadata>, ApiError>>;
+}
+
+impl LbryApi for Api {
+    fn stream_accounts(&self, page_size: usize) -> LocalBoxStream<'_, Account> {
+        Api::stream_accounts(self, page_size).boxed_local()
+    }
+
+    fn stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> LocalBoxStream<'_, Claim> {
+        Api::stream_claims_by_account_id(self, account_id, page_size).boxed_local()
+    }
+
+    fn stream_comments_by_claim_id(
+        &self,
+        claim_id: String,
+        page_size: usize,
+    ) -> LocalBoxStream<'_, Comment> {
+        Api::stream_comments_by_claim_id(self, claim_id, page_size).boxed_local()
+    }
+
+    fn try_stream_accounts(
+        &self,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Account, ApiError>> {
+        Api::try_stream_accounts(self, page_size).boxed()
+    }
+
+    fn try_stream_channels(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Channel, ApiError>> {
+        Api::try_stream_channels(self, account_id, page_size).boxed()
+    }
+
+    fn try_stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>> {
+        Api::try_stream_claims_by_account_id(self, account_id, page_size).boxed()
+    }
+
+    fn try_stream_claims_by_channel_id(
+        &self,
+        channel_id: String,
+        page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>> {
+        Api::try_stream_claims_by_channel_id(self, channel_id, page_size).boxed()
+    }
+
+    fn try_stream_comments_by_claim_id_from(
+        &self,
+        claim_id: String,
+        page_size: usize,
+        cursors: CommentCursors,
+    ) -> BoxStream<'static, Result<Comment, ApiError>> {
+        Api::try_stream_comments_by_claim_id_from(self, claim_id, page_size, cursors).boxed()
+    }
+
+    fn try_stream_comments_by_claim_id_until(
+        &self,
+        claim_id: String,
+        page_size: usize,
+        stop: CommentStop,
+    ) -> BoxStream<'static, Result<Comment, ApiError>> {
+        Api::try_stream_comments_by_claim_id_until(self, claim_id, page_size, stop).boxed()
+    }
+
+    fn resolve_channel(&self, url: &str) -> BoxFuture<'static, Result<Channel, ApiError>> {
+        Api::resolve_channel(self, url).boxed()
+    }
+
+    fn resolve_claims(
+        &self,
+        urls: Vec<String>,
+    ) -> BoxFuture<'static, Result<HashMap<String, ClaimMetadata>, ApiError>> {
+        Api::resolve_claims(self, urls).boxed()
+    }
+}
+
+pub fn stream_all_comments<'a, A: LbryApi>(
+    api: &'a A,
+    page_size: usize,
+) -> impl Stream<Item = (Account, Claim, Comment)> + 'a {
+    api.stream_accounts(page_size)
+        .map(move |account| {
+            api.stream_claims_by_account_id(account.id.clone(), page_size)
+                .zip(stream::repeat(account))
+        })
+        .flatten()
+        .map(move |(claim, account)| {
+            api.stream_comments_by_claim_id(claim.id.clone(), page_size)
+                .zip(stream::repeat((account, claim)))
+                .map(|(comment, (account, claim))| (account, claim, comment))
+        })
+        .flatten()
+}
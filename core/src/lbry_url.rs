@@ -0,0 +1,226 @@
+use std::{fmt, str::FromStr};
+
+const WEB_HOSTS: [&str; 3] = ["odysee.com/", "www.odysee.com/", "lbry.tv/"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrlPart {
+    pub name: String,
+    pub claim_id: Option<String>,
+}
+
+impl UrlPart {
+    fn parse(value: &str) -> Result<Self, String> {
+        let (name, claim_id) = match value.find(&['#', ':'][..]) {
+            Some(index) => (&value[..index], Some(&value[index + 1..])),
+            None => (value, None),
+        };
+
+        if name.is_empty() || name == "@" {
+            return Err(format!("Missing claim name in {}", value));
+        }
+
+        if name.contains(&['$', '*'][..]) {
+            return Err(format!("Unsupported URL modifier in {}", value));
+        }
+
+        match claim_id {
+            Some(claim_id)
+                if claim_id.is_empty()
+                    || claim_id.len() > 40
+                    || !claim_id.chars().all(|ch| ch.is_ascii_hexdigit()) =>
+            {
+                Err(format!("Invalid claim id in {}", value))
+            }
+            _ => Ok(Self {
+                name: name.to_string(),
+                claim_id: claim_id.map(|claim_id| claim_id.to_lowercase()),
+            }),
+        }
+    }
+
+    fn render(&self, separator: char) -> String {
+        match &self.claim_id {
+            Some(claim_id) => format!("{}{}{}", self.name, separator, claim_id),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LbryUrl {
+    pub channel: Option<UrlPart>,
+    pub stream: Option<UrlPart>,
+}
+
+impl LbryUrl {
+    pub fn stream(name: &str, claim_id: &str) -> Self {
+        Self {
+            channel: None,
+            stream: Some(UrlPart {
+                name: name.to_string(),
+                claim_id: Some(claim_id.to_string()),
+            }),
+        }
+    }
+
+    pub fn is_channel(&self) -> bool {
+        self.channel.is_some() && self.stream.is_none()
+    }
+
+    pub fn claim_id(&self) -> Option<&str> {
+        self.stream
+            .as_ref()
+            .or(self.channel.as_ref())
+            .and_then(|part| part.claim_id.as_deref())
+    }
+
+    fn render(&self, separator: char) -> String {
+        vec![&self.channel, &self.stream]
+            .into_iter()
+            .filter_map(|part| part.as_ref())
+            .map(|part| part.render(separator))
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    pub fn web_url(&self) -> String {
+        format!("https://odysee.com/{}", self.render(':'))
+    }
+}
+
+impl FromStr for LbryUrl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let without_scheme = trimmed
+            .trim_start_matches("lbry://")
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let path = WEB_HOSTS
+            .iter()
+            .find(|host| without_scheme.starts_with(*host))
+            .map(|host| &without_scheme[host.len()..])
+            .unwrap_or(without_scheme);
+        let path = path
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+
+        if let Some(embed) = path.strip_prefix("$/embed/") {
+            let mut segments = embed.split('/');
+            let name = segments.next().unwrap_or_default();
+
+            return match segments.next() {
+                Some(claim_id) => UrlPart::parse(&format!("{}#{}", name, claim_id)),
+                None => UrlPart::parse(name),
+            }
+            .map(|stream| Self {
+                channel: None,
+                stream: Some(stream),
+            });
+        }
+
+        let segments = path.split('/').collect::<Vec<&str>>();
+
+        match segments.as_slice() {
+            [channel, stream] if channel.starts_with('@') && !stream.starts_with('@') => Ok(Self {
+                channel: Some(UrlPart::parse(channel)?),
+                stream: Some(UrlPart::parse(stream)?),
+            }),
+            [channel] if channel.starts_with('@') => Ok(Self {
+                channel: Some(UrlPart::parse(channel)?),
+                stream: None,
+            }),
+            [stream] => Ok(Self {
+                channel: None,
+                stream: Some(UrlPart::parse(stream)?),
+            }),
+            _ => Err(format!("Invalid LBRY URL {}", value)),
+        }
+    }
+}
+
+impl fmt::Display for LbryUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "lbry://{}", self.render('#'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LbryUrl;
+
+    fn canonical(value: &str) -> Option<String> {
+        value.parse::<LbryUrl>().ok().map(|url| url.to_string())
+    }
+
+    #[test]
+    fn lbry_url_should_normalize_url_shapes() {
+        let cases = vec![
+            (
+                "lbry://@channel#abc/video#def",
+                "lbry://@channel#abc/video#def",
+            ),
+            (
+                "lbry://@channel:abc/video:def",
+                "lbry://@channel#abc/video#def",
+            ),
+            ("@channel#ABC/video#def", "lbry://@channel#abc/video#def"),
+            (
+                "https://odysee.com/@channel:abc/video:def?r=referrer",
+                "lbry://@channel#abc/video#def",
+            ),
+            ("odysee.com/@channel:abc/", "lbry://@channel#abc"),
+            ("https://www.odysee.com/@channel", "lbry://@channel"),
+            ("https://lbry.tv/video:def", "lbry://video#def"),
+            ("https://odysee.com/$/embed/video/def", "lbry://video#def"),
+            ("video#def", "lbry://video#def"),
+            ("  lbry://video  ", "lbry://video"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(canonical(value), Some(expected.to_string()), "{}", value);
+        }
+    }
+
+    #[test]
+    fn lbry_url_should_reject_invalid_urls() {
+        let cases = vec![
+            "",
+            "lbry://",
+            "lbry://@",
+            "lbry://video#",
+            "lbry://video#xyz",
+            "lbry://video$2",
+            "lbry://@a/@b",
+            "lbry://video/other",
+            "lbry://@channel/video/extra",
+        ];
+
+        for value in cases {
+            assert_eq!(canonical(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn lbry_url_should_build_links() {
+        let url = "lbry://@channel#abc/video#def"
+            .parse::<LbryUrl>()
+            .expect("Invalid URL");
+
+        assert_eq!(url.web_url(), "https://odysee.com/@channel:abc/video:def");
+        assert_eq!(url.claim_id(), Some("def"));
+        assert!(!url.is_channel());
+
+        let channel = "@channel:abc".parse::<LbryUrl>().expect("Invalid URL");
+        assert!(channel.is_channel());
+        assert_eq!(channel.claim_id(), Some("abc"));
+
+        assert_eq!(
+            LbryUrl::stream("video", "def").to_string(),
+            "lbry://video#def"
+        );
+    }
+}
@@ -8,23 +8,199 @@ extern crate diesel_migrations;
 extern crate log;
 
 pub mod schema;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+
+mod anomaly;
+#[macro_use]
+mod backend;
+mod batch;
+mod bundle;
+mod channel;
+mod client;
+mod cluster;
+mod collection;
+mod commentron;
+mod config;
+mod cursor;
+mod digest;
+#[cfg(feature = "disk")]
+mod disk;
+mod endpoints;
+#[cfg(feature = "events")]
+mod events;
+mod export;
+mod feed;
+mod import;
+mod interleave;
+mod interlock;
+mod lbry_api;
+mod lbry_url;
+mod locale;
+mod maintenance;
+mod metrics;
+mod milestone;
+#[cfg(feature = "test-support")]
+mod mock_sdk;
+mod moderation;
+mod notifier;
+mod ordering;
+#[cfg(feature = "profiles")]
+mod profile;
+mod ratelimit;
+mod reaction;
+#[cfg(feature = "read-links")]
+mod read_link;
+mod reconcile;
+mod report;
+mod retention;
+mod retry;
+mod scrub;
+mod search;
+mod snooze;
+mod sort;
+mod stats;
+mod status;
+mod store;
+mod sync;
+mod telemetry;
+mod template;
+mod throttle;
+mod timeouts;
+mod trace;
+mod translate;
+mod visibility;
+mod webhook;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
+#[cfg(feature = "postgres")]
+use diesel::pg::upsert::excluded;
 use diesel::prelude::*;
+use diesel_migrations::RunMigrationsError;
 use futures::{
-    future::{FutureExt, TryFutureExt},
+    future::{self, FutureExt, TryFutureExt},
     prelude::Future,
-    stream::{self, FuturesUnordered, Stream, StreamExt},
+    stream::{self, Stream, StreamExt, TryStreamExt},
 };
+#[cfg(feature = "email")]
 use lettre_email::{Email, EmailBuilder};
-use reqwest::Client;
-use serde::{de::DeserializeOwned, Deserialize};
+use reqwest::{header::RETRY_AFTER, Client, RequestBuilder, StatusCode};
+#[cfg(feature = "schemas")]
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, value::Value};
+#[cfg(feature = "email")]
+use std::sync::{Arc, RwLock};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+use tokio::time::{delay_for, timeout};
+
+use self::backend::StorageConnection;
+use self::cursor::{try_stream_resumable, try_stream_until, CommentPaging};
+use self::endpoints::is_unavailable;
+use self::retry::parse_retry_after;
+use self::schema::{
+    canned_replies, claim_settings, claims, comment_cursors, comment_reactions, comment_tags,
+    commenter_milestones, comments, comments_archive, deliveries, notification_channels,
+    notification_variants, saved_searches, snoozes, supports, sync_runs, wallet_transactions,
+};
 
-use self::schema::comments;
+#[cfg(feature = "disk")]
+pub use self::disk::DiskGuard;
+#[cfg(feature = "events")]
+pub use self::events::{stream_event_comments, EventSubscription, SdkEvent};
+#[cfg(feature = "schemas")]
+pub use self::export::export_schemas;
+#[cfg(feature = "profiles")]
+pub use self::profile::ConfigProfiles;
+#[cfg(feature = "read-links")]
+pub use self::read_link::ReadLinks;
+pub use self::{
+    anomaly::{detect_anomalies, z_score, Anomaly},
+    backend::{is_mysql_url, is_postgres_url, is_server_url, DEFAULT_POOL_SIZE},
+    batch::RequestBatcher,
+    bundle::{BundleMerge, ConflictStrategy, RuleBundle},
+    channel::ChannelTarget,
+    client::{ClientConfig, DEFAULT_USER_AGENT},
+    cluster::cluster_comments,
+    collection::{ClaimCollections, Collection, CollectionValue},
+    commentron::{CommentBackend, Commentron},
+    config::{config_key, render_env_file, ConfigKey, ConfigKind, CONFIG_KEYS, REDACTED_TEXT},
+    cursor::{CommentCursors, CommentStop},
+    digest::{
+        order_comments, render_claim_summaries, render_digest, split_clustered_digest,
+        split_collection_digest, split_digest, summarize_claims, ClaimSummary, DigestOrder,
+    },
+    endpoints::Endpoints,
+    export::{
+        commenter_contacts, render_commenters_csv, render_markdown_thread, CommenterContact,
+        Exporter,
+    },
+    feed::{parse_feed, ClaimFeeds, FeedItem},
+    import::{import_odysee_export, import_youtube_csv, parse_csv, ImportFormat},
+    interleave::{interleave_streams, Interleave},
+    interlock::EnvInterlock,
+    lbry_api::{stream_all_comments, LbryApi},
+    lbry_url::{LbryUrl, UrlPart},
+    locale::{Language, Recipient},
+    maintenance::MaintenanceReport,
+    metrics::{ApiMetrics, MethodMetrics, MetricsPusher, RunMetrics},
+    milestone::{MilestoneAction, Milestones},
+    moderation::ModerationError,
+    notifier::{Notifier, NotifierBuilder, NotifierEvent},
+    ordering::ClaimLocks,
+    ratelimit::RateLimiter,
+    reaction::{reaction_changes, ReactionChange, ReactionCounts, ReactionList},
+    reconcile::{ClaimCount, ReconcileReport},
+    report::{previous_month, MonthlyReport, DEFAULT_REPORT_TEMPLATE},
+    retention::{parse_retention, RetentionPolicy},
+    retry::RetryPolicy,
+    scrub::{ScrubPattern, Scrubber, SCRUBBED_TEXT},
+    search::{SearchQuery, SearchTerm},
+    snooze::{parse_duration, SnoozeKind},
+    sort::CommentSort,
+    stats::{ViewCounts, DEFAULT_VIEW_COUNT_URL},
+    status::{SdkStatus, WalletStatus},
+    store::{CommentStore, MemoryStore, StoreError},
+    sync::{diff_comments, CommentDelta, SyncDelta, SyncError, Syncer},
+    telemetry::{OtlpExporter, RunSpans, SpanCollector},
+    template::{Template, TemplateSplit},
+    throttle::{Throttle, ThrottledCommenter},
+    timeouts::ApiTimeouts,
+    trace::{ApiTrace, ApiTracer},
+    translate::{
+        Translation, TranslationProvider, Translator, DEFAULT_DEEPL_URL, DEFAULT_LIBRETRANSLATE_URL,
+    },
+    visibility::CommentVisibility,
+    webhook::{
+        webhook_payload, PayloadVersion, Webhook, WebhookPayloadV1, WebhookPayloadV2, Webhooks,
+    },
+};
 
 embed_migrations!("../migrations");
 
+#[cfg(feature = "mysql")]
+mod mysql_migrations {
+    embed_migrations!("../migrations_mysql");
+
+    pub(crate) use self::embedded_migrations::run;
+}
+
+#[cfg(feature = "mysql")]
+const MYSQL_TABLES: &str = "canned_replies, claim_settings, claims, comment_cursors, \
+    comment_reactions, comment_tags, commenter_milestones, comments, comments_archive, \
+    deliveries, notification_channels, notification_variants, saved_searches, snoozes, supports, \
+    sync_runs, wallet_transactions";
+
+#[cfg(feature = "postgres")]
+mod postgres_migrations {
+    embed_migrations!("../migrations_postgres");
+
+    pub(crate) use self::embedded_migrations::run;
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Account {
     pub id: String,
@@ -32,6 +208,44 @@ pub struct Account {
     pub is_default: bool,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct Channel {
+    #[serde(rename(deserialize = "claim_id"))]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub has_signing_key: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClaimMetadata {
+    pub title: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub canonical_url: Option<String>,
+    pub effective_amount: Option<f64>,
+    pub view_count: Option<i64>,
+}
+
+impl ClaimMetadata {
+    pub fn from_value(value: &Value) -> Self {
+        let text = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(|text| text.as_str())
+                .filter(|text| !text.is_empty())
+                .map(String::from)
+        };
+
+        Self {
+            title: text("/value/title"),
+            thumbnail_url: text("/value/thumbnail/url"),
+            canonical_url: text("/canonical_url").or(text("/permanent_url")),
+            effective_amount: text("/meta/effective_amount").and_then(|amount| amount.parse().ok()),
+            view_count: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Claim {
     #[serde(rename(deserialize = "claim_id"))]
@@ -39,6 +253,14 @@ pub struct Claim {
     pub name: String,
     #[serde(with = "date_format")]
     pub timestamp: DateTime<Utc>,
+    #[serde(skip)]
+    pub metadata: ClaimMetadata,
+}
+
+impl Claim {
+    pub fn permanent_url(&self) -> String {
+        LbryUrl::stream(&self.name, &self.id).to_string()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -48,6 +270,9 @@ pub struct Comment {
 
     pub claim_id: String,
 
+    #[serde(default)]
+    pub parent_id: Option<String>,
+
     pub comment: String,
 
     #[serde(rename(deserialize = "channel_id"))]
@@ -59,11 +284,74 @@ pub struct Comment {
 
     pub is_hidden: bool,
 
+    #[serde(default, rename(deserialize = "is_channel_signature_valid"))]
+    pub is_signature_valid: Option<bool>,
+
     #[serde(with = "date_format")]
     pub timestamp: DateTime<Utc>,
+
+    #[serde(default, deserialize_with = "amount_format::deserialize")]
+    pub support_amount: f64,
+
+    #[serde(skip_deserializing, default = "default_source")]
+    pub source: String,
+}
+
+impl Comment {
+    pub fn has_valid_signature(&self) -> bool {
+        self.is_signature_valid == Some(true)
+    }
+}
+
+fn default_source() -> String {
+    "lbry".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Support {
+    pub txid: String,
+    pub nout: usize,
+    pub claim_id: String,
+    pub name: String,
+    #[serde(deserialize_with = "amount_format::deserialize")]
+    pub amount: f64,
+    #[serde(default)]
+    pub is_tip: bool,
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "supports"]
+pub struct SupportEntity {
+    pub id: String,
+    pub account_id: String,
+    pub claim_id: String,
+    pub claim_name: String,
+    pub amount: f64,
+    pub is_tip: bool,
+    pub received_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Transaction {
+    pub txid: String,
+    #[serde(deserialize_with = "amount_format::deserialize")]
+    pub value: f64,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "wallet_transactions"]
+pub struct TransactionEntity {
+    pub txid: String,
+    pub account_id: String,
+    pub amount: f64,
+    pub timestamp: Option<NaiveDateTime>,
+    pub received_at: NaiveDateTime,
 }
 
-#[derive(Debug, Insertable, Queryable)]
+#[derive(Clone, Debug, Insertable, Queryable, Serialize)]
+#[cfg_attr(feature = "schemas", derive(JsonSchema))]
 #[table_name = "comments"]
 pub struct CommentEntity {
     pub id: String,
@@ -76,6 +364,249 @@ pub struct CommentEntity {
     pub comment: String,
     pub is_hidden: bool,
     pub timestamp: NaiveDateTime,
+    pub support_amount: f64,
+    pub is_read: bool,
+    pub parent_id: Option<String>,
+    pub claim_title: Option<String>,
+    pub claim_thumbnail_url: Option<String>,
+    pub claim_canonical_url: Option<String>,
+    pub source: String,
+    pub is_signature_valid: Option<bool>,
+}
+
+impl CommentEntity {
+    pub fn new(account: Account, claim: Claim, comment: Comment) -> Self {
+        let Account { id: account_id, .. } = account;
+
+        let Claim {
+            name: claim_name,
+            metadata,
+            ..
+        } = claim;
+
+        let Comment {
+            id,
+            claim_id,
+            parent_id,
+            commenter_id,
+            commenter_name,
+            commenter_url,
+            comment,
+            is_hidden,
+            is_signature_valid,
+            timestamp,
+            support_amount,
+            source,
+        } = comment;
+
+        Self {
+            id,
+            account_id,
+            claim_id,
+            claim_name,
+            commenter_id,
+            commenter_name,
+            commenter_url,
+            comment,
+            is_hidden,
+            timestamp: timestamp.naive_utc(),
+            support_amount,
+            is_read: false,
+            parent_id,
+            claim_title: metadata.title,
+            claim_thumbnail_url: metadata.thumbnail_url,
+            claim_canonical_url: metadata.canonical_url,
+            source,
+            is_signature_valid,
+        }
+    }
+
+    pub fn has_valid_signature(&self) -> bool {
+        self.is_signature_valid == Some(true)
+    }
+
+    pub fn claim_display_name(&self) -> &str {
+        self.claim_title.as_deref().unwrap_or(&self.claim_name)
+    }
+
+    pub fn claim_web_url(&self) -> String {
+        self.claim_canonical_url
+            .as_deref()
+            .and_then(|url| url.parse::<LbryUrl>().ok())
+            .unwrap_or_else(|| LbryUrl::stream(&self.claim_name, &self.claim_id))
+            .web_url()
+    }
+}
+
+#[derive(Clone, Debug, Insertable, PartialEq, Queryable)]
+#[table_name = "comment_reactions"]
+pub struct Reaction {
+    pub comment_id: String,
+    pub likes: i32,
+    pub dislikes: i32,
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "commenter_milestones"]
+pub struct CommenterMilestone {
+    pub commenter_id: String,
+    pub milestone: i64,
+    pub reached_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "comment_cursors"]
+pub struct CommentCursor {
+    pub claim_id: String,
+    pub next_page: i32,
+    pub total_pages: i32,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Insertable, PartialEq, Queryable)]
+#[table_name = "claims"]
+pub struct ClaimEntity {
+    pub id: String,
+    pub name: String,
+    pub title: Option<String>,
+    pub view_count: Option<i64>,
+    pub effective_amount: Option<f64>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ClaimEntity {
+    pub fn new(claim: &Claim, updated_at: NaiveDateTime) -> Self {
+        Self {
+            id: claim.id.clone(),
+            name: claim.name.clone(),
+            title: claim.metadata.title.clone(),
+            view_count: claim.metadata.view_count,
+            effective_amount: claim.metadata.effective_amount,
+            updated_at,
+        }
+    }
+}
+
+impl CommentCursor {
+    pub fn is_complete(&self) -> bool {
+        self.next_page > self.total_pages
+    }
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "comment_tags"]
+pub struct CommentTag {
+    pub comment_id: String,
+    pub tag: String,
+}
+
+pub const DELIVERY_EMAIL: &str = "email";
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_DONE: &str = "done";
+pub const DELIVERY_DEAD: &str = "dead";
+
+pub fn email_delivery_target(address: &str) -> String {
+    format!("{}:{}", DELIVERY_EMAIL, address)
+}
+
+#[derive(Clone, Debug, Insertable, PartialEq, Queryable)]
+#[table_name = "deliveries"]
+pub struct Delivery {
+    pub comment_id: String,
+    pub target: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Delivery {
+    pub fn pending(comment_id: String, target: String) -> Self {
+        let now = Utc::now().naive_utc();
+
+        Self {
+            comment_id,
+            target,
+            status: DELIVERY_PENDING.to_string(),
+            attempts: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_email(&self) -> bool {
+        self.target == DELIVERY_EMAIL || self.target.starts_with(&email_delivery_target(""))
+    }
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "notification_variants"]
+pub struct NotificationVariant {
+    pub comment_id: String,
+    pub variant: String,
+    pub sent_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Insertable, PartialEq, Queryable)]
+#[table_name = "notification_channels"]
+pub struct NotificationChannel {
+    pub name: String,
+    pub kind: String,
+    pub target: String,
+    pub token_env: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+#[table_name = "saved_searches"]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub recipient: Option<String>,
+}
+
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "canned_replies"]
+pub struct CannedReply {
+    pub name: String,
+    pub body: String,
+    pub channel_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+#[table_name = "claim_settings"]
+pub struct ClaimSetting {
+    pub claim_id: String,
+    pub retention_days: Option<i32>,
+}
+
+#[derive(Clone, Debug, Queryable)]
+pub struct SyncRun {
+    pub id: i32,
+    pub kind: String,
+    pub status: String,
+    pub details: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[table_name = "sync_runs"]
+pub struct NewSyncRun {
+    pub kind: String,
+    pub status: String,
+    pub details: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Queryable, Serialize)]
+#[table_name = "snoozes"]
+pub struct Snooze {
+    pub kind: String,
+    pub target: String,
+    pub until: NaiveDateTime,
 }
 
 mod date_format {
@@ -91,16 +622,83 @@ mod date_format {
     }
 }
 
+mod amount_format {
+    use serde::{de::Error, Deserialize, Deserializer};
+    use serde_json::Value;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Number(number) => number
+                .as_f64()
+                .ok_or_else(|| D::Error::custom("Invalid amount")),
+            Value::String(text) => text.parse::<f64>().map_err(D::Error::custom),
+            Value::Null => Ok(0.0),
+            _ => Err(D::Error::custom("Invalid amount")),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Api {
     client: Client,
-    url: String,
+    endpoints: Endpoints,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    batcher: RequestBatcher,
+    tracer: ApiTracer,
+    metrics: ApiMetrics,
+    page_concurrency: usize,
+    ordered_pages: bool,
+    comment_visibility: CommentVisibility,
+    comment_sort: CommentSort,
+    timeouts: ApiTimeouts,
+    basic_auth: Option<(String, Option<String>)>,
+    headers: Vec<(String, String)>,
+}
+
+fn rate_limit_error(resp: &reqwest::Response) -> Option<ApiError> {
+    if resp.status() != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let retry_after = resp
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()));
+
+    Some(ApiError::RateLimited { retry_after })
 }
 
 #[derive(Debug)]
 pub enum ApiError {
     InvalidResponse,
     NetworkError(reqwest::Error),
+    DeadlineExceeded,
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    Rpc {
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
+}
+
+impl ApiError {
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Self::InvalidResponse | Self::DeadlineExceeded | Self::Rpc { .. } => false,
+            Self::RateLimited { .. } => true,
+            Self::NetworkError(ref reqwest_error) => reqwest_error
+                .status()
+                .map(|status| status.is_server_error())
+                .unwrap_or(true),
+        }
+    }
 }
 
 impl std::fmt::Display for ApiError {
@@ -108,13 +706,53 @@ impl std::fmt::Display for ApiError {
         match *self {
             Self::InvalidResponse => write!(f, "Invalid response received"),
             Self::NetworkError(ref reqwest_error) => reqwest_error.fmt(f),
+            Self::DeadlineExceeded => write!(f, "Deadline exceeded"),
+            Self::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(f, "Rate limited for {}s", retry_after.as_secs()),
+            Self::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            Self::Rpc {
+                code, ref message, ..
+            } => write!(f, "RPC error {}: {}", code, message),
         }
     }
 }
 
 #[derive(Debug, Deserialize)]
-pub struct ApiPayload<A> {
-    result: PaginatedApiResult<A>,
+pub struct ApiPayload<R> {
+    result: R,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+fn parse_payload<R>(payload: Value) -> Result<R, ApiError>
+where
+    R: DeserializeOwned,
+{
+    if let Some(error) = payload.get("error") {
+        return match serde_json::from_value::<RpcError>(error.clone()) {
+            Ok(RpcError {
+                code,
+                message,
+                data,
+            }) => Err(ApiError::Rpc {
+                code,
+                message,
+                data,
+            }),
+            Err(_) => Err(ApiError::InvalidResponse),
+        };
+    }
+
+    serde_json::from_value::<ApiPayload<R>>(payload)
+        .map(|payload| payload.result)
+        .map_err(|_| ApiError::InvalidResponse)
 }
 
 #[derive(Debug, Deserialize)]
@@ -126,7 +764,13 @@ pub struct PaginatedApiResult<A> {
     total_pages: usize,
 }
 
-fn stream_paginated<'r, A: 'r, F: 'r, Fut: 'r>(mut f: F) -> impl Stream<Item = A> + 'r
+const DEFAULT_PAGE_CONCURRENCY: usize = 4;
+
+fn try_stream_paginated<'r, A: 'r, F: 'r, Fut: 'r>(
+    page_concurrency: usize,
+    ordered_pages: bool,
+    mut f: F,
+) -> impl Stream<Item = Result<A, ApiError>> + 'r
 where
     F: FnMut(usize) -> Fut,
     Fut: Future<Output = Result<PaginatedApiResult<A>, ApiError>>,
@@ -135,117 +779,609 @@ where
     let initial_result = f(1);
 
     initial_result
-        .map_ok(|paginated| {
-            let PaginatedApiResult {
-                total_pages, items, ..
-            } = paginated;
+        .map(move |initial| match initial {
+            Ok(paginated) => {
+                let PaginatedApiResult {
+                    total_pages, items, ..
+                } = paginated;
 
-            (total_pages, items)
-        })
-        .unwrap_or_else(|_| (0, Vec::default()))
-        .map(|(total_pages, items)| {
-            let initial_stream = stream::iter(items);
-            let rest_stream = (2..=total_pages)
-                .into_iter()
-                .map(move |page| {
-                    f(page)
-                        .map_ok(|result| result.items)
-                        .unwrap_or_else(|_| Vec::<A>::default())
-                        .map(|items| stream::iter(items))
-                })
-                .collect::<FuturesUnordered<_>>()
+                let initial_stream = stream::iter(items.into_iter().map(Ok).collect::<Vec<_>>());
+                let pages = stream::iter((2..=total_pages).map(move |page| {
+                    f(page).map(|result| match result {
+                        Ok(result) => {
+                            stream::iter(result.items.into_iter().map(Ok).collect::<Vec<_>>())
+                        }
+                        Err(err) => stream::iter(vec![Err(err)]),
+                    })
+                }));
+                let rest_stream = if ordered_pages {
+                    pages.buffered(page_concurrency.max(1)).left_stream()
+                } else {
+                    pages
+                        .buffer_unordered(page_concurrency.max(1))
+                        .right_stream()
+                }
                 .flatten();
 
-            initial_stream.chain(rest_stream)
+                initial_stream.chain(rest_stream).left_stream()
+            }
+            Err(err) => stream::iter(vec![Err(err)]).right_stream(),
         })
         .flatten_stream()
 }
 
+fn skip_errors<'r, A: 'r>(
+    stream: impl Stream<Item = Result<A, ApiError>> + 'r,
+) -> impl Stream<Item = A> + 'r {
+    stream.filter_map(|result| future::ready(result.ok()))
+}
+
 impl Api {
     pub fn new(url: String) -> Self {
+        Self::with_timeouts(url, ApiTimeouts::default())
+    }
+
+    pub fn with_timeouts(url: String, timeouts: ApiTimeouts) -> Self {
+        Self::with_client_config(url, timeouts, ClientConfig::default())
+    }
+
+    pub fn with_client_config(url: String, timeouts: ApiTimeouts, config: ClientConfig) -> Self {
+        let client = config
+            .build_client(&timeouts)
+            .expect("Unable to build HTTP client");
+
+        Self::with_client(url, client, timeouts)
+    }
+
+    pub fn with_client(url: String, client: Client, timeouts: ApiTimeouts) -> Self {
         Self {
-            client: Client::new(),
-            url,
+            client,
+            endpoints: Endpoints::new(vec![url]),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            batcher: RequestBatcher::default(),
+            tracer: ApiTracer::default(),
+            metrics: ApiMetrics::default(),
+            page_concurrency: DEFAULT_PAGE_CONCURRENCY,
+            ordered_pages: false,
+            comment_visibility: CommentVisibility::All,
+            comment_sort: CommentSort::Newest,
+            timeouts,
+            basic_auth: None,
+            headers: Vec::new(),
         }
     }
 
-    fn request_data<'a, 'r: 'a, 'b, A: 'r>(
-        &'a self,
-        payload: &'b Value,
-    ) -> impl Future<Output = Result<PaginatedApiResult<A>, ApiError>> + 'r
-    where
-        A: DeserializeOwned + std::fmt::Debug,
-    {
-        self.client
-            .post(&self.url)
-            .json(payload)
-            .send()
-            .map_err(|err| ApiError::NetworkError(err))
-            .and_then(|resp| {
-                resp.json::<ApiPayload<A>>()
-                    .map_err(|_| ApiError::InvalidResponse)
-            })
-            .map_ok(|payload| payload.result)
+    pub fn with_endpoints(self, endpoints: Endpoints) -> Self {
+        Self { endpoints, ..self }
     }
 
-    pub fn list_accounts<'a, 'r: 'a>(
-        &'a self,
-        page: usize,
-        page_size: usize,
-    ) -> impl Future<Output = Result<PaginatedApiResult<Account>, ApiError>> + 'r {
-        self.request_data::<Account>(&json!({
-            "method": "account_list",
-            "params": {
-                "page": page,
-                "page_size": page_size,
-            }
-        }))
+    pub fn with_basic_auth(self, username: String, password: Option<String>) -> Self {
+        Self {
+            basic_auth: Some((username, password)),
+            ..self
+        }
     }
 
-    pub fn stream_accounts<'a, 'r: 'a>(
-        &'a self,
-        page_size: usize,
-    ) -> impl Stream<Item = Account> + 'r {
-        let api_ref = self.clone();
-        let f = move |page| {
-            debug!("Fetching accounts in page {}", page);
+    pub fn with_header(mut self, name: String, value: String) -> Self {
+        self.headers.push((name, value));
 
-            api_ref
-                .list_accounts(page, page_size)
-                .inspect_ok(move |_| {
-                    debug!("Done fetching accounts for page {}", page);
-                })
-                .inspect_err(|err| {
-                    debug!("Error fetching accounts: {}", err);
-                })
-        };
+        self
+    }
 
-        stream_paginated(f)
+    pub fn with_bearer_token(self, token: String) -> Self {
+        self.with_header("Authorization".to_string(), format!("Bearer {}", token))
     }
 
-    pub fn list_claims_by_account_id<'a, 'b, 'r: 'a>(
-        &'a self,
-        account_id: &'b str,
-        page: usize,
-        page_size: usize,
-    ) -> impl Future<Output = Result<PaginatedApiResult<Claim>, ApiError>> + 'r {
-        self.request_data::<Claim>(&json!({
-            "method": "claim_list",
-            "params": {
-                "account_id": account_id,
-                "page": page,
-                "page_size": page_size,
-            }
-        }))
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
     }
 
-    pub fn stream_claims_by_account_id<'a, 'r: 'a>(
-        &'a self,
-        account_id: String,
-        page_size: usize,
-    ) -> impl Stream<Item = Claim> + 'r {
-        let api = self.clone();
-        let f = move |page| {
+    pub fn with_page_concurrency(self, page_concurrency: usize) -> Self {
+        Self {
+            page_concurrency,
+            ..self
+        }
+    }
+
+    pub fn with_ordered_pages(self, ordered_pages: bool) -> Self {
+        Self {
+            ordered_pages,
+            ..self
+        }
+    }
+
+    pub fn with_comment_visibility(self, comment_visibility: CommentVisibility) -> Self {
+        Self {
+            comment_visibility,
+            ..self
+        }
+    }
+
+    pub fn with_comment_sort(self, comment_sort: CommentSort) -> Self {
+        Self {
+            comment_sort,
+            ..self
+        }
+    }
+
+    pub fn with_rate_limiter(self, rate_limiter: RateLimiter) -> Self {
+        Self {
+            rate_limiter,
+            ..self
+        }
+    }
+
+    pub fn with_batcher(self, batcher: RequestBatcher) -> Self {
+        Self { batcher, ..self }
+    }
+
+    pub fn with_tracer(self, tracer: ApiTracer) -> Self {
+        Self { tracer, ..self }
+    }
+
+    pub fn metrics(&self) -> &ApiMetrics {
+        &self.metrics
+    }
+
+    fn post(&self, url: &str, payload: &Value) -> RequestBuilder {
+        let builder = self
+            .headers
+            .iter()
+            .fold(self.client.post(url), |builder, (name, value)| {
+                builder.header(name.as_str(), value.as_str())
+            });
+
+        let builder = match &self.basic_auth {
+            Some((username, password)) => builder.basic_auth(username, password.as_ref()),
+            None => builder,
+        };
+
+        builder.json(payload)
+    }
+
+    fn send_request<'a, 'r: 'a, 'b>(
+        &'a self,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<Value, ApiError>> + 'r {
+        let api = self.clone();
+        let payload = payload.clone();
+
+        async move {
+            let mut tried = 0;
+
+            loop {
+                let (index, url) = api.endpoints.current();
+                let res = api.send_request_to(url, &payload).await;
+
+                tried += 1;
+
+                match res {
+                    Err(err) if is_unavailable(&err) => {
+                        api.endpoints.mark_failed(index, &err);
+
+                        if tried >= api.endpoints.len() {
+                            return Err(err);
+                        }
+                    }
+                    res => {
+                        api.endpoints.mark_ok(index);
+
+                        return res;
+                    }
+                }
+            }
+        }
+    }
+
+    fn send_request_to<'a, 'r: 'a, 'b>(
+        &'a self,
+        url: &'b str,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<Value, ApiError>> + 'r {
+        self.post(url, payload)
+            .send()
+            .map_err(|err| ApiError::NetworkError(err))
+            .and_then(|resp| async move {
+                if let Some(err) = rate_limit_error(&resp) {
+                    return Err(err);
+                }
+
+                let status_error = resp.error_for_status_ref().err();
+                let payload = resp.json::<Value>().await;
+
+                match (payload, status_error) {
+                    (Ok(payload), _) if payload.get("error").is_some() => Ok(payload),
+                    (_, Some(status_error)) => Err(ApiError::NetworkError(status_error)),
+                    (Ok(payload), None) => Ok(payload),
+                    (Err(_), None) => Err(ApiError::InvalidResponse),
+                }
+            })
+    }
+
+    fn request<'a, 'r: 'a, 'b, R: 'r>(
+        &'a self,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<R, ApiError>> + 'r
+    where
+        R: DeserializeOwned,
+    {
+        let api = self.clone();
+        let payload = payload.clone();
+
+        async move {
+            let started_at = Instant::now();
+            let mut attempt = 1;
+
+            loop {
+                let permit = api.rate_limiter.acquire().await;
+                let res = api.send_request(&payload).await;
+
+                drop(permit);
+
+                let trace = ApiTrace::new(&payload, started_at.elapsed(), attempt, res.as_ref());
+                api.metrics.record(&trace);
+                api.tracer.record(|| trace);
+
+                match res.and_then(parse_payload::<R>) {
+                    Err(ApiError::RateLimited { retry_after })
+                        if attempt < api.retry_policy.max_attempts =>
+                    {
+                        let pause =
+                            retry_after.unwrap_or_else(|| api.retry_policy.backoff(attempt));
+
+                        warn!(
+                            "Rate limited by {}, pausing requests for {:?}",
+                            api.endpoints.current().1,
+                            pause
+                        );
+
+                        api.rate_limiter.pause(pause);
+
+                        attempt += 1;
+                    }
+                    Err(err) if err.is_retryable() && attempt < api.retry_policy.max_attempts => {
+                        let backoff = api.retry_policy.backoff(attempt);
+
+                        debug!(
+                            "Retrying request in {:?} after attempt {} failed: {}",
+                            backoff, attempt, err
+                        );
+
+                        delay_for(backoff).await;
+
+                        attempt += 1;
+                    }
+                    res => return res,
+                }
+            }
+        }
+    }
+
+    fn request_data<'a, 'r: 'a, 'b, A: 'r>(
+        &'a self,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<PaginatedApiResult<A>, ApiError>> + 'r
+    where
+        A: DeserializeOwned + std::fmt::Debug,
+    {
+        let request = if self.batcher.is_enabled() {
+            self.request_batched::<PaginatedApiResult<A>>(payload)
+                .left_future()
+        } else {
+            self.request::<PaginatedApiResult<A>>(payload)
+                .right_future()
+        };
+
+        timeout(self.timeouts.page_deadline, request)
+            .map(|res| res.unwrap_or(Err(ApiError::DeadlineExceeded)))
+    }
+
+    fn send_batch<'a, 'r: 'a>(
+        &'a self,
+        batch: Value,
+    ) -> impl Future<Output = Result<Vec<Value>, ApiError>> + 'r {
+        let api = self.clone();
+
+        async move {
+            let permit = api.rate_limiter.acquire().await;
+            let (index, url) = api.endpoints.current();
+            let resp = api
+                .post(url, &batch)
+                .send()
+                .await
+                .map_err(ApiError::NetworkError)
+                .and_then(|resp| match rate_limit_error(&resp) {
+                    Some(err) => Err(err),
+                    None => resp.error_for_status().map_err(ApiError::NetworkError),
+                });
+
+            drop(permit);
+
+            match &resp {
+                Err(ApiError::RateLimited { retry_after }) => {
+                    let pause = retry_after.unwrap_or_else(|| api.retry_policy.backoff(1));
+
+                    warn!("Rate limited by {}, pausing requests for {:?}", url, pause);
+
+                    api.rate_limiter.pause(pause);
+                }
+                Err(err) if is_unavailable(err) => api.endpoints.mark_failed(index, err),
+                _ => api.endpoints.mark_ok(index),
+            }
+
+            resp?
+                .json::<Vec<Value>>()
+                .await
+                .map_err(|_| ApiError::InvalidResponse)
+        }
+    }
+
+    fn request_batched<'a, 'r: 'a, 'b, R: 'r>(
+        &'a self,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<R, ApiError>> + 'r
+    where
+        R: DeserializeOwned,
+    {
+        let api = self.clone();
+        let payload = payload.clone();
+
+        async move {
+            let started_at = Instant::now();
+            let response = api
+                .batcher
+                .submit(payload.clone(), |batch| api.send_batch(batch))
+                .await;
+
+            match response {
+                Some(response) => {
+                    let trace =
+                        ApiTrace::new(&payload, started_at.elapsed(), 1, Ok(&response)).batched();
+                    api.metrics.record(&trace);
+                    api.tracer.record(|| trace);
+
+                    parse_payload(response)
+                }
+                None => api.request::<R>(&payload).await,
+            }
+        }
+    }
+
+    pub fn status<'a, 'r: 'a>(&'a self) -> impl Future<Output = Result<SdkStatus, ApiError>> + 'r {
+        self.request::<SdkStatus>(&json!({
+            "method": "status",
+            "params": {}
+        }))
+    }
+
+    pub fn resolve_claims<'a, 'r: 'a>(
+        &'a self,
+        urls: Vec<String>,
+    ) -> impl Future<Output = Result<HashMap<String, ClaimMetadata>, ApiError>> + 'r {
+        self.request::<HashMap<String, Value>>(&json!({
+            "method": "resolve",
+            "params": {
+                "urls": urls,
+            }
+        }))
+        .map_ok(|resolved| {
+            resolved
+                .values()
+                .filter_map(|value| {
+                    value
+                        .get("claim_id")
+                        .and_then(|claim_id| claim_id.as_str())
+                        .map(|claim_id| (claim_id.to_string(), ClaimMetadata::from_value(value)))
+                })
+                .collect::<HashMap<String, ClaimMetadata>>()
+        })
+    }
+
+    pub fn resolve_channel<'a, 'b, 'r: 'a>(
+        &'a self,
+        url: &'b str,
+    ) -> impl Future<Output = Result<Channel, ApiError>> + 'r {
+        self.request::<HashMap<String, Value>>(&json!({
+            "method": "resolve",
+            "params": {
+                "urls": [url],
+            }
+        }))
+        .and_then(|resolved| {
+            let channel = resolved
+                .into_iter()
+                .next()
+                .and_then(|(_, value)| serde_json::from_value::<Channel>(value).ok())
+                .ok_or(ApiError::InvalidResponse);
+
+            future::ready(channel)
+        })
+    }
+
+    pub fn list_accounts<'a, 'r: 'a>(
+        &'a self,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Account>, ApiError>> + 'r {
+        self.request_data::<Account>(&json!({
+            "method": "account_list",
+            "params": {
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_accounts<'a, 'r: 'a>(
+        &'a self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Account, ApiError>> + 'r {
+        let api_ref = self.clone();
+        let f = move |page| {
+            debug!("Fetching accounts in page {}", page);
+
+            api_ref
+                .list_accounts(page, page_size)
+                .inspect_ok(move |_| {
+                    debug!("Done fetching accounts for page {}", page);
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching accounts: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_accounts<'a, 'r: 'a>(
+        &'a self,
+        page_size: usize,
+    ) -> impl Stream<Item = Account> + 'r {
+        skip_errors(self.try_stream_accounts(page_size))
+    }
+
+    pub fn list_channels<'a, 'b, 'r: 'a>(
+        &'a self,
+        account_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Channel>, ApiError>> + 'r {
+        self.request_data::<Channel>(&json!({
+            "method": "channel_list",
+            "params": {
+                "account_id": account_id,
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_channels<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Channel, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
+            debug!(
+                "Fetching channels of account {} in page {}",
+                account_id, page
+            );
+
+            let inner_account_id = account_id.clone();
+
+            api.list_channels(&account_id, page, page_size)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching channels for account {} in page {}",
+                        inner_account_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching channels: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_channels<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Channel> + 'r {
+        skip_errors(self.try_stream_channels(account_id, page_size))
+    }
+
+    pub fn is_claim_owned<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+    ) -> impl Future<Output = Result<bool, ApiError>> + 'r {
+        self.request_data::<Claim>(&json!({
+            "method": "claim_list",
+            "params": {
+                "claim_id": claim_id,
+                "page": 1,
+                "page_size": 1,
+            }
+        }))
+        .map_ok(|result| !result.items.is_empty())
+    }
+
+    pub fn has_channel_signing_key<'a, 'r: 'a>(
+        &'a self,
+        channel_name: String,
+    ) -> impl Future<Output = Result<bool, ApiError>> + 'r {
+        self.request_data::<Channel>(&json!({
+            "method": "channel_list",
+            "params": {
+                "name": channel_name,
+            }
+        }))
+        .map_ok(|result| result.items.iter().any(|channel| channel.has_signing_key))
+    }
+
+    pub fn get_signing_channel_id<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+    ) -> impl Future<Output = Result<Option<String>, ApiError>> + 'r {
+        self.request_data::<Value>(&json!({
+            "method": "claim_list",
+            "params": {
+                "claim_id": claim_id,
+                "page": 1,
+                "page_size": 1,
+            }
+        }))
+        .map_ok(|result| {
+            result
+                .items
+                .first()
+                .and_then(|claim| claim["signing_channel"]["claim_id"].as_str())
+                .map(String::from)
+        })
+    }
+
+    pub fn has_channel_id_signing_key<'a, 'r: 'a>(
+        &'a self,
+        channel_id: String,
+    ) -> impl Future<Output = Result<bool, ApiError>> + 'r {
+        self.request_data::<Channel>(&json!({
+            "method": "channel_list",
+            "params": {
+                "claim_id": channel_id,
+            }
+        }))
+        .map_ok(|result| result.items.iter().any(|channel| channel.has_signing_key))
+    }
+
+    pub fn list_claims_by_account_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        account_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Claim>, ApiError>> + 'r {
+        self.request_data::<Claim>(&json!({
+            "method": "claim_list",
+            "params": {
+                "account_id": account_id,
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_claims_by_account_id<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Claim, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
             debug!("Fetching claims of account {} in page {}", account_id, page);
 
             let inner_account_id = account_id.clone();
@@ -262,230 +1398,3315 @@ impl Api {
                 })
         };
 
-        stream_paginated(f)
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_claims_by_account_id<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Claim> + 'r {
+        skip_errors(self.try_stream_claims_by_account_id(account_id, page_size))
+    }
+
+    pub fn list_claims_by_channel_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        channel_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Claim>, ApiError>> + 'r {
+        self.request_data::<Claim>(&json!({
+            "method": "claim_search",
+            "params": {
+                "channel_ids": [channel_id],
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_claims_by_channel_id<'a, 'r: 'a>(
+        &'a self,
+        channel_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Claim, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
+            debug!("Fetching claims of channel {} in page {}", channel_id, page);
+
+            let inner_channel_id = channel_id.clone();
+
+            api.list_claims_by_channel_id(&channel_id, page, page_size)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching claims for channel {} in page {}",
+                        inner_channel_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching claims: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_claims_by_channel_id<'a, 'r: 'a>(
+        &'a self,
+        channel_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Claim> + 'r {
+        skip_errors(self.try_stream_claims_by_channel_id(channel_id, page_size))
+    }
+
+    pub fn list_reactions<'a, 'r: 'a>(
+        &'a self,
+        comment_ids: Vec<String>,
+    ) -> impl Future<Output = Result<HashMap<String, ReactionCounts>, ApiError>> + 'r {
+        self.request::<ReactionList>(&json!({
+            "method": "comment_react_list",
+            "params": {
+                "comment_ids": comment_ids.join(","),
+            }
+        }))
+        .map_ok(|reaction_list| reaction_list.others_reactions)
+    }
+
+    pub fn create_comment<'a, 'r: 'a>(
+        &'a self,
+        comment: String,
+        claim_id: String,
+        parent_id: Option<String>,
+        channel_name: String,
+    ) -> impl Future<Output = Result<Value, ApiError>> + 'r {
+        self.request::<Value>(&json!({
+            "method": "comment_create",
+            "params": {
+                "comment": comment,
+                "claim_id": claim_id,
+                "parent_id": parent_id,
+                "channel_name": channel_name,
+            }
+        }))
+    }
+
+    pub fn hide_comments<'a, 'r: 'a>(
+        &'a self,
+        comment_ids: Vec<String>,
+    ) -> impl Future<Output = Result<HashMap<String, Value>, ApiError>> + 'r {
+        self.request::<HashMap<String, Value>>(&json!({
+            "method": "comment_hide",
+            "params": {
+                "comment_ids": comment_ids,
+            }
+        }))
+    }
+
+    pub fn abandon_comment<'a, 'r: 'a>(
+        &'a self,
+        comment_id: String,
+    ) -> impl Future<Output = Result<HashMap<String, Value>, ApiError>> + 'r {
+        self.request::<HashMap<String, Value>>(&json!({
+            "method": "comment_abandon",
+            "params": {
+                "comment_id": comment_id,
+            }
+        }))
+    }
+
+    pub fn list_collections<'a, 'r: 'a>(
+        &'a self,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Collection>, ApiError>> + 'r {
+        self.request_data::<Collection>(&json!({
+            "method": "collection_list",
+            "params": {
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_collections<'a, 'r: 'a>(
+        &'a self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Collection, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
+            debug!("Fetching collections in page {}", page);
+
+            api.list_collections(page, page_size)
+                .inspect_ok(move |_| {
+                    debug!("Done fetching collections in page {}", page);
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching collections: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn list_supports<'a, 'b, 'r: 'a>(
+        &'a self,
+        account_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Support>, ApiError>> + 'r {
+        self.request_data::<Support>(&json!({
+            "method": "support_list",
+            "params": {
+                "account_id": account_id,
+                "received": true,
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_supports<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Support, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
+            debug!(
+                "Fetching supports of account {} in page {}",
+                account_id, page
+            );
+
+            let inner_account_id = account_id.clone();
+
+            api.list_supports(&account_id, page, page_size)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching supports for account {} in page {}",
+                        inner_account_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching supports: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_supports<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Support> + 'r {
+        skip_errors(self.try_stream_supports(account_id, page_size))
+    }
+
+    pub fn list_transactions<'a, 'b, 'r: 'a>(
+        &'a self,
+        account_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Transaction>, ApiError>> + 'r {
+        self.request_data::<Transaction>(&json!({
+            "method": "transaction_list",
+            "params": {
+                "account_id": account_id,
+                "page": page,
+                "page_size": page_size,
+            }
+        }))
+    }
+
+    pub fn try_stream_transactions<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Transaction, ApiError>> + 'r {
+        let api = self.clone();
+        let f = move |page| {
+            debug!(
+                "Fetching transactions of account {} in page {}",
+                account_id, page
+            );
+
+            let inner_account_id = account_id.clone();
+
+            api.list_transactions(&account_id, page, page_size)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching transactions for account {} in page {}",
+                        inner_account_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching transactions: {}", err);
+                })
+        };
+
+        try_stream_paginated(self.page_concurrency, self.ordered_pages, f)
+    }
+
+    pub fn stream_transactions<'a, 'r: 'a>(
+        &'a self,
+        account_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Transaction> + 'r {
+        skip_errors(self.try_stream_transactions(account_id, page_size))
+    }
+
+    pub fn list_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+        page: usize,
+        page_size: usize,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r {
+        self.list_comments_by_claim_id_sorted(claim_id, page, page_size, self.comment_sort)
+    }
+
+    pub fn count_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+    ) -> impl Future<Output = Result<usize, ApiError>> + 'r {
+        self.list_comments_by_claim_id(claim_id, 1, 1)
+            .map_ok(|paginated| paginated.total_items)
+    }
+
+    pub fn list_comments_by_claim_id_sorted<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: &'b str,
+        page: usize,
+        page_size: usize,
+        sort: CommentSort,
+    ) -> impl Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r {
+        let mut payload = json!({
+            "method": "comment_list",
+            "params": {
+                "claim_id": claim_id,
+                "page": page,
+                "page_size": page_size,
+            }
+        });
+        self.comment_visibility.apply(&mut payload);
+        sort.apply(&mut payload);
+
+        self.request_data::<Comment>(&payload)
+    }
+
+    fn try_stream_comment_pages<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        paging: CommentPaging,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        let api = self.clone();
+        let cursor_claim_id = claim_id.clone();
+        let sort = paging.sort(self.comment_sort);
+        let f = move |page| {
+            debug!("Fetching comment of claim {} in page {}", &claim_id, page);
+
+            let inner_claim_id = claim_id.clone();
+
+            api.list_comments_by_claim_id_sorted(&claim_id, page, page_size, sort)
+                .inspect_ok(move |_| {
+                    debug!(
+                        "Done fetching comments for claim {} in page {}",
+                        inner_claim_id, page
+                    );
+                })
+                .inspect_err(|err| {
+                    debug!("Error fetching comments: {}", err);
+                })
+        };
+
+        match paging {
+            CommentPaging::All => {
+                try_stream_paginated(self.page_concurrency, self.ordered_pages, f).left_stream()
+            }
+            CommentPaging::From(cursors) => {
+                try_stream_resumable(self.page_concurrency, cursor_claim_id, cursors, f)
+                    .left_stream()
+                    .right_stream()
+            }
+            CommentPaging::Until(stop) => try_stream_until(stop, f).right_stream().right_stream(),
+        }
+    }
+
+    pub fn try_stream_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::All)
+    }
+
+    pub fn try_stream_comments_by_claim_id_from<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        cursors: CommentCursors,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::From(cursors))
+    }
+
+    pub fn try_stream_comments_by_claim_id_since<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        after: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(
+            claim_id,
+            page_size,
+            CommentPaging::Until(CommentStop::Since(after)),
+        )
+    }
+
+    pub fn try_stream_comments_by_claim_id_until<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        stop: CommentStop,
+    ) -> impl Stream<Item = Result<Comment, ApiError>> + 'r {
+        self.try_stream_comment_pages(claim_id, page_size, CommentPaging::Until(stop))
+    }
+
+    pub fn list_comments_by_claim_id_since<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        after: DateTime<Utc>,
+        page_size: usize,
+    ) -> impl Future<Output = Result<Vec<Comment>, ApiError>> + 'r {
+        self.try_stream_comments_by_claim_id_since(claim_id, page_size, after)
+            .try_collect()
+    }
+
+    pub fn stream_comments_by_claim_id<'a, 'b, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+    ) -> impl Stream<Item = Comment> + 'r {
+        skip_errors(self.try_stream_comments_by_claim_id(claim_id, page_size))
+    }
+
+    pub fn stream_comments_by_claim_id_from<'a, 'r: 'a>(
+        &'a self,
+        claim_id: String,
+        page_size: usize,
+        cursors: CommentCursors,
+    ) -> impl Stream<Item = Comment> + 'r {
+        skip_errors(self.try_stream_comments_by_claim_id_from(claim_id, page_size, cursors))
+    }
+}
+
+type KnownClaimRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    NaiveDateTime,
+);
+
+fn merge_archived(
+    archived: Vec<CommentEntity>,
+    comments: Vec<CommentEntity>,
+) -> Vec<CommentEntity> {
+    let mut merged = archived.into_iter().chain(comments).collect::<Vec<_>>();
+
+    merged.sort_by_key(|comment| comment.timestamp);
+
+    merged
+}
+
+pub struct Storage {
+    conn: StorageConnection,
+}
+
+impl Storage {
+    pub fn open(database_url: String) -> Result<Self, diesel::ConnectionError> {
+        Self::open_with_pool_size(database_url, DEFAULT_POOL_SIZE)
+    }
+
+    pub fn open_with_pool_size(
+        database_url: String,
+        pool_size: u32,
+    ) -> Result<Self, diesel::ConnectionError> {
+        StorageConnection::establish(&database_url, pool_size).map(|conn| {
+            let storage = Self { conn };
+
+            match &storage.conn {
+                StorageConnection::Sqlite(pool) => pool
+                    .get()
+                    .map_err(RunMigrationsError::QueryError)
+                    .and_then(|conn| embedded_migrations::run(&*conn)),
+                #[cfg(feature = "mysql")]
+                StorageConnection::Mysql(pool) => pool
+                    .get()
+                    .map_err(RunMigrationsError::QueryError)
+                    .and_then(|conn| mysql_migrations::run(&*conn)),
+                #[cfg(feature = "postgres")]
+                StorageConnection::Postgres(pool) => pool
+                    .get()
+                    .map_err(RunMigrationsError::QueryError)
+                    .and_then(|conn| postgres_migrations::run(&*conn)),
+            }
+            .expect(&format!("Unable to run migrations"));
+
+            storage
+        })
+    }
+
+    pub fn save_comment(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+    ) -> Result<CommentEntity, diesel::result::Error> {
+        let new_comment = CommentEntity::new(account, claim, comment);
+
+        with_conn!(self, |conn| diesel::insert_into(comments::table)
+            .values(&new_comment)
+            .execute(conn))
+        .map(|_| new_comment)
+    }
+
+    pub fn save_comment_with_deliveries(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+        targets: Vec<String>,
+    ) -> Result<CommentEntity, diesel::result::Error> {
+        self.transaction(|| {
+            let comment_entity = self.save_comment(account, claim, comment)?;
+
+            self.save_deliveries(
+                targets
+                    .into_iter()
+                    .map(|target| Delivery::pending(comment_entity.id.clone(), target))
+                    .collect(),
+            )?;
+
+            Ok(comment_entity)
+        })
+    }
+
+    pub fn import_comments(
+        &self,
+        comment_entities: Vec<CommentEntity>,
+    ) -> Result<usize, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::insert_or_ignore_into(comments::table)
+                .values(&comment_entities)
+                .execute(conn),
+            |conn| diesel::insert_into(comments::table)
+                .values(&comment_entities)
+                .on_conflict_do_nothing()
+                .execute(conn)
+        )
+    }
+
+    pub fn get_comment_by_id(&self, comment_id: String) -> Option<CommentEntity> {
+        use self::schema::comments::dsl::comments as c;
+
+        use self::schema::comments_archive::dsl::comments_archive as a;
+
+        with_conn!(self, |conn| c
+            .find(&comment_id)
+            .first(conn)
+            .or_else(|_| a.find(&comment_id).first(conn)))
+        .ok()
+    }
+
+    pub fn delete_comment_by_id(&self, comment_id: String) -> Result<(), diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, id};
+        use self::schema::comments_archive::dsl::{comments_archive as a, id as archive_id};
+
+        with_conn!(self, |conn| diesel::delete(c.filter(id.eq(&comment_id)))
+            .execute(conn)
+            .and_then(
+                |_| diesel::delete(a.filter(archive_id.eq(&comment_id))).execute(conn)
+            ))
+        .map(|_| ())
+    }
+
+    pub fn update_comment(
+        &self,
+        comment_entity: CommentEntity,
+        comment: &Comment,
+    ) -> Result<CommentEntity, diesel::result::Error> {
+        use self::schema::comments::dsl::{comment as text, comments as c, id, is_hidden};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| diesel::update(
+            c.filter(id.eq(&comment_entity.id))
+        )
+        .set((text.eq(&comment.comment), is_hidden.eq(comment.is_hidden)))
+        .execute(conn)
+        .and_then(|_| diesel::update(
+            archive::comments_archive.filter(archive::id.eq(&comment_entity.id))
+        )
+        .set((
+            archive::comment.eq(&comment.comment),
+            archive::is_hidden.eq(comment.is_hidden)
+        ))
+        .execute(conn)))
+        .map(|_| CommentEntity {
+            comment: comment.comment.clone(),
+            is_hidden: comment.is_hidden,
+            ..comment_entity
+        })
+    }
+
+    pub fn get_comments_by_claim_id(
+        &self,
+        comment_claim_id: String,
+    ) -> Result<Vec<CommentEntity>, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c, timestamp};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| archive::comments_archive
+            .filter(archive::claim_id.eq(&comment_claim_id))
+            .order(archive::timestamp.asc())
+            .load(conn)
+            .and_then(|archived| c
+                .filter(claim_id.eq(&comment_claim_id))
+                .order(timestamp.asc())
+                .load(conn)
+                .map(|comments| merge_archived(archived, comments))))
+    }
+
+    pub fn get_unread_comments(&self) -> Result<Vec<CommentEntity>, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, is_read};
+
+        with_conn!(self, |conn| c.filter(is_read.eq(false)).load(conn))
+    }
+
+    pub fn get_replied_comment_ids(
+        &self,
+        commenter_ids: Vec<String>,
+    ) -> Result<HashSet<String>, diesel::result::Error> {
+        use self::schema::comments::dsl::{commenter_id, comments as c, parent_id};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| archive::comments_archive
+            .filter(archive::commenter_id.eq_any(&commenter_ids))
+            .select(archive::parent_id)
+            .load::<Option<String>>(conn)
+            .and_then(|archived| c
+                .filter(commenter_id.eq_any(&commenter_ids))
+                .select(parent_id)
+                .load::<Option<String>>(conn)
+                .map(|replied| archived
+                    .into_iter()
+                    .chain(replied)
+                    .flatten()
+                    .collect())))
+    }
+
+    pub fn mark_comments_as_read(
+        &self,
+        comment_ids: Vec<String>,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, id, is_read};
+
+        with_conn!(self, |conn| diesel::update(
+            c.filter(id.eq_any(comment_ids))
+        )
+        .set(is_read.eq(true))
+        .execute(conn))
+    }
+
+    pub fn get_comments_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<CommentEntity>, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, timestamp};
+        use self::schema::comments_archive::dsl as archive;
+
+        let since = since.unwrap_or_else(|| NaiveDateTime::from_timestamp(0, 0));
+
+        with_conn!(self, |conn| archive::comments_archive
+            .filter(archive::timestamp.ge(since))
+            .order(archive::timestamp.asc())
+            .load(conn)
+            .and_then(|archived| c
+                .filter(timestamp.ge(since))
+                .order(timestamp.asc())
+                .load(conn)
+                .map(|comments| merge_archived(archived, comments))))
+    }
+
+    pub fn get_comments_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<CommentEntity>, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, timestamp};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| archive::comments_archive
+            .filter(archive::timestamp.ge(start))
+            .filter(archive::timestamp.lt(end))
+            .order(archive::timestamp.asc())
+            .load(conn)
+            .and_then(|archived| c
+                .filter(timestamp.ge(start))
+                .filter(timestamp.lt(end))
+                .order(timestamp.asc())
+                .load(conn)
+                .map(|comments| merge_archived(archived, comments))))
+    }
+
+    pub fn save_support(
+        &self,
+        account: &Account,
+        support: Support,
+    ) -> Result<Option<SupportEntity>, diesel::result::Error> {
+        let Support {
+            txid,
+            nout,
+            claim_id,
+            name: claim_name,
+            amount,
+            is_tip,
+        } = support;
+
+        let new_support = SupportEntity {
+            id: format!("{}:{}", txid, nout),
+            account_id: account.id.clone(),
+            claim_id,
+            claim_name,
+            amount,
+            is_tip,
+            received_at: Utc::now().naive_utc(),
+        };
+
+        with_upsert_conn!(
+            self,
+            |conn| diesel::insert_or_ignore_into(supports::table)
+                .values(&new_support)
+                .execute(conn),
+            |conn| diesel::insert_into(supports::table)
+                .values(&new_support)
+                .on_conflict_do_nothing()
+                .execute(conn)
+        )
+        .map(|count| if count > 0 { Some(new_support) } else { None })
+    }
+
+    pub fn has_transactions(
+        &self,
+        transaction_account_id: &str,
+    ) -> Result<bool, diesel::result::Error> {
+        use self::schema::wallet_transactions::dsl::{account_id, wallet_transactions as w};
+
+        with_conn!(self, |conn| w
+            .filter(account_id.eq(transaction_account_id))
+            .count()
+            .get_result::<i64>(conn))
+        .map(|count| count > 0)
+    }
+
+    pub fn save_transaction(
+        &self,
+        account: &Account,
+        transaction: Transaction,
+    ) -> Result<Option<TransactionEntity>, diesel::result::Error> {
+        let new_transaction = TransactionEntity {
+            txid: transaction.txid,
+            account_id: account.id.clone(),
+            amount: transaction.value,
+            timestamp: transaction
+                .timestamp
+                .map(|timestamp| NaiveDateTime::from_timestamp(timestamp, 0)),
+            received_at: Utc::now().naive_utc(),
+        };
+
+        with_upsert_conn!(
+            self,
+            |conn| diesel::insert_or_ignore_into(wallet_transactions::table)
+                .values(&new_transaction)
+                .execute(conn),
+            |conn| diesel::insert_into(wallet_transactions::table)
+                .values(&new_transaction)
+                .on_conflict_do_nothing()
+                .execute(conn)
+        )
+        .map(|count| {
+            if count > 0 {
+                Some(new_transaction)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn save_reactions(&self, reactions: Vec<Reaction>) -> Result<usize, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(comment_reactions::table)
+                .values(&reactions)
+                .execute(conn),
+            |conn| {
+                use self::schema::comment_reactions::dsl::{comment_id, dislikes, likes};
+
+                diesel::insert_into(comment_reactions::table)
+                    .values(&reactions)
+                    .on_conflict(comment_id)
+                    .do_update()
+                    .set((likes.eq(excluded(likes)), dislikes.eq(excluded(dislikes))))
+                    .execute(conn)
+            }
+        )
+    }
+
+    pub fn get_reactions_by_comment_ids(
+        &self,
+        comment_ids: Vec<String>,
+    ) -> Result<Vec<Reaction>, diesel::result::Error> {
+        use self::schema::comment_reactions::dsl::{comment_id, comment_reactions as r};
+
+        with_conn!(self, |conn| r
+            .filter(comment_id.eq_any(comment_ids))
+            .load(conn))
+    }
+
+    pub fn count_comments_by_claim_id(
+        &self,
+        comment_claim_id: String,
+    ) -> Result<i64, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| c
+            .filter(claim_id.eq(&comment_claim_id))
+            .count()
+            .get_result::<i64>(conn)
+            .and_then(|count| archive::comments_archive
+                .filter(archive::claim_id.eq(&comment_claim_id))
+                .count()
+                .get_result::<i64>(conn)
+                .map(|archived| count + archived)))
+    }
+
+    pub fn count_comments_by_commenter_id(
+        &self,
+        comment_commenter_id: String,
+    ) -> Result<i64, diesel::result::Error> {
+        use self::schema::comments::dsl::{commenter_id, comments as c};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| c
+            .filter(commenter_id.eq(&comment_commenter_id))
+            .count()
+            .get_result::<i64>(conn)
+            .and_then(|count| archive::comments_archive
+                .filter(archive::commenter_id.eq(&comment_commenter_id))
+                .count()
+                .get_result::<i64>(conn)
+                .map(|archived| count + archived)))
+    }
+
+    pub fn save_commenter_milestone(
+        &self,
+        commenter_milestone: CommenterMilestone,
+    ) -> Result<bool, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::insert_or_ignore_into(commenter_milestones::table)
+                .values(&commenter_milestone)
+                .execute(conn),
+            |conn| diesel::insert_into(commenter_milestones::table)
+                .values(&commenter_milestone)
+                .on_conflict_do_nothing()
+                .execute(conn)
+        )
+        .map(|count| count > 0)
+    }
+
+    pub fn hide_comments(&self, comment_ids: Vec<String>) -> Result<usize, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, id, is_hidden};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| diesel::update(
+            c.filter(id.eq_any(&comment_ids))
+        )
+        .set(is_hidden.eq(true))
+        .execute(conn)
+        .and_then(|count| diesel::update(
+            archive::comments_archive.filter(archive::id.eq_any(&comment_ids))
+        )
+        .set(archive::is_hidden.eq(true))
+        .execute(conn)
+        .map(|archived| count + archived)))
+    }
+
+    pub fn tag_comments(
+        &self,
+        comment_ids: Vec<String>,
+        comment_tag: String,
+    ) -> Result<usize, diesel::result::Error> {
+        let tags = comment_ids
+            .into_iter()
+            .map(|comment_id| CommentTag {
+                comment_id,
+                tag: comment_tag.clone(),
+            })
+            .collect::<Vec<CommentTag>>();
+
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(comment_tags::table)
+                .values(&tags)
+                .execute(conn),
+            |conn| diesel::insert_into(comment_tags::table)
+                .values(&tags)
+                .on_conflict_do_nothing()
+                .execute(conn)
+        )
+    }
+
+    pub fn get_comment_cursors(&self) -> Result<Vec<CommentCursor>, diesel::result::Error> {
+        with_conn!(self, |conn| comment_cursors::table.load(conn))
+    }
+
+    pub fn save_comment_cursor(
+        &self,
+        cursor: CommentCursor,
+    ) -> Result<CommentCursor, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(comment_cursors::table)
+                .values(&cursor)
+                .execute(conn),
+            |conn| {
+                use self::schema::comment_cursors::dsl::{
+                    claim_id, next_page, total_pages, updated_at,
+                };
+
+                diesel::insert_into(comment_cursors::table)
+                    .values(&cursor)
+                    .on_conflict(claim_id)
+                    .do_update()
+                    .set((
+                        next_page.eq(excluded(next_page)),
+                        total_pages.eq(excluded(total_pages)),
+                        updated_at.eq(excluded(updated_at)),
+                    ))
+                    .execute(conn)
+            }
+        )
+        .map(|_| cursor)
+    }
+
+    pub fn delete_comment_cursor(
+        &self,
+        cursor_claim_id: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::comment_cursors::dsl::{claim_id, comment_cursors as c};
+
+        with_conn!(self, |conn| diesel::delete(
+            c.filter(claim_id.eq(cursor_claim_id))
+        )
+        .execute(conn))
+    }
+
+    pub fn get_comment_ids_by_tag(
+        &self,
+        comment_tag: String,
+    ) -> Result<Vec<String>, diesel::result::Error> {
+        use self::schema::comment_tags::dsl::{comment_id, comment_tags as t, tag};
+
+        with_conn!(self, |conn| t
+            .filter(tag.eq(comment_tag))
+            .select(comment_id)
+            .load(conn))
+    }
+
+    pub fn save_notification_variant(
+        &self,
+        notification_variant: NotificationVariant,
+    ) -> Result<NotificationVariant, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(notification_variants::table)
+                .values(&notification_variant)
+                .execute(conn),
+            |conn| {
+                use self::schema::notification_variants::dsl::{comment_id, sent_at, variant};
+
+                diesel::insert_into(notification_variants::table)
+                    .values(&notification_variant)
+                    .on_conflict(comment_id)
+                    .do_update()
+                    .set((variant.eq(excluded(variant)), sent_at.eq(excluded(sent_at))))
+                    .execute(conn)
+            }
+        )
+        .map(|_| notification_variant)
+    }
+
+    pub fn save_notification_channel(
+        &self,
+        channel: NotificationChannel,
+    ) -> Result<NotificationChannel, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(notification_channels::table)
+                .values(&channel)
+                .execute(conn),
+            |conn| {
+                use self::schema::notification_channels::dsl::{kind, name, target, token_env};
+
+                diesel::insert_into(notification_channels::table)
+                    .values(&channel)
+                    .on_conflict(name)
+                    .do_update()
+                    .set((
+                        kind.eq(excluded(kind)),
+                        target.eq(excluded(target)),
+                        token_env.eq(excluded(token_env)),
+                    ))
+                    .execute(conn)
+            }
+        )
+        .map(|_| channel)
+    }
+
+    pub fn get_notification_channels(
+        &self,
+    ) -> Result<Vec<NotificationChannel>, diesel::result::Error> {
+        use self::schema::notification_channels::dsl::{name, notification_channels as n};
+
+        with_conn!(self, |conn| n.order(name.asc()).load(conn))
+    }
+
+    pub fn delete_notification_channel_by_name(
+        &self,
+        channel_name: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::notification_channels::dsl::{name, notification_channels as n};
+
+        with_conn!(self, |conn| diesel::delete(n.filter(name.eq(channel_name)))
+            .execute(conn))
+    }
+
+    pub fn save_search(&self, search: SavedSearch) -> Result<SavedSearch, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(saved_searches::table)
+                .values(&search)
+                .execute(conn),
+            |conn| {
+                use self::schema::saved_searches::dsl::{name, query, recipient};
+
+                diesel::insert_into(saved_searches::table)
+                    .values(&search)
+                    .on_conflict(name)
+                    .do_update()
+                    .set((query.eq(excluded(query)), recipient.eq(excluded(recipient))))
+                    .execute(conn)
+            }
+        )
+        .map(|_| search)
+    }
+
+    pub fn get_saved_searches(&self) -> Result<Vec<SavedSearch>, diesel::result::Error> {
+        use self::schema::saved_searches::dsl::{name, saved_searches as s};
+
+        with_conn!(self, |conn| s.order(name.asc()).load(conn))
+    }
+
+    pub fn delete_saved_search_by_name(
+        &self,
+        search_name: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::saved_searches::dsl::{name, saved_searches as s};
+
+        with_conn!(self, |conn| diesel::delete(s.filter(name.eq(search_name)))
+            .execute(conn))
+    }
+
+    pub fn save_snooze(&self, snooze: Snooze) -> Result<Snooze, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(snoozes::table)
+                .values(&snooze)
+                .execute(conn),
+            |conn| {
+                use self::schema::snoozes::dsl::{kind, target, until};
+
+                diesel::insert_into(snoozes::table)
+                    .values(&snooze)
+                    .on_conflict((kind, target))
+                    .do_update()
+                    .set(until.eq(excluded(until)))
+                    .execute(conn)
+            }
+        )
+        .map(|_| snooze)
+    }
+
+    pub fn get_active_snoozes(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<Snooze>, diesel::result::Error> {
+        use self::schema::snoozes::dsl::{snoozes as s, until};
+
+        with_conn!(self, |conn| s
+            .filter(until.gt(now))
+            .order(until.asc())
+            .load(conn))
+    }
+
+    pub fn delete_snooze(
+        &self,
+        snooze_kind: SnoozeKind,
+        snooze_target: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::snoozes::dsl::{kind, snoozes as s, target};
+
+        with_conn!(self, |conn| diesel::delete(
+            s.filter(kind.eq(snooze_kind.as_str()))
+                .filter(target.eq(snooze_target)),
+        )
+        .execute(conn))
+    }
+
+    pub fn delete_expired_snoozes(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::snoozes::dsl::{snoozes as s, until};
+
+        with_conn!(self, |conn| diesel::delete(s.filter(until.le(now)))
+            .execute(conn))
+    }
+
+    pub fn save_canned_reply(
+        &self,
+        canned_reply: CannedReply,
+    ) -> Result<CannedReply, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(canned_replies::table)
+                .values(&canned_reply)
+                .execute(conn),
+            |conn| {
+                use self::schema::canned_replies::dsl::{body, channel_name, name};
+
+                diesel::insert_into(canned_replies::table)
+                    .values(&canned_reply)
+                    .on_conflict(name)
+                    .do_update()
+                    .set((
+                        body.eq(excluded(body)),
+                        channel_name.eq(excluded(channel_name)),
+                    ))
+                    .execute(conn)
+            }
+        )
+        .map(|_| canned_reply)
+    }
+
+    pub fn get_canned_replies(&self) -> Result<Vec<CannedReply>, diesel::result::Error> {
+        use self::schema::canned_replies::dsl::{canned_replies as r, name};
+
+        with_conn!(self, |conn| r.order(name.asc()).load(conn))
+    }
+
+    pub fn get_canned_reply_by_name(&self, reply_name: String) -> Option<CannedReply> {
+        use self::schema::canned_replies::dsl::canned_replies as r;
+
+        with_conn!(self, |conn| r.find(reply_name).first(conn)).ok()
+    }
+
+    pub fn delete_canned_reply_by_name(
+        &self,
+        reply_name: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::canned_replies::dsl::{canned_replies as r, name};
+
+        with_conn!(self, |conn| diesel::delete(r.filter(name.eq(reply_name)))
+            .execute(conn))
+    }
+
+    pub fn save_claim_setting(
+        &self,
+        setting: ClaimSetting,
+    ) -> Result<ClaimSetting, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(claim_settings::table)
+                .values(&setting)
+                .execute(conn),
+            |conn| {
+                use self::schema::claim_settings::dsl::{claim_id, retention_days};
+
+                diesel::insert_into(claim_settings::table)
+                    .values(&setting)
+                    .on_conflict(claim_id)
+                    .do_update()
+                    .set(retention_days.eq(excluded(retention_days)))
+                    .execute(conn)
+            }
+        )
+        .map(|_| setting)
+    }
+
+    pub fn save_claim(&self, claim: ClaimEntity) -> Result<ClaimEntity, diesel::result::Error> {
+        with_upsert_conn!(
+            self,
+            |conn| diesel::replace_into(claims::table)
+                .values(&claim)
+                .execute(conn),
+            |conn| {
+                use self::schema::claims::dsl::{
+                    effective_amount, id, name, title, updated_at, view_count,
+                };
+
+                diesel::insert_into(claims::table)
+                    .values(&claim)
+                    .on_conflict(id)
+                    .do_update()
+                    .set((
+                        name.eq(excluded(name)),
+                        title.eq(excluded(title)),
+                        view_count.eq(excluded(view_count)),
+                        effective_amount.eq(excluded(effective_amount)),
+                        updated_at.eq(excluded(updated_at)),
+                    ))
+                    .execute(conn)
+            }
+        )
+        .map(|_| claim)
+    }
+
+    pub fn get_claim(
+        &self,
+        claim_id: String,
+    ) -> Result<Option<ClaimEntity>, diesel::result::Error> {
+        with_conn!(self, |conn| claims::table.find(claim_id).first(conn)).optional()
+    }
+
+    pub fn get_top_claims(&self, limit: i64) -> Result<Vec<ClaimEntity>, diesel::result::Error> {
+        use self::schema::claims::dsl::{claims as c, effective_amount, view_count};
+
+        with_conn!(self, |conn| c
+            .order((view_count.desc(), effective_amount.desc()))
+            .limit(limit)
+            .load(conn))
+    }
+
+    pub fn get_claim_settings(&self) -> Result<Vec<ClaimSetting>, diesel::result::Error> {
+        use self::schema::claim_settings::dsl::{claim_id, claim_settings as s};
+
+        with_conn!(self, |conn| s.order(claim_id.asc()).load(conn))
+    }
+
+    pub fn delete_claim_setting(
+        &self,
+        setting_claim_id: String,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::claim_settings::dsl::{claim_id, claim_settings as s};
+
+        with_conn!(self, |conn| diesel::delete(
+            s.filter(claim_id.eq(setting_claim_id))
+        )
+        .execute(conn))
+    }
+
+    pub fn delete_comments_by_claim_id_before(
+        &self,
+        comment_claim_id: String,
+        before: NaiveDateTime,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c, timestamp};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| diesel::delete(
+            c.filter(claim_id.eq(&comment_claim_id))
+                .filter(timestamp.lt(before)),
+        )
+        .execute(conn)
+        .and_then(|count| diesel::delete(
+            archive::comments_archive
+                .filter(archive::claim_id.eq(&comment_claim_id))
+                .filter(archive::timestamp.lt(before)),
+        )
+        .execute(conn)
+        .map(|archived| count + archived)))
+    }
+
+    pub fn delete_comments_before_except_claim_ids(
+        &self,
+        excluded_claim_ids: Vec<String>,
+        before: NaiveDateTime,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c, timestamp};
+        use self::schema::comments_archive::dsl as archive;
+
+        with_conn!(self, |conn| diesel::delete(
+            c.filter(claim_id.ne_all(&excluded_claim_ids))
+                .filter(timestamp.lt(before)),
+        )
+        .execute(conn)
+        .and_then(|count| diesel::delete(
+            archive::comments_archive
+                .filter(archive::claim_id.ne_all(&excluded_claim_ids))
+                .filter(archive::timestamp.lt(before)),
+        )
+        .execute(conn)
+        .map(|archived| count + archived)))
+    }
+
+    pub fn archive_comments_before(
+        &self,
+        before: NaiveDateTime,
+    ) -> Result<usize, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, timestamp};
+
+        let archived = c.filter(timestamp.lt(before));
+
+        self.transaction(|| {
+            with_conn!(self, |conn| diesel::insert_into(comments_archive::table)
+                .values(archived)
+                .into_columns(comments_archive::all_columns)
+                .execute(conn)
+                .and_then(|_| diesel::delete(archived).execute(conn)))
+        })
+    }
+
+    pub fn save_sync_run(&self, sync_run: NewSyncRun) -> Result<(), diesel::result::Error> {
+        with_conn!(self, |conn| diesel::insert_into(sync_runs::table)
+            .values(&sync_run)
+            .execute(conn))
+        .map(|_| ())
+    }
+
+    pub fn get_sync_runs(
+        &self,
+        run_kind: String,
+        limit: i64,
+    ) -> Result<Vec<SyncRun>, diesel::result::Error> {
+        use self::schema::sync_runs::dsl::{id, kind, sync_runs as s};
+
+        with_conn!(self, |conn| s
+            .filter(kind.eq(run_kind))
+            .order(id.desc())
+            .limit(limit)
+            .load(conn))
+    }
+
+    pub fn get_last_sync_run(
+        &self,
+        run_kind: String,
+        run_status: String,
+    ) -> Result<Option<SyncRun>, diesel::result::Error> {
+        use self::schema::sync_runs::dsl::{id, kind, status, sync_runs as s};
+
+        with_conn!(self, |conn| s
+            .filter(kind.eq(run_kind))
+            .filter(status.eq(run_status))
+            .order(id.desc())
+            .first(conn))
+        .optional()
+    }
+
+    pub fn save_deliveries(
+        &self,
+        new_deliveries: Vec<Delivery>,
+    ) -> Result<(), diesel::result::Error> {
+        use self::schema::deliveries::dsl::{comment_id, deliveries as d};
+
+        for delivery in new_deliveries {
+            with_conn!(self, |conn| diesel::delete(
+                d.filter(comment_id.eq(&delivery.comment_id))
+                    .filter(self::schema::deliveries::dsl::target.eq(&delivery.target))
+            )
+            .execute(conn)
+            .and_then(|_| diesel::insert_into(deliveries::table)
+                .values(&delivery)
+                .execute(conn)))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_deliveries(
+        &self,
+        delivery_status: String,
+    ) -> Result<Vec<Delivery>, diesel::result::Error> {
+        use self::schema::deliveries::dsl::{created_at, deliveries as d, status};
+
+        with_conn!(self, |conn| d
+            .filter(status.eq(delivery_status))
+            .order(created_at.asc())
+            .load(conn))
+    }
+
+    pub fn get_delivery(
+        &self,
+        delivery_comment_id: String,
+        delivery_target: String,
+    ) -> Result<Option<Delivery>, diesel::result::Error> {
+        use self::schema::deliveries::dsl::deliveries as d;
+
+        with_conn!(self, |conn| d
+            .find((delivery_comment_id, delivery_target))
+            .first(conn)
+            .optional())
+    }
+
+    pub fn record_delivery_attempt(
+        &self,
+        delivery_comment_id: String,
+        delivery_target: String,
+        error: Option<String>,
+        max_attempts: i32,
+    ) -> Result<Option<Delivery>, diesel::result::Error> {
+        use self::schema::deliveries::dsl::{
+            attempts, deliveries as d, last_error, status, updated_at,
+        };
+
+        let delivery: Option<Delivery> = with_conn!(self, |conn| d
+            .find((&delivery_comment_id, &delivery_target))
+            .first(conn)
+            .optional())?;
+
+        let delivery = match delivery {
+            Some(delivery) if delivery.status == DELIVERY_PENDING => delivery,
+            _ => return Ok(None),
+        };
+        let delivery = Delivery {
+            status: match &error {
+                None => DELIVERY_DONE.to_string(),
+                Some(_) if delivery.attempts + 1 >= max_attempts => DELIVERY_DEAD.to_string(),
+                Some(_) => DELIVERY_PENDING.to_string(),
+            },
+            attempts: delivery.attempts + 1,
+            last_error: error,
+            updated_at: Utc::now().naive_utc(),
+            ..delivery
+        };
+
+        with_conn!(self, |conn| diesel::update(
+            d.find((&delivery.comment_id, &delivery.target))
+        )
+        .set((
+            status.eq(&delivery.status),
+            attempts.eq(delivery.attempts),
+            last_error.eq(&delivery.last_error),
+            updated_at.eq(delivery.updated_at),
+        ))
+        .execute(conn))?;
+
+        Ok(Some(delivery))
+    }
+
+    pub fn skip_deliveries(
+        &self,
+        delivery_comment_id: String,
+    ) -> Result<(), diesel::result::Error> {
+        use self::schema::deliveries::dsl::{comment_id, deliveries as d, status, updated_at};
+
+        with_conn!(self, |conn| diesel::update(
+            d.filter(comment_id.eq(&delivery_comment_id))
+                .filter(status.eq(DELIVERY_PENDING))
+        )
+        .set((
+            status.eq(DELIVERY_DONE),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn))
+        .map(|_| ())
+    }
+
+    pub fn run_maintenance(&self) -> Result<MaintenanceReport, diesel::result::Error> {
+        #[allow(clippy::infallible_destructuring_match)]
+        let pooled = match &self.conn {
+            StorageConnection::Sqlite(pool) => pool.get()?,
+            #[cfg(feature = "mysql")]
+            StorageConnection::Mysql(pool) => {
+                let conn = &*pool.get()?;
+
+                diesel::sql_query(format!("ANALYZE TABLE {}", MYSQL_TABLES)).execute(conn)?;
+                diesel::sql_query(format!("OPTIMIZE TABLE {}", MYSQL_TABLES)).execute(conn)?;
+
+                return Ok(MaintenanceReport::new(Vec::new(), false));
+            }
+            #[cfg(feature = "postgres")]
+            StorageConnection::Postgres(pool) => {
+                diesel::sql_query("VACUUM ANALYZE").execute(&*pool.get()?)?;
+
+                return Ok(MaintenanceReport::new(Vec::new(), false));
+            }
+        };
+        let conn = &*pooled;
+
+        let integrity = diesel::sql_query("PRAGMA integrity_check")
+            .load::<maintenance::IntegrityCheckRow>(conn)?
+            .into_iter()
+            .map(|row| row.integrity_check)
+            .collect::<Vec<String>>();
+
+        let report = MaintenanceReport::new(integrity, false);
+
+        if !report.is_healthy() {
+            return Ok(report);
+        }
+
+        diesel::sql_query("ANALYZE").execute(conn)?;
+
+        let auto_vacuum = diesel::sql_query("PRAGMA auto_vacuum")
+            .load::<maintenance::AutoVacuumRow>(conn)?
+            .into_iter()
+            .next()
+            .map(|row| row.auto_vacuum)
+            .unwrap_or_default();
+
+        let converted_to_incremental = auto_vacuum != 2;
+
+        if converted_to_incremental {
+            diesel::sql_query("PRAGMA auto_vacuum = INCREMENTAL").execute(conn)?;
+            diesel::sql_query("VACUUM").execute(conn)?;
+        } else {
+            diesel::sql_query("PRAGMA incremental_vacuum").execute(conn)?;
+        }
+
+        Ok(MaintenanceReport {
+            converted_to_incremental,
+            ..report
+        })
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn explain(&self, query: &str) -> Result<Vec<String>, diesel::result::Error> {
+        match &self.conn {
+            StorageConnection::Sqlite(pool) => {
+                diesel::sql_query(format!("EXPLAIN QUERY PLAN {}", query))
+                    .load::<maintenance::QueryPlanRow>(&*pool.get()?)
+                    .map(|rows| rows.into_iter().map(|row| row.detail).collect())
+            }
+            #[cfg(feature = "mysql")]
+            StorageConnection::Mysql(pool) => diesel::sql_query(format!("EXPLAIN {}", query))
+                .load::<maintenance::MysqlQueryPlanRow>(&*pool.get()?)
+                .map(|rows| rows.into_iter().map(|row| row.detail()).collect()),
+            #[cfg(feature = "postgres")]
+            StorageConnection::Postgres(pool) => diesel::sql_query(format!("EXPLAIN {}", query))
+                .load::<maintenance::PostgresQueryPlanRow>(&*pool.get()?)
+                .map(|rows| rows.into_iter().map(|row| row.query_plan).collect()),
+        }
+    }
+
+    pub fn get_latest_comment_timestamp(
+        &self,
+    ) -> Result<Option<NaiveDateTime>, diesel::result::Error> {
+        use self::schema::comments::dsl::{comments as c, timestamp};
+
+        with_conn!(self, |conn| c
+            .select(diesel::dsl::max(timestamp))
+            .first(conn))
+    }
+
+    pub fn get_latest_claim_comment_timestamps(
+        &self,
+    ) -> Result<Vec<(String, NaiveDateTime)>, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c};
+
+        with_conn!(self, |conn| c
+            .group_by(claim_id)
+            .select((
+                claim_id,
+                diesel::dsl::sql::<diesel::sql_types::Timestamp>("MAX(timestamp)"),
+            ))
+            .load(conn))
+    }
+
+    pub fn get_known_claims(&self) -> Result<Vec<(Account, Claim)>, diesel::result::Error> {
+        use self::schema::comments::dsl::{
+            account_id, claim_canonical_url, claim_id, claim_name, claim_thumbnail_url,
+            claim_title, comments as c,
+        };
+
+        let rows = match &self.conn {
+            StorageConnection::Sqlite(pool) => c
+                .group_by(claim_id)
+                .select((
+                    account_id,
+                    claim_id,
+                    claim_name,
+                    claim_title,
+                    claim_thumbnail_url,
+                    claim_canonical_url,
+                    diesel::dsl::sql::<diesel::sql_types::Timestamp>("MAX(timestamp)"),
+                ))
+                .load::<KnownClaimRow>(&*pool.get()?)?,
+            #[cfg(feature = "mysql")]
+            StorageConnection::Mysql(pool) => {
+                use diesel::{
+                    dsl::sql,
+                    sql_types::{Nullable, Text, Timestamp},
+                };
+
+                let latest = |column: &str| {
+                    format!(
+                        "SUBSTRING_INDEX(GROUP_CONCAT({} ORDER BY timestamp DESC SEPARATOR '\\n'), '\\n', 1)",
+                        column
+                    )
+                };
+
+                c.group_by(claim_id)
+                    .select((
+                        sql::<Text>(&latest("account_id")),
+                        claim_id,
+                        sql::<Text>(&latest("claim_name")),
+                        sql::<Nullable<Text>>(&latest("claim_title")),
+                        sql::<Nullable<Text>>(&latest("claim_thumbnail_url")),
+                        sql::<Nullable<Text>>(&latest("claim_canonical_url")),
+                        sql::<Timestamp>("MAX(timestamp)"),
+                    ))
+                    .load::<KnownClaimRow>(&*pool.get()?)?
+            }
+            #[cfg(feature = "postgres")]
+            StorageConnection::Postgres(pool) => {
+                use diesel::{
+                    dsl::sql,
+                    sql_types::{Nullable, Text, Timestamp},
+                };
+
+                c.group_by(claim_id)
+                    .select((
+                        sql::<Text>("(ARRAY_AGG(account_id ORDER BY timestamp DESC))[1]"),
+                        claim_id,
+                        sql::<Text>("(ARRAY_AGG(claim_name ORDER BY timestamp DESC))[1]"),
+                        sql::<Nullable<Text>>(
+                            "(ARRAY_AGG(claim_title ORDER BY timestamp DESC))[1]",
+                        ),
+                        sql::<Nullable<Text>>(
+                            "(ARRAY_AGG(claim_thumbnail_url ORDER BY timestamp DESC))[1]",
+                        ),
+                        sql::<Nullable<Text>>(
+                            "(ARRAY_AGG(claim_canonical_url ORDER BY timestamp DESC))[1]",
+                        ),
+                        sql::<Timestamp>("MAX(timestamp)"),
+                    ))
+                    .load::<KnownClaimRow>(&*pool.get()?)?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(account, id, name, title, thumbnail_url, canonical_url, timestamp)| {
+                    (
+                        Account {
+                            id: account,
+                            name: String::new(),
+                            is_default: false,
+                        },
+                        Claim {
+                            id,
+                            name,
+                            timestamp: DateTime::from_utc(timestamp, Utc),
+                            metadata: ClaimMetadata {
+                                title,
+                                thumbnail_url,
+                                canonical_url,
+                                ..ClaimMetadata::default()
+                            },
+                        },
+                    )
+                },
+            )
+            .collect())
+    }
+
+    pub fn get_claim_comment_ids(&self) -> Result<Vec<(String, String)>, diesel::result::Error> {
+        use self::schema::comments::dsl::{claim_id, comments as c, id};
+
+        with_conn!(self, |conn| c.select((claim_id, id)).load(conn))
+    }
+
+    pub fn get_comment_timestamps_since(
+        &self,
+        since: NaiveDateTime,
+    ) -> Result<Vec<(String, NaiveDateTime)>, diesel::result::Error> {
+        use self::schema::comments::dsl::{account_id, comments as c, timestamp};
+
+        with_conn!(self, |conn| c
+            .select((account_id, timestamp))
+            .filter(timestamp.ge(since))
+            .load(conn))
+    }
+
+    pub fn transaction<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: From<diesel::result::Error>,
+    {
+        match &self.conn {
+            StorageConnection::Sqlite(pool) => pool.transaction(f),
+            #[cfg(feature = "mysql")]
+            StorageConnection::Mysql(pool) => pool.transaction(f),
+            #[cfg(feature = "postgres")]
+            StorageConnection::Postgres(pool) => pool.transaction(f),
+        }
+    }
+
+    pub fn test_transaction<T, E, F>(&self, f: F) -> T
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: std::fmt::Debug,
+    {
+        let mut user_result = None;
+        let _ = self.transaction::<(), diesel::result::Error, _>(|| {
+            user_result = Some(f());
+
+            Err(diesel::result::Error::RollbackTransaction)
+        });
+
+        user_result
+            .expect("Transaction did not run")
+            .expect("Transaction did not succeed")
+    }
+}
+
+#[cfg(feature = "email")]
+#[derive(Clone, Debug)]
+pub struct Emails {
+    from: String,
+    to: String,
+    similarity: Option<f64>,
+    recipients: Vec<Recipient>,
+    channel_recipients: Arc<RwLock<Vec<Recipient>>>,
+    collections: Arc<RwLock<ClaimCollections>>,
+    flag_hidden: bool,
+    flag_unverified: bool,
+    #[cfg(feature = "read-links")]
+    read_links: Option<ReadLinks>,
+}
+
+#[cfg(feature = "email")]
+const DIGEST_HEADER_BYTES: usize = 1024;
+
+#[cfg(feature = "email")]
+fn reply_label(recipient: &Recipient, reply_to: Option<&CommentEntity>) -> Option<String> {
+    reply_to.map(|parent| {
+        format!(
+            "{} {}",
+            recipient.translate("Reply to"),
+            parent.commenter_name
+        )
+    })
+}
+
+#[cfg(feature = "email")]
+impl Emails {
+    pub fn new(from: String, to: String) -> Self {
+        Self {
+            from,
+            to,
+            similarity: None,
+            recipients: Vec::new(),
+            channel_recipients: Arc::new(RwLock::new(Vec::new())),
+            collections: Arc::new(RwLock::new(ClaimCollections::default())),
+            flag_hidden: false,
+            flag_unverified: false,
+            #[cfg(feature = "read-links")]
+            read_links: None,
+        }
+    }
+
+    pub fn with_similarity(mut self, threshold: f64) -> Self {
+        self.similarity = Some(threshold);
+        self
+    }
+
+    pub fn with_recipients(self, recipients: Vec<Recipient>) -> Self {
+        Self { recipients, ..self }
+    }
+
+    pub fn with_hidden_flag(self, flag_hidden: bool) -> Self {
+        Self {
+            flag_hidden,
+            ..self
+        }
+    }
+
+    pub fn with_unverified_flag(self, flag_unverified: bool) -> Self {
+        Self {
+            flag_unverified,
+            ..self
+        }
+    }
+
+    #[cfg(feature = "read-links")]
+    pub fn with_read_links(self, read_links: Option<ReadLinks>) -> Self {
+        Self { read_links, ..self }
+    }
+
+    #[cfg(feature = "read-links")]
+    fn read_link(
+        &self,
+        recipient: &Recipient,
+        label: &'static str,
+        comment_ids: &[String],
+    ) -> String {
+        self.read_links
+            .as_ref()
+            .map(|links| {
+                format!(
+                    "      ---\n      {}: {}\n",
+                    recipient.translate(label),
+                    links.url(comment_ids)
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "read-links"))]
+    fn read_link(
+        &self,
+        _recipient: &Recipient,
+        _label: &'static str,
+        _comment_ids: &[String],
+    ) -> String {
+        "".to_string()
+    }
+
+    pub fn set_collections(&self, collections: ClaimCollections) {
+        *self.collections.write().expect("Unable to get lock") = collections;
+    }
+
+    pub fn set_channel_recipients(&self, recipients: Vec<Recipient>) {
+        *self.channel_recipients.write().expect("Unable to get lock") = recipients;
+    }
+
+    pub fn recipient_addresses(&self) -> Vec<String> {
+        self.recipients()
+            .into_iter()
+            .map(|recipient| recipient.address)
+            .collect()
+    }
+
+    fn recipients(&self) -> Vec<Recipient> {
+        let mut recipients = if self.recipients.is_empty() {
+            vec![Recipient::new(self.to.to_string())]
+        } else {
+            self.recipients.clone()
+        };
+
+        self.channel_recipients
+            .read()
+            .expect("Unable to get lock")
+            .iter()
+            .for_each(|recipient| {
+                if !recipients
+                    .iter()
+                    .any(|existing| existing.address == recipient.address)
+                {
+                    recipients.push(recipient.clone());
+                }
+            });
+
+        recipients
+    }
+
+    fn addressed(&self, builder: EmailBuilder) -> EmailBuilder {
+        self.recipients()
+            .into_iter()
+            .fold(builder, |builder, recipient| builder.to(recipient.address))
+    }
+
+    pub fn notification_email(&self, comment: CommentEntity) -> Email {
+        self.localized_notification_email(
+            &Recipient::new(self.to.to_string()),
+            &comment,
+            None,
+            None,
+            None,
+        )
+    }
+
+    pub fn notification_emails(
+        &self,
+        comment: &CommentEntity,
+        claim: Option<&ClaimEntity>,
+        reply_to: Option<&CommentEntity>,
+        translation: Option<&Translation>,
+    ) -> Vec<Email> {
+        self.recipients()
+            .iter()
+            .map(|recipient| {
+                self.localized_notification_email(recipient, comment, claim, reply_to, translation)
+            })
+            .collect()
+    }
+
+    fn notification_subject(
+        &self,
+        recipient: &Recipient,
+        comment: &CommentEntity,
+        reply_to: Option<&CommentEntity>,
+    ) -> String {
+        format!(
+            "{}{}{}{}{}{} {} {} {}",
+            if self.flag_unverified && !comment.has_valid_signature() {
+                format!("[{}] ", recipient.translate("Unverified"))
+            } else {
+                "".to_string()
+            },
+            reply_label(recipient, reply_to)
+                .map(|label| format!("[{}] ", label))
+                .unwrap_or_default(),
+            if self.flag_hidden && comment.is_hidden {
+                format!("[{}] ", recipient.translate("Hidden"))
+            } else {
+                "".to_string()
+            },
+            if comment.source != "lbry" {
+                format!("[{}] ", comment.source)
+            } else {
+                "".to_string()
+            },
+            if comment.support_amount > 0.0 {
+                format!("[{} LBC] ", comment.support_amount)
+            } else {
+                "".to_string()
+            },
+            recipient.translate("New Comment from"),
+            comment.commenter_name,
+            recipient.translate("on"),
+            comment.claim_display_name()
+        )
+    }
+
+    fn localized_notification_email(
+        &self,
+        recipient: &Recipient,
+        comment: &CommentEntity,
+        claim: Option<&ClaimEntity>,
+        reply_to: Option<&CommentEntity>,
+        translation: Option<&Translation>,
+    ) -> Email {
+        let web_url = Some(comment.claim_web_url());
+        let claim_links = vec![
+            &comment.claim_canonical_url,
+            &web_url,
+            &comment.claim_thumbnail_url,
+        ]
+        .into_iter()
+        .filter_map(|link| link.as_ref())
+        .map(|link| format!("\n      {}", link))
+        .collect::<String>();
+        let claim_stats = claim
+            .map(|claim| {
+                vec![
+                    claim
+                        .view_count
+                        .map(|count| format!("{} {}", count, recipient.translate("views"))),
+                    claim
+                        .effective_amount
+                        .map(|amount| format!("{} LBC", amount)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>()
+                .join(" | ")
+            })
+            .filter(|stats| !stats.is_empty())
+            .map(|stats| format!("\n      {}", stats))
+            .unwrap_or_default();
+        let reply_label = reply_label(recipient, reply_to);
+        let reply_quote = reply_to
+            .map(|parent| {
+                format!(
+                    "\n      {}\n      > {}\n",
+                    reply_label.as_deref().unwrap_or_default(),
+                    parent
+                        .comment
+                        .lines()
+                        .collect::<Vec<&str>>()
+                        .join("\n      > ")
+                )
+            })
+            .unwrap_or_default();
+        let translated = translation
+            .map(|translation| {
+                format!(
+                    "\n      ---\n      {} {}\n      {}",
+                    recipient.translate("Translated from"),
+                    translation.language,
+                    translation.text
+                )
+            })
+            .unwrap_or_default();
+
+        EmailBuilder::new()
+            .to(recipient.address.to_string())
+            .from(self.from.to_string())
+            .header(("X-Comment-Source", comment.source.as_str()))
+            .subject(self.notification_subject(recipient, comment, reply_to))
+            .text(format!(
+                "
+      {}{}{}
+      ---
+{}
+      {} ({})
+      {}
+      ===
+      {}{}
+{}",
+                comment.claim_display_name(),
+                claim_links,
+                claim_stats,
+                reply_quote,
+                comment.commenter_name,
+                comment.commenter_url,
+                recipient.format_timestamp(comment.timestamp),
+                comment.comment,
+                translated,
+                self.read_link(recipient, "Mark as read", &[comment.id.clone()])
+            ))
+            .build()
+            .expect("Could not build email")
+    }
+
+    pub fn template_notification_email(
+        &self,
+        template: &Template,
+        variant: &str,
+        comment: &CommentEntity,
+    ) -> Email {
+        let (subject, text) = template.render(comment);
+
+        self.addressed(EmailBuilder::new())
+            .from(self.from.to_string())
+            .header(("X-Notification-Variant", variant))
+            .header(("X-Comment-Source", comment.source.as_str()))
+            .subject(subject)
+            .text(text)
+            .build()
+            .expect("Could not build email")
+    }
+
+    pub fn source_notification_email(&self, template: &Template, comment: &CommentEntity) -> Email {
+        let (subject, text) = template.render(comment);
+
+        self.addressed(EmailBuilder::new())
+            .from(self.from.to_string())
+            .header(("X-Comment-Source", comment.source.as_str()))
+            .subject(subject)
+            .text(text)
+            .build()
+            .expect("Could not build email")
+    }
+
+    fn digest_emails_titled(
+        &self,
+        title: &'static str,
+        comments: Vec<CommentEntity>,
+        order: DigestOrder,
+        max_bytes: usize,
+        with_read_link: bool,
+    ) -> Vec<Email> {
+        let comments = order_comments(comments, order);
+        let comment_ids = comments
+            .iter()
+            .map(|comment| comment.id.clone())
+            .collect::<Vec<String>>();
+        let collections = self.collections.read().expect("Unable to get lock");
+
+        self.recipients()
+            .into_iter()
+            .flat_map(|recipient| {
+                let comments = recipient.localize_comments(&comments);
+                let parts = split_collection_digest(
+                    &comments,
+                    order,
+                    max_bytes.saturating_sub(DIGEST_HEADER_BYTES),
+                    self.similarity,
+                    &collections,
+                );
+                let total_parts = parts.len();
+                let read_link = if with_read_link {
+                    self.read_link(&recipient, "Mark all as read", &comment_ids)
+                } else {
+                    "".to_string()
+                };
+
+                parts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, part)| {
+                        let subject = if total_parts > 1 {
+                            format!(
+                                "{} {} ({} {}/{})",
+                                comments.len(),
+                                recipient.translate(title),
+                                recipient.translate("Digest"),
+                                index + 1,
+                                total_parts
+                            )
+                        } else {
+                            format!("{} {}", comments.len(), recipient.translate(title))
+                        };
+
+                        EmailBuilder::new()
+                            .to(recipient.address.to_string())
+                            .from(self.from.to_string())
+                            .subject(subject)
+                            .text(format!("{}{}", part, read_link))
+                            .build()
+                            .expect("Could not build email")
+                    })
+                    .collect::<Vec<Email>>()
+            })
+            .collect::<Vec<Email>>()
+    }
+
+    pub fn digest_emails(
+        &self,
+        comments: Vec<CommentEntity>,
+        order: DigestOrder,
+        max_bytes: usize,
+    ) -> Vec<Email> {
+        self.digest_emails_titled("New Comments", comments, order, max_bytes, false)
+    }
+
+    pub fn catch_up_emails(
+        &self,
+        comments: Vec<CommentEntity>,
+        order: DigestOrder,
+        max_bytes: usize,
+    ) -> Vec<Email> {
+        self.digest_emails_titled("Older Comments", comments, order, max_bytes, false)
+    }
+
+    pub fn away_emails(&self, comments: &[CommentEntity], since: NaiveDateTime) -> Vec<Email> {
+        let summaries = summarize_claims(comments);
+
+        self.recipients()
+            .into_iter()
+            .map(|recipient| {
+                EmailBuilder::new()
+                    .to(recipient.address.to_string())
+                    .from(self.from.to_string())
+                    .subject(format!(
+                        "{}: {} {}",
+                        recipient.translate("While You Were Away"),
+                        comments.len(),
+                        recipient.translate("New Comments")
+                    ))
+                    .text(format!(
+                        "
+      {} {}
+      ---
+{}",
+                        recipient.translate("Since"),
+                        recipient.format_timestamp(since),
+                        render_claim_summaries(&summaries)
+                    ))
+                    .build()
+                    .expect("Could not build email")
+            })
+            .collect::<Vec<Email>>()
+    }
+
+    pub fn reminder_emails(
+        &self,
+        comments: Vec<CommentEntity>,
+        order: DigestOrder,
+        max_bytes: usize,
+    ) -> Vec<Email> {
+        self.digest_emails_titled("Unread Comments", comments, order, max_bytes, true)
+    }
+
+    fn localized_emails<F>(&self, recipients: Vec<Recipient>, render: F) -> Vec<Email>
+    where
+        F: Fn(&Recipient) -> (String, String),
+    {
+        recipients
+            .iter()
+            .map(|recipient| {
+                let (subject, text) = render(recipient);
+
+                EmailBuilder::new()
+                    .to(recipient.address.to_string())
+                    .from(self.from.to_string())
+                    .subject(subject)
+                    .text(text)
+                    .build()
+                    .expect("Could not build email")
+            })
+            .collect()
+    }
+
+    pub fn search_emails(&self, search: &SavedSearch, comment: &CommentEntity) -> Vec<Email> {
+        let recipients = match &search.recipient {
+            Some(address) => vec![Recipient::new(address.to_string())],
+            None => self.recipients(),
+        };
+
+        self.localized_emails(recipients, |recipient| {
+            (
+                format!(
+                    "[{}] {} {} {} {}",
+                    search.name,
+                    recipient.translate("New Comment from"),
+                    comment.commenter_name,
+                    recipient.translate("on"),
+                    comment.claim_display_name()
+                ),
+                format!(
+                    "
+      {} {}
+      ---
+
+      {} ({})
+      {}
+      ===
+      {}
+",
+                    recipient.translate("Matched"),
+                    search.query,
+                    comment.commenter_name,
+                    comment.commenter_url,
+                    recipient.format_timestamp(comment.timestamp),
+                    comment.comment
+                ),
+            )
+        })
+    }
+
+    pub fn throttled_emails(
+        &self,
+        throttled: ThrottledCommenter,
+        order: DigestOrder,
+    ) -> Vec<Email> {
+        let ThrottledCommenter {
+            commenter_name,
+            comments,
+        } = throttled;
+        let comments = order_comments(comments, order);
+        let collections = self.collections.read().expect("Unable to get lock");
+
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!(
+                    "+{} {} {}",
+                    comments.len(),
+                    recipient.translate("more from"),
+                    commenter_name
+                ),
+                split_collection_digest(
+                    &recipient.localize_comments(&comments),
+                    order,
+                    usize::MAX,
+                    self.similarity,
+                    &collections,
+                )
+                .concat(),
+            )
+        })
+    }
+
+    pub fn maintenance_emails(&self, status: &str, details: &str) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!("{} {}", recipient.translate("Database maintenance"), status),
+                format!(
+                    "
+      {} {}
+      ---
+
+      {}
+",
+                    recipient.translate("Database maintenance finished with status"),
+                    status,
+                    details
+                ),
+            )
+        })
+    }
+
+    pub fn ping_emails(&self, comment: &CommentEntity) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!(
+                    "Ping: {} {} {}",
+                    comment.commenter_name,
+                    recipient.translate("commented on"),
+                    comment.claim_display_name()
+                ),
+                "".to_string(),
+            )
+        })
+    }
+
+    pub fn preflight_emails(&self, problems: &[String]) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                recipient
+                    .translate("SDK is not ready, skipping comment checks")
+                    .to_string(),
+                format!(
+                    "
+      {}
+      ---
+
+      {}
+",
+                    recipient.translate("SDK is not ready"),
+                    problems.join("\n      ")
+                ),
+            )
+        })
+    }
+
+    pub fn disk_space_emails(&self, problems: &[String]) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                recipient
+                    .translate("Disk space is low, pausing comment ingestion")
+                    .to_string(),
+                format!(
+                    "
+      {}
+      ---
+
+      {}
+",
+                    recipient.translate("Disk space is low"),
+                    problems.join("\n      ")
+                ),
+            )
+        })
+    }
+
+    pub fn heartbeat_emails(&self, since: Option<NaiveDateTime>) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            let since = since
+                .map(|since| recipient.format_timestamp(since))
+                .unwrap_or(recipient.translate("the beginning").to_string());
+
+            (
+                format!(
+                    "{} {}",
+                    recipient.translate("Still alive, 0 new comments since"),
+                    since
+                ),
+                format!(
+                    "
+      {}
+      ---
+
+      {} {}.
+",
+                    recipient.translate("Still alive"),
+                    recipient
+                        .translate("The notifier is running but no new comments arrived since"),
+                    since
+                ),
+            )
+        })
+    }
+
+    pub fn report_email(
+        &self,
+        recipients: &[String],
+        report: &MonthlyReport,
+        template: &str,
+    ) -> Email {
+        let rendered = report.render(template);
+        let mut lines = rendered.splitn(2, '\n');
+        let subject = lines.next().unwrap_or_default().trim().to_string();
+        let text = lines.next().unwrap_or_default().to_string();
+
+        let builder = if recipients.is_empty() {
+            self.addressed(EmailBuilder::new())
+        } else {
+            recipients
+                .iter()
+                .fold(EmailBuilder::new(), |builder, recipient| {
+                    builder.to(recipient.to_string())
+                })
+        };
+
+        builder
+            .from(self.from.to_string())
+            .subject(subject)
+            .text(text)
+            .build()
+            .expect("Could not build email")
+    }
+
+    pub fn reaction_emails(&self, changes: &[(ReactionChange, CommentEntity)]) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            let text = changes
+                .iter()
+                .map(|(change, comment)| {
+                    format!(
+                        "
+      {} ({}) {} {}
+      +{} {}, +{} {}
+      ===
+      {}
+",
+                        comment.commenter_name,
+                        comment.commenter_url,
+                        recipient.translate("on"),
+                        comment.claim_display_name(),
+                        change.likes,
+                        recipient.translate("likes"),
+                        change.dislikes,
+                        recipient.translate("dislikes"),
+                        comment.comment
+                    )
+                })
+                .collect::<String>();
+
+            (
+                format!(
+                    "{} {} {}",
+                    recipient.translate("New Reactions on"),
+                    changes.len(),
+                    recipient.translate("Comments")
+                ),
+                text,
+            )
+        })
+    }
+
+    pub fn milestone_emails(&self, comment: &CommentEntity, milestone: i64) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!(
+                    "{} {} {} {}",
+                    comment.commenter_name,
+                    recipient.translate("reached"),
+                    milestone,
+                    recipient.translate("comments")
+                ),
+                format!(
+                    "
+      {} ({}) {} #{} {} {}
+      ===
+      {}
+",
+                    comment.commenter_name,
+                    comment.commenter_url,
+                    recipient.translate("posted their comment"),
+                    milestone,
+                    recipient.translate("on"),
+                    comment.claim_display_name(),
+                    comment.comment
+                ),
+            )
+        })
+    }
+
+    pub fn support_emails(&self, support: &SupportEntity) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!(
+                    "{} LBC {} {} {}",
+                    support.amount,
+                    recipient.translate(if support.is_tip { "tip" } else { "support" }),
+                    recipient.translate("on"),
+                    support.claim_name
+                ),
+                format!(
+                    "
+      {}
+      ---
+
+      {} LBC ({})
+",
+                    support.claim_name, support.amount, support.id
+                ),
+            )
+        })
+    }
+
+    pub fn transaction_emails(&self, transaction: &TransactionEntity) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            let date = transaction
+                .timestamp
+                .map(|timestamp| recipient.format_timestamp(timestamp))
+                .unwrap_or(recipient.translate("Unconfirmed").to_string());
+
+            (
+                format!(
+                    "{} {} LBC",
+                    recipient.translate("Received"),
+                    transaction.amount
+                ),
+                format!(
+                    "
+      {}
+      ---
+
+      {}: {} LBC
+      {}: {}
+      {}: {}
+      {}: {}
+",
+                    recipient.translate("Incoming transaction"),
+                    recipient.translate("Amount"),
+                    transaction.amount,
+                    recipient.translate("Date"),
+                    date,
+                    recipient.translate("Transaction"),
+                    transaction.txid,
+                    recipient.translate("Account"),
+                    transaction.account_id
+                ),
+            )
+        })
+    }
+
+    pub fn anomaly_emails(&self, anomaly: &Anomaly) -> Vec<Email> {
+        self.localized_emails(self.recipients(), |recipient| {
+            (
+                format!(
+                    "{} {} {} {} {}",
+                    recipient.translate(if anomaly.is_spike() {
+                        "Comment spike"
+                    } else {
+                        "Comment drop"
+                    }),
+                    recipient.translate("on"),
+                    anomaly.day,
+                    recipient.translate("for"),
+                    anomaly.account_id
+                ),
+                format!(
+                    "
+      {} {} {}
+      ---
+
+      {}: {:.2}
+      {}: {:.2}
+      Z-Score: {:.2}
+",
+                    anomaly.count,
+                    recipient.translate("comments on"),
+                    anomaly.day,
+                    recipient.translate("Mean"),
+                    anomaly.mean,
+                    recipient.translate("Standard Deviation"),
+                    anomaly.stddev,
+                    anomaly.z_score
+                ),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use futures::{future, stream::StreamExt};
+    use rand::seq::SliceRandom;
+    use std::{collections::HashSet, time::Duration};
+    use tokio::time::delay_for;
+
+    use crate::{
+        email_delivery_target, parse_payload, testing, try_stream_paginated, Account, Api,
+        ApiError, Claim, ClaimEntity, ClaimMetadata, Comment, Delivery, PaginatedApiResult,
+        Storage, Support, Transaction, DELIVERY_DEAD, DELIVERY_DONE, DELIVERY_EMAIL,
+        DELIVERY_PENDING,
+    };
+    #[cfg(feature = "email")]
+    use crate::{
+        render_digest, CommentEntity, DigestOrder, Emails, Language, Recipient, ThrottledCommenter,
+        Translation, DIGEST_HEADER_BYTES,
+    };
+
+    const TEST_DB: &str = "test.db";
+    const TEST_URL: &str = "http://localhost:5279";
+
+    #[test]
+    fn storage_should_work() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+        #[cfg(feature = "email")]
+        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string());
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let account = Account {
+                id: "id".to_string(),
+                name: "name".to_string(),
+                is_default: true,
+            };
+
+            let claim = Claim {
+                id: "id".to_string(),
+                name: "name".to_string(),
+                timestamp: Utc::now(),
+                metadata: ClaimMetadata::default(),
+            };
+
+            let comment = Comment {
+                id: "id".to_string(),
+                claim_id: "claim_id".to_string(),
+                parent_id: None,
+                comment: "comment".to_string(),
+                commenter_id: "commenter_id".to_string(),
+                commenter_name: "commenter_name".to_string(),
+                commenter_url: "commenter_url".to_string(),
+                is_hidden: false,
+                timestamp: Utc::now(),
+                support_amount: 0.0,
+                source: "lbry".to_string(),
+                is_signature_valid: None,
+            };
+
+            let saved_comment = storage
+                .save_comment(account, claim, comment)
+                .expect("Unable to save");
+
+            let entity = storage
+                .get_comment_by_id(saved_comment.id)
+                .expect("Unable to fetch");
+
+            assert_eq!(entity.comment, "comment");
+            assert_eq!(entity.claim_name, "name");
+            #[cfg(feature = "email")]
+            assert_eq!(
+                emails.notification_subject(
+                    &Recipient::new("to@mail.com".to_string()),
+                    &entity,
+                    None
+                ),
+                "New Comment from commenter_name on name"
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_track_latest_comment_timestamps() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            for &(id, claim_id, day) in &[("old", "first", 1), ("new", "second", 2)] {
+                let comment = Comment {
+                    timestamp: Utc.ymd(2020, 8, day).and_hms(0, 0, 0),
+                    ..testing::comment(id, claim_id)
+                };
+
+                storage
+                    .save_comment(testing::account("id"), testing::claim(claim_id), comment)
+                    .expect("Unable to save");
+            }
+
+            let mut timestamps = storage
+                .get_latest_claim_comment_timestamps()
+                .expect("Unable to fetch");
+
+            timestamps.sort();
+
+            assert_eq!(
+                storage.get_latest_comment_timestamp(),
+                Ok(Some(NaiveDate::from_ymd(2020, 8, 2).and_hms(0, 0, 0)))
+            );
+            assert_eq!(
+                timestamps,
+                vec![
+                    (
+                        "first".to_string(),
+                        NaiveDate::from_ymd(2020, 8, 1).and_hms(0, 0, 0)
+                    ),
+                    (
+                        "second".to_string(),
+                        NaiveDate::from_ymd(2020, 8, 2).and_hms(0, 0, 0)
+                    ),
+                ]
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_list_known_claims() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let claim = Claim {
+                metadata: ClaimMetadata {
+                    title: Some("Title".to_string()),
+                    ..ClaimMetadata::default()
+                },
+                ..testing::claim("claim_id")
+            };
+
+            storage
+                .save_comment(
+                    testing::account("account_id"),
+                    claim,
+                    testing::comment("id", "claim_id"),
+                )
+                .expect("Unable to save");
+
+            let known_claims = storage.get_known_claims().expect("Unable to fetch");
+
+            assert_eq!(known_claims.len(), 1);
+            assert_eq!(known_claims[0].0.id, "account_id");
+            assert_eq!(known_claims[0].1.id, "claim_id");
+            assert_eq!(known_claims[0].1.name, "claim_id");
+            assert_eq!(known_claims[0].1.metadata.title, Some("Title".to_string()));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_list_claim_comment_ids() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            storage
+                .save_comment(
+                    testing::account("id"),
+                    testing::claim("claim_id"),
+                    testing::comment("id", "claim_id"),
+                )
+                .expect("Unable to save");
+
+            assert_eq!(
+                storage.get_claim_comment_ids(),
+                Ok(vec![("claim_id".to_string(), "id".to_string())])
+            );
+
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn digest_emails_should_leave_room_for_headers() {
+        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string());
+        let comments = vec![
+            testing::comment_entity("first", "claim_id"),
+            testing::comment_entity("second", "claim_id"),
+        ];
+        let body_bytes = render_digest(&comments, DigestOrder::Chronological).len();
+        let digest_emails = |max_bytes| {
+            emails
+                .digest_emails(comments.clone(), DigestOrder::Chronological, max_bytes)
+                .len()
+        };
+
+        assert_eq!(digest_emails(body_bytes + DIGEST_HEADER_BYTES), 1);
+        assert_eq!(digest_emails(body_bytes + DIGEST_HEADER_BYTES - 1), 2);
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn notification_subject_should_label_replies() {
+        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string());
+        let recipient = Recipient::new("to@mail.com".to_string());
+        let parent = CommentEntity {
+            commenter_name: "@owner".to_string(),
+            ..testing::comment_entity("parent", "claim_id")
+        };
+        let reply = CommentEntity {
+            parent_id: Some("parent".to_string()),
+            ..testing::comment_entity("reply", "claim_id")
+        };
+        let translation = Translation {
+            language: "es".to_string(),
+            text: "Hola".to_string(),
+        };
+
+        assert_eq!(
+            emails.notification_subject(&recipient, &reply, Some(&parent)),
+            "[Reply to @owner] New Comment from @commenter on my-video"
+        );
+        assert_eq!(
+            emails.notification_subject(&recipient, &reply, None),
+            "New Comment from @commenter on my-video"
+        );
+        assert_eq!(
+            emails
+                .notification_emails(&reply, None, Some(&parent), Some(&translation))
+                .len(),
+            1
+        );
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn status_emails_should_reach_every_recipient() {
+        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string())
+            .with_recipients(vec![
+                Recipient::new("first@mail.com".to_string()),
+                Recipient {
+                    language: Language::Spanish,
+                    ..Recipient::new("second@mail.com".to_string())
+                },
+            ]);
+        let throttled = ThrottledCommenter {
+            commenter_name: "@commenter".to_string(),
+            comments: vec![testing::comment_entity("comment_id", "claim_id")],
+        };
+
+        assert_eq!(
+            emails
+                .throttled_emails(throttled, DigestOrder::Chronological)
+                .len(),
+            2
+        );
+        assert_eq!(emails.heartbeat_emails(None).len(), 2);
+    }
+
+    #[test]
+    fn storage_should_dedupe_supports() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let account = Account {
+                id: "id".to_string(),
+                name: "name".to_string(),
+                is_default: true,
+            };
+
+            let support = serde_json::from_value::<Support>(serde_json::json!({
+                "txid": "txid",
+                "nout": 0,
+                "claim_id": "claim_id",
+                "name": "my-video",
+                "amount": "1.5",
+                "is_tip": true
+            }))
+            .expect("Unable to parse support");
+
+            let saved = storage
+                .save_support(&account, support.clone())
+                .expect("Unable to save")
+                .expect("Support should be new");
+
+            assert_eq!(saved.id, "txid:0");
+            assert_eq!(saved.amount, 1.5);
+            assert!(storage
+                .save_support(&account, support)
+                .expect("Unable to save")
+                .is_none());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_rank_claims() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let claim = |id: &str, view_count: Option<i64>| ClaimEntity {
+                id: id.to_string(),
+                name: id.to_string(),
+                title: None,
+                view_count,
+                effective_amount: Some(1.0),
+                updated_at: chrono::NaiveDate::from_ymd(2020, 9, 5).and_hms(0, 0, 0),
+            };
+
+            storage.save_claim(claim("quiet", Some(3)))?;
+            storage.save_claim(claim("popular", Some(120)))?;
+            storage.save_claim(claim("quiet", Some(30)))?;
+
+            let top_claims = storage.get_top_claims(10)?;
+            assert_eq!(
+                top_claims
+                    .iter()
+                    .map(|claim| (claim.id.as_str(), claim.view_count))
+                    .collect::<Vec<(&str, Option<i64>)>>(),
+                vec![("popular", Some(120)), ("quiet", Some(30))]
+            );
+            assert_eq!(
+                storage.get_claim("quiet".to_string())?,
+                Some(claim("quiet", Some(30)))
+            );
+            assert_eq!(storage.get_claim("missing".to_string())?, None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_dedupe_transactions() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let account = Account {
+                id: "id".to_string(),
+                name: "name".to_string(),
+                is_default: true,
+            };
+
+            let transaction = serde_json::from_value::<Transaction>(serde_json::json!({
+                "txid": "txid",
+                "value": "2.5",
+                "timestamp": null
+            }))
+            .expect("Unable to parse transaction");
+
+            assert!(!storage
+                .has_transactions(&account.id)
+                .expect("Unable to check"));
+
+            let saved = storage
+                .save_transaction(&account, transaction.clone())
+                .expect("Unable to save")
+                .expect("Transaction should be new");
+
+            assert_eq!(saved.amount, 2.5);
+            assert!(storage
+                .has_transactions(&account.id)
+                .expect("Unable to check"));
+            assert!(storage
+                .save_transaction(&account, transaction)
+                .expect("Unable to save")
+                .is_none());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn storage_should_archive_comments() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let comment = Comment {
+                timestamp: Utc::now() - chrono::Duration::days(400),
+                ..testing::comment("id", "claim_id")
+            };
+
+            let saved = storage
+                .save_comment(testing::account("id"), testing::claim("claim_id"), comment)
+                .expect("Unable to save");
+            let cutoff = Utc::now().naive_utc() - chrono::Duration::days(365);
+
+            storage
+                .mark_comments_as_read(vec![saved.id.clone()])
+                .expect("Unable to mark");
+
+            assert_eq!(storage.archive_comments_before(cutoff), Ok(1));
+            assert!(storage
+                .get_claim_comment_ids()
+                .expect("Unable to fetch")
+                .is_empty());
+            assert_eq!(
+                storage
+                    .get_comment_by_id(saved.id.clone())
+                    .map(|entity| entity.comment),
+                Some("Hello".to_string())
+            );
+            assert_eq!(
+                storage
+                    .get_comments_by_claim_id(saved.claim_id.clone())
+                    .expect("Unable to fetch")
+                    .len(),
+                1
+            );
+            assert_eq!(
+                storage.count_comments_by_commenter_id(saved.commenter_id.clone()),
+                Ok(1)
+            );
+
+            storage
+                .delete_comment_by_id(saved.id.clone())
+                .expect("Unable to delete");
+
+            assert!(storage.get_comment_by_id(saved.id).is_none());
+
+            Ok(())
+        });
     }
 
-    pub fn list_comments_by_claim_id<'a, 'b, 'r: 'a>(
-        &'a self,
-        claim_id: &'b str,
-        page: usize,
-        page_size: usize,
-    ) -> impl Future<Output = Result<PaginatedApiResult<Comment>, ApiError>> + 'r {
-        self.request_data::<Comment>(&json!({
-            "method": "comment_list",
-            "params": {
-                "claim_id": claim_id,
-                "page": page,
-                "page_size": page_size,
+    #[test]
+    fn storage_should_archive_unread_comments() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
+
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            for &(id, days) in &[("old", 400), ("recent", 10)] {
+                let comment = Comment {
+                    timestamp: Utc::now() - chrono::Duration::days(days),
+                    ..testing::comment(id, "claim_id")
+                };
+
+                storage
+                    .save_comment(testing::account("id"), testing::claim("claim_id"), comment)
+                    .expect("Unable to save");
             }
-        }))
-    }
 
-    pub fn stream_comments_by_claim_id<'a, 'b, 'r: 'a>(
-        &'a self,
-        claim_id: String,
-        page_size: usize,
-    ) -> impl Stream<Item = Comment> + 'r {
-        let api = self.clone();
-        let f = move |page| {
-            debug!("Fetching comment of claim {} in page {}", &claim_id, page);
+            let cutoff = Utc::now().naive_utc() - chrono::Duration::days(365);
 
-            let inner_claim_id = claim_id.clone();
+            assert_eq!(storage.archive_comments_before(cutoff), Ok(1));
+            assert_eq!(
+                storage.get_claim_comment_ids(),
+                Ok(vec![("claim_id".to_string(), "recent".to_string())])
+            );
+            assert_eq!(
+                storage
+                    .get_comment_by_id("old".to_string())
+                    .map(|entity| entity.is_read),
+                Some(false)
+            );
 
-            api.list_comments_by_claim_id(&claim_id, page, page_size)
-                .inspect_ok(move |_| {
-                    debug!(
-                        "Done fetching comments for claim {} in page {}",
-                        inner_claim_id, page
-                    );
-                })
-                .inspect_err(|err| {
-                    debug!("Error fetching comments: {}", err);
-                })
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn notification_subject_should_flag_unverified() {
+        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string())
+            .with_unverified_flag(true);
+        let recipient = Recipient::new("to@mail.com".to_string());
+        let entity = |is_signature_valid| CommentEntity {
+            is_signature_valid,
+            ..testing::comment_entity("id", "claim_id")
+        };
+        let subject = |is_signature_valid| {
+            emails.notification_subject(&recipient, &entity(is_signature_valid), None)
         };
 
-        stream_paginated(f)
+        assert_eq!(
+            subject(Some(true)),
+            "New Comment from @commenter on my-video"
+        );
+        assert!(subject(Some(false)).starts_with("[Unverified] "));
+        assert!(subject(None).starts_with("[Unverified] "));
+        assert_eq!(
+            Emails::new("from@mail.com".to_string(), "to@mail.com".to_string())
+                .notification_subject(&recipient, &entity(None), None),
+            "New Comment from @commenter on my-video"
+        );
     }
-}
 
-pub struct Storage {
-    conn: SqliteConnection,
-}
+    #[test]
+    fn storage_should_track_deliveries() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
 
-impl Storage {
-    pub fn open(database_url: String) -> Result<Self, diesel::ConnectionError> {
-        SqliteConnection::establish(&database_url).map(|conn| {
-            embedded_migrations::run(&conn).expect(&format!("Unable to run migrations"));
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let saved = storage
+                .save_comment_with_deliveries(
+                    testing::account("id"),
+                    testing::claim("claim_id"),
+                    testing::comment("delivery_id", "claim_id"),
+                    vec![
+                        DELIVERY_EMAIL.to_string(),
+                        "https://example.com/hook".to_string(),
+                    ],
+                )
+                .expect("Unable to save");
+            let pending = |status: &str| {
+                storage
+                    .get_deliveries(status.to_string())
+                    .expect("Unable to fetch")
+                    .into_iter()
+                    .map(|delivery| delivery.target)
+                    .collect::<Vec<String>>()
+            };
 
-            Self { conn }
-        })
-    }
+            assert_eq!(
+                pending(DELIVERY_PENDING),
+                vec![DELIVERY_EMAIL, "https://example.com/hook"]
+            );
 
-    pub fn save_comment(
-        &self,
-        account: Account,
-        claim: Claim,
-        comment: Comment,
-    ) -> Result<CommentEntity, diesel::result::Error> {
-        let Account { id: account_id, .. } = account;
+            let done = storage
+                .record_delivery_attempt(saved.id.clone(), DELIVERY_EMAIL.to_string(), None, 3)
+                .expect("Unable to record")
+                .expect("Missing delivery");
+            assert_eq!((done.status.as_str(), done.attempts), (DELIVERY_DONE, 1));
 
-        let Claim {
-            name: claim_name, ..
-        } = claim;
+            let retried = storage
+                .record_delivery_attempt(
+                    saved.id.clone(),
+                    "https://example.com/hook".to_string(),
+                    Some("timed out".to_string()),
+                    2,
+                )
+                .expect("Unable to record")
+                .expect("Missing delivery");
+            assert_eq!(retried.status, DELIVERY_PENDING);
+            assert_eq!(retried.last_error, Some("timed out".to_string()));
 
-        let Comment {
-            id,
-            claim_id,
-            commenter_id,
-            commenter_name,
-            commenter_url,
-            comment,
-            is_hidden,
-            timestamp,
-            ..
-        } = comment;
+            storage
+                .record_delivery_attempt(
+                    saved.id.clone(),
+                    "https://example.com/hook".to_string(),
+                    Some("timed out".to_string()),
+                    2,
+                )
+                .expect("Unable to record");
 
-        let new_comment = CommentEntity {
-            id,
-            account_id,
-            claim_id,
-            claim_name,
-            commenter_id,
-            commenter_name,
-            commenter_url,
-            comment,
-            is_hidden,
-            timestamp: timestamp.naive_utc(),
-        };
+            assert!(pending(DELIVERY_PENDING).is_empty());
+            assert_eq!(pending(DELIVERY_DEAD), vec!["https://example.com/hook"]);
+            assert_eq!(
+                storage.record_delivery_attempt(saved.id, DELIVERY_EMAIL.to_string(), None, 3),
+                Ok(None)
+            );
 
-        diesel::insert_into(comments::table)
-            .values(&new_comment)
-            .execute(&self.conn)
-            .map(|_| new_comment)
+            Ok(())
+        });
     }
 
-    pub fn get_comment_by_id(&self, comment_id: String) -> Option<CommentEntity> {
-        use self::schema::comments::dsl::comments as c;
+    #[test]
+    fn storage_should_track_email_deliveries_per_recipient() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
 
-        c.find(comment_id).first(&self.conn).ok()
-    }
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let first = email_delivery_target("first@mail.com");
+            let second = email_delivery_target("second@mail.com");
+            storage
+                .save_deliveries(vec![
+                    Delivery::pending("recipient_id".to_string(), first.clone()),
+                    Delivery::pending("recipient_id".to_string(), second.clone()),
+                ])
+                .expect("Unable to save");
+            storage
+                .record_delivery_attempt("recipient_id".to_string(), first.clone(), None, 3)
+                .expect("Unable to record");
+            storage
+                .record_delivery_attempt(
+                    "recipient_id".to_string(),
+                    second.clone(),
+                    Some("mailbox unavailable".to_string()),
+                    3,
+                )
+                .expect("Unable to record");
+            let status = |target: &str| {
+                storage
+                    .get_delivery("recipient_id".to_string(), target.to_string())
+                    .expect("Unable to fetch")
+                    .map(|delivery| delivery.status)
+            };
 
-    pub fn delete_comment_by_id(&self, comment_id: String) -> Result<(), diesel::result::Error> {
-        use self::schema::comments::dsl::{comments as c, id};
+            assert_eq!(status(&first), Some(DELIVERY_DONE.to_string()));
+            assert_eq!(status(&second), Some(DELIVERY_PENDING.to_string()));
+            assert_eq!(status(DELIVERY_EMAIL), None);
+            assert!(storage
+                .get_deliveries(DELIVERY_PENDING.to_string())
+                .expect("Unable to fetch")
+                .iter()
+                .all(Delivery::is_email));
 
-        diesel::delete(c.filter(id.eq(comment_id)))
-            .execute(&self.conn)
-            .map(|_| ())
+            Ok(())
+        });
     }
 
-    pub fn transaction<T, E, F>(&self, f: F) -> Result<T, E>
-    where
-        F: FnOnce() -> Result<T, E>,
-        E: From<diesel::result::Error>,
-    {
-        self.conn.transaction(f)
-    }
+    #[test]
+    fn storage_should_skip_deliveries() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
 
-    pub fn test_transaction<T, E, F>(&self, f: F) -> T
-    where
-        F: FnOnce() -> Result<T, E>,
-        E: std::fmt::Debug,
-    {
-        self.conn.test_transaction(f)
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            storage
+                .save_deliveries(vec![Delivery::pending(
+                    "skipped_id".to_string(),
+                    DELIVERY_EMAIL.to_string(),
+                )])
+                .expect("Unable to save");
+            storage
+                .skip_deliveries("skipped_id".to_string())
+                .expect("Unable to skip");
+
+            let done = storage
+                .get_deliveries(DELIVERY_DONE.to_string())
+                .expect("Unable to fetch");
+            assert_eq!(done.len(), 1);
+            assert_eq!(done[0].attempts, 0);
+
+            Ok(())
+        });
     }
-}
 
-#[derive(Clone, Debug)]
-pub struct Emails {
-    from: String,
-    to: String,
-}
+    #[test]
+    fn storage_should_find_replied_comments() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
 
-impl Emails {
-    pub fn new(from: String, to: String) -> Self {
-        Self { from, to }
+        storage.test_transaction::<_, diesel::result::Error, _>(|| {
+            let account = testing::account("account_id");
+            let claim = testing::claim("claim_id");
+            let comment = |id: &str, parent_id: Option<&str>, commenter_id: &str| Comment {
+                parent_id: parent_id.map(String::from),
+                commenter_id: commenter_id.to_string(),
+                ..testing::comment(id, "claim_id")
+            };
+
+            for comment in [
+                comment("question", None, "commenter_id"),
+                comment("other", None, "commenter_id"),
+                comment("answer", Some("question"), "own_id"),
+                comment("thread", Some("other"), "commenter_id"),
+            ] {
+                storage
+                    .save_comment(account.clone(), claim.clone(), comment)
+                    .expect("Unable to save");
+            }
+
+            assert_eq!(
+                storage
+                    .get_replied_comment_ids(vec!["own_id".to_string()])
+                    .expect("Unable to fetch"),
+                vec!["question".to_string()]
+                    .into_iter()
+                    .collect::<HashSet<String>>()
+            );
+
+            Ok(())
+        });
     }
 
-    pub fn notification_email(&self, comment: CommentEntity) -> Email {
-        EmailBuilder::new()
-            .to(self.to.to_string())
-            .from(self.from.to_string())
-            .subject(format!(
-                "New Comment from {} on {}",
-                comment.commenter_name, comment.claim_name
-            ))
-            .text(format!(
-                "
-      {}
-      ---
+    #[tokio::test]
+    async fn try_stream_paginated_should_yield_errors() {
+        let results = try_stream_paginated(1, false, |page| {
+            future::ready(if page == 2 {
+                Err(ApiError::DeadlineExceeded)
+            } else {
+                Ok(PaginatedApiResult {
+                    items: vec![page],
+                    page,
+                    page_size: 1,
+                    total_items: 3,
+                    total_pages: 3,
+                })
+            })
+        })
+        .collect::<Vec<Result<usize, ApiError>>>()
+        .await;
 
-      {} ({})
-      {}
-      ===
-      {}
-",
-                comment.claim_name,
-                comment.commenter_name,
-                comment.commenter_url,
-                comment.timestamp,
-                comment.comment
-            ))
-            .build()
-            .expect("Could not build email")
+        let mut items = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok().copied())
+            .collect::<Vec<usize>>();
+        items.sort();
+
+        assert_eq!(items, vec![1, 3]);
+        assert_eq!(results.iter().filter(|result| result.is_err()).count(), 1);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use chrono::Utc;
-    use futures::stream::StreamExt;
-    use rand::seq::SliceRandom;
+    #[tokio::test]
+    async fn try_stream_paginated_should_keep_page_order() {
+        let results = try_stream_paginated(3, true, |page| async move {
+            delay_for(Duration::from_millis(40 / page as u64)).await;
 
-    use crate::{Account, Api, Claim, Comment, Emails, Storage};
+            Ok(PaginatedApiResult {
+                items: vec![page * 10, page * 10 + 1],
+                page,
+                page_size: 2,
+                total_items: 8,
+                total_pages: 4,
+            })
+        })
+        .collect::<Vec<Result<usize, ApiError>>>()
+        .await
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<usize>>();
 
-    const TEST_DB: &str = "test.db";
-    const TEST_URL: &str = "http://localhost:5279";
+        assert_eq!(results, vec![10, 11, 20, 21, 30, 31, 40, 41]);
+    }
 
     #[test]
-    fn storage_should_work() {
-        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
-        let emails = Emails::new("from@mail.com".to_string(), "to@mail.com".to_string());
+    fn parse_payload_should_read_rpc_errors() {
+        assert_eq!(
+            parse_payload::<i32>(serde_json::json!({ "result": 1 })).ok(),
+            Some(1)
+        );
 
-        storage.test_transaction::<_, diesel::result::Error, _>(|| {
-            let account = Account {
-                id: "id".to_string(),
-                name: "name".to_string(),
-                is_default: true,
-            };
+        match parse_payload::<i32>(serde_json::json!({
+            "error": {
+                "code": -32601,
+                "message": "Invalid method requested: foo.",
+                "data": { "name": "AttributeError" }
+            }
+        })) {
+            Err(ApiError::Rpc {
+                code,
+                message,
+                data,
+            }) => {
+                assert_eq!(code, -32601);
+                assert_eq!(message, "Invalid method requested: foo.");
+                assert!(data.is_some());
+            }
+            res => panic!("Expected an RPC error, got {:?}", res),
+        }
 
-            let claim = Claim {
-                id: "id".to_string(),
-                name: "name".to_string(),
-                timestamp: Utc::now(),
-            };
+        assert!(matches!(
+            parse_payload::<i32>(serde_json::json!({ "result": "one" })),
+            Err(ApiError::InvalidResponse)
+        ));
+    }
 
-            let comment = Comment {
-                id: "id".to_string(),
-                claim_id: "claim_id".to_string(),
-                comment: "comment".to_string(),
-                commenter_id: "commenter_id".to_string(),
-                commenter_name: "commenter_name".to_string(),
-                commenter_url: "commenter_url".to_string(),
-                is_hidden: false,
-                timestamp: Utc::now(),
-            };
+    #[test]
+    fn claim_metadata_should_parse() {
+        let metadata = ClaimMetadata::from_value(&serde_json::json!({
+            "canonical_url": "lbry://@channel#a/my-video#b",
+            "meta": { "effective_amount": "12.5" },
+            "value": {
+                "title": "My Video",
+                "thumbnail": { "url": "https://thumbnails.lbry.com/b" }
+            }
+        }));
 
-            let saved_comment = storage
-                .save_comment(account, claim, comment)
-                .expect("Unable to save");
+        assert_eq!(
+            metadata,
+            ClaimMetadata {
+                title: Some("My Video".to_string()),
+                thumbnail_url: Some("https://thumbnails.lbry.com/b".to_string()),
+                canonical_url: Some("lbry://@channel#a/my-video#b".to_string()),
+                effective_amount: Some(12.5),
+                view_count: None,
+            }
+        );
+        assert_eq!(
+            ClaimMetadata::from_value(&serde_json::json!({ "value": { "title": "" } })),
+            ClaimMetadata::default()
+        );
+    }
 
-            let entity = storage
-                .get_comment_by_id(saved_comment.id)
-                .expect("Unable to fetch");
+    #[cfg(debug_assertions)]
+    #[test]
+    fn storage_explain_should_use_indexes() {
+        let storage = Storage::open(TEST_DB.to_string()).expect("Unable to connect");
 
-            dbg!(emails.notification_email(entity));
+        vec![
+            (
+                "SELECT * FROM comments WHERE claim_id = 'id' ORDER BY timestamp",
+                "comments_claim_id_timestamp",
+            ),
+            (
+                "SELECT * FROM comments WHERE commenter_id = 'id'",
+                "comments_commenter_id",
+            ),
+            (
+                "SELECT * FROM comments WHERE account_id = 'id' AND timestamp >= 0",
+                "comments_account_id_timestamp",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(query, index)| {
+            let plan = storage.explain(query).expect("Unable to explain");
 
-            Ok(())
+            assert!(
+                plan.iter().any(|detail| detail.contains(index)),
+                "{} does not use {}: {:?}",
+                query,
+                index,
+                plan
+            );
         });
     }
 
@@ -524,4 +4745,127 @@ mod tests {
 
         let _comment_id = comment_ids.choose(rng).unwrap_or(&"".to_string()).clone();
     }
+
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn api_stream_should_fetch_from_mock_sdk() {
+        let server = crate::testing::MockSdk::new()
+            .with_account("account", "Account")
+            .with_claim("account", "first", "first")
+            .with_claim("account", "second", "second")
+            .with_claim("account", "third", "third")
+            .with_comment("first", "a", "Hello")
+            .with_comment("third", "b", "How?")
+            .with_comment("third", "c", "Nice")
+            .start();
+        let api = Api::new(server.url()).with_page_concurrency(2);
+
+        let comments = crate::stream_all_comments(&api, 2)
+            .map(|(account, claim, comment)| (account.id, claim.id, comment.id))
+            .collect::<Vec<(String, String, String)>>()
+            .await;
+
+        assert_eq!(
+            comments,
+            vec![
+                ("account".to_string(), "first".to_string(), "a".to_string()),
+                ("account".to_string(), "third".to_string(), "b".to_string()),
+                ("account".to_string(), "third".to_string(), "c".to_string()),
+            ]
+        );
+        assert_eq!(server.request_count(), 6);
+
+        match api.resolve_claims(vec!["lbry://first".to_string()]).await {
+            Err(ApiError::Rpc { code, .. }) => assert_eq!(code, -32601),
+            res => panic!("Expected an RPC error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn api_should_send_moderation_requests() {
+        let server = crate::testing::MockSdk::new().start();
+        let api = Api::new(server.url());
+
+        let hidden = api
+            .hide_comments(vec!["a".to_string(), "b".to_string()])
+            .await
+            .expect("Unable to hide comments");
+        let abandoned = api
+            .abandon_comment("c".to_string())
+            .await
+            .expect("Unable to abandon comment");
+
+        assert_eq!(hidden.len(), 2);
+        assert_eq!(abandoned["c"], serde_json::json!({ "abandoned": true }));
+
+        let payloads = server.payloads();
+        assert_eq!(
+            payloads,
+            vec![
+                serde_json::json!({
+                    "method": "comment_hide",
+                    "params": { "comment_ids": ["a", "b"] },
+                }),
+                serde_json::json!({
+                    "method": "comment_abandon",
+                    "params": { "comment_id": "c" },
+                }),
+            ]
+        );
+    }
+
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn api_should_refuse_to_hide_comments_without_a_signing_channel() {
+        let server = crate::testing::MockSdk::new()
+            .with_account("account", "Account")
+            .with_claim("account", "unsigned", "unsigned")
+            .start();
+        let api = Api::new(server.url());
+
+        match api
+            .hide_owned_comments(&["other".to_string()], vec!["a".to_string()])
+            .await
+        {
+            Err(crate::ModerationError::ClaimNotOwned(claim_id)) => assert_eq!(claim_id, "other"),
+            res => panic!("Expected an unowned claim, got {:?}", res.map(|_| ())),
+        }
+
+        match api
+            .hide_owned_comments(&["unsigned".to_string()], vec!["a".to_string()])
+            .await
+        {
+            Err(crate::ModerationError::UnsignedClaim(claim_id)) => {
+                assert_eq!(claim_id, "unsigned")
+            }
+            res => panic!("Expected an unsigned claim, got {:?}", res.map(|_| ())),
+        }
+
+        assert!(server
+            .payloads()
+            .iter()
+            .all(|payload| payload["method"] != "comment_hide"));
+    }
+
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn api_should_fail_over_to_replica() {
+        let server = crate::testing::MockSdk::new()
+            .with_account("account", "Account")
+            .start();
+        let api = Api::new(server.url()).with_endpoints(crate::Endpoints::new(vec![
+            "http://127.0.0.1:9".to_string(),
+            server.url(),
+        ]));
+
+        let account_ids = api
+            .stream_accounts(10)
+            .map(|account| account.id)
+            .collect::<Vec<String>>()
+            .await;
+
+        assert_eq!(account_ids, vec!["account"]);
+        assert_eq!(api.endpoints.current().0, 1);
+    }
 }
@@ -0,0 +1,438 @@
+use chrono::{Duration, FixedOffset, NaiveDateTime};
+use std::str::FromStr;
+
+use crate::CommentEntity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+    Portuguese,
+}
+
+impl Language {
+    pub fn translate(&self, text: &'static str) -> &'static str {
+        match (self, text) {
+            (Self::Spanish, "New Comment from") => "Nuevo comentario de",
+            (Self::Spanish, "on") => "en",
+            (Self::Spanish, "New Comments") => "comentarios nuevos",
+            (Self::Spanish, "Unread Comments") => "comentarios sin leer",
+            (Self::Spanish, "Mark as read") => "Marcar como leído",
+            (Self::Spanish, "Mark all as read") => "Marcar todo como leído",
+            (Self::Spanish, "Older Comments") => "comentarios antiguos",
+            (Self::Spanish, "While You Were Away") => "Mientras no estabas",
+            (Self::Spanish, "Since") => "Desde",
+            (Self::Spanish, "views") => "visualizaciones",
+            (Self::Spanish, "Hidden") => "Oculto",
+            (Self::Spanish, "Unverified") => "No verificado",
+            (Self::Spanish, "Digest") => "Resumen",
+            (Self::Spanish, "Reply to") => "Respuesta a",
+            (Self::Spanish, "Translated from") => "Traducido de",
+            (Self::Spanish, "Matched") => "Coincide con",
+            (Self::Spanish, "more from") => "más de",
+            (Self::Spanish, "commented on") => "comentó en",
+            (Self::Spanish, "reached") => "alcanzó",
+            (Self::Spanish, "comments") => "comentarios",
+            (Self::Spanish, "Comments") => "comentarios",
+            (Self::Spanish, "comments on") => "comentarios el",
+            (Self::Spanish, "posted their comment") => "publicó su comentario",
+            (Self::Spanish, "New Reactions on") => "Nuevas reacciones en",
+            (Self::Spanish, "likes") => "me gusta",
+            (Self::Spanish, "dislikes") => "no me gusta",
+            (Self::Spanish, "tip") => "propina",
+            (Self::Spanish, "support") => "apoyo",
+            (Self::Spanish, "Received") => "Recibidos",
+            (Self::Spanish, "Incoming transaction") => "Transacción entrante",
+            (Self::Spanish, "Amount") => "Importe",
+            (Self::Spanish, "Date") => "Fecha",
+            (Self::Spanish, "Transaction") => "Transacción",
+            (Self::Spanish, "Account") => "Cuenta",
+            (Self::Spanish, "Unconfirmed") => "Sin confirmar",
+            (Self::Spanish, "Comment spike") => "Pico de comentarios",
+            (Self::Spanish, "Comment drop") => "Caída de comentarios",
+            (Self::Spanish, "for") => "para",
+            (Self::Spanish, "Mean") => "Media",
+            (Self::Spanish, "Standard Deviation") => "Desviación estándar",
+            (Self::Spanish, "Database maintenance") => "Mantenimiento de la base de datos",
+            (Self::Spanish, "Database maintenance finished with status") => {
+                "Mantenimiento de la base de datos terminado con estado"
+            }
+            (Self::Spanish, "SDK is not ready, skipping comment checks") => {
+                "El SDK no está listo, se omite la revisión de comentarios"
+            }
+            (Self::Spanish, "SDK is not ready") => "El SDK no está listo",
+            (Self::Spanish, "Disk space is low, pausing comment ingestion") => {
+                "Poco espacio en disco, se pausa la ingesta de comentarios"
+            }
+            (Self::Spanish, "Disk space is low") => "Poco espacio en disco",
+            (Self::Spanish, "Still alive, 0 new comments since") => {
+                "Sigo activo, 0 comentarios nuevos desde"
+            }
+            (Self::Spanish, "Still alive") => "Sigo activo",
+            (Self::Spanish, "The notifier is running but no new comments arrived since") => {
+                "El notificador está activo pero no llegaron comentarios nuevos desde"
+            }
+            (Self::Spanish, "the beginning") => "el principio",
+            (Self::French, "New Comment from") => "Nouveau commentaire de",
+            (Self::French, "on") => "sur",
+            (Self::French, "New Comments") => "nouveaux commentaires",
+            (Self::French, "Unread Comments") => "commentaires non lus",
+            (Self::French, "Mark as read") => "Marquer comme lu",
+            (Self::French, "Mark all as read") => "Tout marquer comme lu",
+            (Self::French, "Older Comments") => "anciens commentaires",
+            (Self::French, "While You Were Away") => "Pendant votre absence",
+            (Self::French, "Since") => "Depuis",
+            (Self::French, "views") => "vues",
+            (Self::French, "Hidden") => "Masqué",
+            (Self::French, "Unverified") => "Non vérifié",
+            (Self::French, "Digest") => "Résumé",
+            (Self::French, "Reply to") => "Réponse à",
+            (Self::French, "Translated from") => "Traduit de",
+            (Self::French, "Matched") => "Correspond à",
+            (Self::French, "more from") => "de plus de",
+            (Self::French, "commented on") => "a commenté",
+            (Self::French, "reached") => "a atteint",
+            (Self::French, "comments") => "commentaires",
+            (Self::French, "Comments") => "commentaires",
+            (Self::French, "comments on") => "commentaires le",
+            (Self::French, "posted their comment") => "a publié son commentaire",
+            (Self::French, "New Reactions on") => "Nouvelles réactions sur",
+            (Self::French, "likes") => "j'aime",
+            (Self::French, "dislikes") => "je n'aime pas",
+            (Self::French, "tip") => "pourboire",
+            (Self::French, "support") => "soutien",
+            (Self::French, "Received") => "Reçu",
+            (Self::French, "Incoming transaction") => "Transaction entrante",
+            (Self::French, "Amount") => "Montant",
+            (Self::French, "Date") => "Date",
+            (Self::French, "Transaction") => "Transaction",
+            (Self::French, "Account") => "Compte",
+            (Self::French, "Unconfirmed") => "Non confirmée",
+            (Self::French, "Comment spike") => "Pic de commentaires",
+            (Self::French, "Comment drop") => "Baisse de commentaires",
+            (Self::French, "for") => "pour",
+            (Self::French, "Mean") => "Moyenne",
+            (Self::French, "Standard Deviation") => "Écart type",
+            (Self::French, "Database maintenance") => "Maintenance de la base de données",
+            (Self::French, "Database maintenance finished with status") => {
+                "Maintenance de la base de données terminée avec le statut"
+            }
+            (Self::French, "SDK is not ready, skipping comment checks") => {
+                "Le SDK n'est pas prêt, vérification des commentaires ignorée"
+            }
+            (Self::French, "SDK is not ready") => "Le SDK n'est pas prêt",
+            (Self::French, "Disk space is low, pausing comment ingestion") => {
+                "Espace disque faible, ingestion des commentaires en pause"
+            }
+            (Self::French, "Disk space is low") => "Espace disque faible",
+            (Self::French, "Still alive, 0 new comments since") => {
+                "Toujours actif, 0 nouveau commentaire depuis"
+            }
+            (Self::French, "Still alive") => "Toujours actif",
+            (Self::French, "The notifier is running but no new comments arrived since") => {
+                "Le notificateur fonctionne mais aucun nouveau commentaire n'est arrivé depuis"
+            }
+            (Self::French, "the beginning") => "le début",
+            (Self::German, "New Comment from") => "Neuer Kommentar von",
+            (Self::German, "on") => "zu",
+            (Self::German, "New Comments") => "neue Kommentare",
+            (Self::German, "Unread Comments") => "ungelesene Kommentare",
+            (Self::German, "Mark as read") => "Als gelesen markieren",
+            (Self::German, "Mark all as read") => "Alle als gelesen markieren",
+            (Self::German, "Older Comments") => "ältere Kommentare",
+            (Self::German, "While You Were Away") => "Während Sie weg waren",
+            (Self::German, "Since") => "Seit",
+            (Self::German, "views") => "Aufrufe",
+            (Self::German, "Hidden") => "Verborgen",
+            (Self::German, "Unverified") => "Unbestätigt",
+            (Self::German, "Digest") => "Übersicht",
+            (Self::German, "Reply to") => "Antwort an",
+            (Self::German, "Translated from") => "Übersetzt aus",
+            (Self::German, "Matched") => "Treffer für",
+            (Self::German, "more from") => "weitere von",
+            (Self::German, "commented on") => "kommentierte",
+            (Self::German, "reached") => "erreichte",
+            (Self::German, "comments") => "Kommentare",
+            (Self::German, "Comments") => "Kommentare",
+            (Self::German, "comments on") => "Kommentare am",
+            (Self::German, "posted their comment") => "schrieb Kommentar",
+            (Self::German, "New Reactions on") => "Neue Reaktionen auf",
+            (Self::German, "likes") => "Likes",
+            (Self::German, "dislikes") => "Dislikes",
+            (Self::German, "tip") => "Trinkgeld",
+            (Self::German, "support") => "Unterstützung",
+            (Self::German, "Received") => "Erhalten",
+            (Self::German, "Incoming transaction") => "Eingehende Transaktion",
+            (Self::German, "Amount") => "Betrag",
+            (Self::German, "Date") => "Datum",
+            (Self::German, "Transaction") => "Transaktion",
+            (Self::German, "Account") => "Konto",
+            (Self::German, "Unconfirmed") => "Unbestätigt",
+            (Self::German, "Comment spike") => "Kommentarspitze",
+            (Self::German, "Comment drop") => "Kommentareinbruch",
+            (Self::German, "for") => "für",
+            (Self::German, "Mean") => "Mittelwert",
+            (Self::German, "Standard Deviation") => "Standardabweichung",
+            (Self::German, "Database maintenance") => "Datenbankwartung",
+            (Self::German, "Database maintenance finished with status") => {
+                "Datenbankwartung beendet mit Status"
+            }
+            (Self::German, "SDK is not ready, skipping comment checks") => {
+                "SDK ist nicht bereit, Kommentarprüfung übersprungen"
+            }
+            (Self::German, "SDK is not ready") => "SDK ist nicht bereit",
+            (Self::German, "Disk space is low, pausing comment ingestion") => {
+                "Wenig Speicherplatz, Kommentaraufnahme pausiert"
+            }
+            (Self::German, "Disk space is low") => "Wenig Speicherplatz",
+            (Self::German, "Still alive, 0 new comments since") => {
+                "Läuft noch, 0 neue Kommentare seit"
+            }
+            (Self::German, "Still alive") => "Läuft noch",
+            (Self::German, "The notifier is running but no new comments arrived since") => {
+                "Der Notifier läuft, aber es kamen keine neuen Kommentare seit"
+            }
+            (Self::German, "the beginning") => "Beginn",
+            (Self::Portuguese, "New Comment from") => "Novo comentário de",
+            (Self::Portuguese, "on") => "em",
+            (Self::Portuguese, "New Comments") => "comentários novos",
+            (Self::Portuguese, "Unread Comments") => "comentários não lidos",
+            (Self::Portuguese, "Mark as read") => "Marcar como lido",
+            (Self::Portuguese, "Mark all as read") => "Marcar tudo como lido",
+            (Self::Portuguese, "Older Comments") => "comentários antigos",
+            (Self::Portuguese, "While You Were Away") => "Enquanto você esteve fora",
+            (Self::Portuguese, "Since") => "Desde",
+            (Self::Portuguese, "views") => "visualizações",
+            (Self::Portuguese, "Hidden") => "Oculto",
+            (Self::Portuguese, "Unverified") => "Não verificado",
+            (Self::Portuguese, "Digest") => "Resumo",
+            (Self::Portuguese, "Reply to") => "Resposta a",
+            (Self::Portuguese, "Translated from") => "Traduzido de",
+            (Self::Portuguese, "Matched") => "Corresponde a",
+            (Self::Portuguese, "more from") => "mais de",
+            (Self::Portuguese, "commented on") => "comentou em",
+            (Self::Portuguese, "reached") => "alcançou",
+            (Self::Portuguese, "comments") => "comentários",
+            (Self::Portuguese, "Comments") => "comentários",
+            (Self::Portuguese, "comments on") => "comentários em",
+            (Self::Portuguese, "posted their comment") => "publicou seu comentário",
+            (Self::Portuguese, "New Reactions on") => "Novas reações em",
+            (Self::Portuguese, "likes") => "curtidas",
+            (Self::Portuguese, "dislikes") => "descurtidas",
+            (Self::Portuguese, "tip") => "gorjeta",
+            (Self::Portuguese, "support") => "apoio",
+            (Self::Portuguese, "Received") => "Recebido",
+            (Self::Portuguese, "Incoming transaction") => "Transação recebida",
+            (Self::Portuguese, "Amount") => "Valor",
+            (Self::Portuguese, "Date") => "Data",
+            (Self::Portuguese, "Transaction") => "Transação",
+            (Self::Portuguese, "Account") => "Conta",
+            (Self::Portuguese, "Unconfirmed") => "Não confirmada",
+            (Self::Portuguese, "Comment spike") => "Pico de comentários",
+            (Self::Portuguese, "Comment drop") => "Queda de comentários",
+            (Self::Portuguese, "for") => "para",
+            (Self::Portuguese, "Mean") => "Média",
+            (Self::Portuguese, "Standard Deviation") => "Desvio padrão",
+            (Self::Portuguese, "Database maintenance") => "Manutenção do banco de dados",
+            (Self::Portuguese, "Database maintenance finished with status") => {
+                "Manutenção do banco de dados concluída com status"
+            }
+            (Self::Portuguese, "SDK is not ready, skipping comment checks") => {
+                "O SDK não está pronto, verificação de comentários ignorada"
+            }
+            (Self::Portuguese, "SDK is not ready") => "O SDK não está pronto",
+            (Self::Portuguese, "Disk space is low, pausing comment ingestion") => {
+                "Pouco espaço em disco, ingestão de comentários pausada"
+            }
+            (Self::Portuguese, "Disk space is low") => "Pouco espaço em disco",
+            (Self::Portuguese, "Still alive, 0 new comments since") => {
+                "Ainda ativo, 0 comentários novos desde"
+            }
+            (Self::Portuguese, "Still alive") => "Ainda ativo",
+            (Self::Portuguese, "The notifier is running but no new comments arrived since") => {
+                "O notificador está ativo, mas nenhum comentário novo chegou desde"
+            }
+            (Self::Portuguese, "the beginning") => "o início",
+            _ => text,
+        }
+    }
+
+    fn date_format(&self) -> &'static str {
+        match self {
+            Self::English => "%Y-%m-%d %H:%M:%S",
+            Self::German => "%d.%m.%Y %H:%M",
+            Self::Spanish | Self::French | Self::Portuguese => "%d/%m/%Y %H:%M",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let code = value
+            .split(&['-', '_'][..])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match code.as_str() {
+            "en" => Ok(Self::English),
+            "es" => Ok(Self::Spanish),
+            "fr" => Ok(Self::French),
+            "de" => Ok(Self::German),
+            "pt" => Ok(Self::Portuguese),
+            _ => Err(format!("Unsupported language {}", value)),
+        }
+    }
+}
+
+fn parse_offset(value: &str) -> Result<FixedOffset, String> {
+    let value = value.trim();
+    let offset = value
+        .trim_start_matches("UTC")
+        .trim_start_matches("GMT")
+        .replace(':', "");
+
+    if offset.is_empty() || offset == "Z" {
+        return Ok(FixedOffset::east(0));
+    }
+
+    let (sign, digits) = match offset.chars().next() {
+        Some('+') => (1, &offset[1..]),
+        Some('-') => (-1, &offset[1..]),
+        _ => return Err(format!("Invalid UTC offset {}", value)),
+    };
+
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>(), Ok(0)),
+        4 => (digits[..2].parse::<i32>(), digits[2..].parse::<i32>()),
+        _ => return Err(format!("Invalid UTC offset {}", value)),
+    };
+
+    match (hours, minutes) {
+        (Ok(hours), Ok(minutes)) if hours <= 14 && minutes < 60 => {
+            Ok(FixedOffset::east(sign * (hours * 3600 + minutes * 60)))
+        }
+        _ => Err(format!("Invalid UTC offset {}", value)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recipient {
+    pub address: String,
+    pub language: Language,
+    pub offset: FixedOffset,
+}
+
+impl Recipient {
+    pub fn new(address: String) -> Self {
+        Self {
+            address,
+            language: Language::English,
+            offset: FixedOffset::east(0),
+        }
+    }
+
+    pub fn translate(&self, text: &'static str) -> &'static str {
+        self.language.translate(text)
+    }
+
+    pub fn local_timestamp(&self, timestamp: NaiveDateTime) -> NaiveDateTime {
+        timestamp + Duration::seconds(self.offset.local_minus_utc() as i64)
+    }
+
+    pub fn format_timestamp(&self, timestamp: NaiveDateTime) -> String {
+        let local = self
+            .local_timestamp(timestamp)
+            .format(self.language.date_format())
+            .to_string();
+
+        if self.offset.local_minus_utc() == 0 {
+            local
+        } else {
+            format!("{} UTC{}", local, self.offset)
+        }
+    }
+
+    pub fn localize_comments(&self, comments: &[CommentEntity]) -> Vec<CommentEntity> {
+        comments
+            .iter()
+            .cloned()
+            .map(|mut comment| {
+                comment.timestamp = self.local_timestamp(comment.timestamp);
+                comment
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Recipient {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split('|').map(str::trim);
+        let address = parts.next().unwrap_or_default();
+
+        if address.is_empty() {
+            return Err(format!("Missing recipient address in {}", value));
+        }
+
+        let language = match parts.next().filter(|part| !part.is_empty()) {
+            Some(language) => language.parse::<Language>()?,
+            None => Language::English,
+        };
+        let offset = match parts.next().filter(|part| !part.is_empty()) {
+            Some(offset) => parse_offset(offset)?,
+            None => FixedOffset::east(0),
+        };
+
+        Ok(Self {
+            address: address.to_string(),
+            language,
+            offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, NaiveDate};
+
+    use super::{Language, Recipient};
+
+    #[test]
+    fn recipient_should_parse_and_format() {
+        let recipient = "ana@example.com|es-MX|-05:00"
+            .parse::<Recipient>()
+            .expect("Invalid recipient");
+
+        assert_eq!(recipient.address, "ana@example.com");
+        assert_eq!(recipient.language, Language::Spanish);
+        assert_eq!(recipient.offset, FixedOffset::west(5 * 3600));
+        assert_eq!(
+            recipient.format_timestamp(NaiveDate::from_ymd(2020, 8, 1).and_hms(3, 30, 0)),
+            "31/07/2020 22:30 UTC-05:00"
+        );
+        assert_eq!(recipient.translate("on"), "en");
+
+        let default = "me@example.com".parse::<Recipient>().expect("Invalid");
+        assert_eq!(default, Recipient::new("me@example.com".to_string()));
+        assert_eq!(
+            default.format_timestamp(NaiveDate::from_ymd(2020, 8, 1).and_hms(3, 30, 0)),
+            "2020-08-01 03:30:00"
+        );
+
+        assert_eq!(
+            "jo@example.com|de|UTC+0530"
+                .parse::<Recipient>()
+                .map(|recipient| recipient.offset),
+            Ok(FixedOffset::east(5 * 3600 + 30 * 60))
+        );
+        assert!("jo@example.com|xx".parse::<Recipient>().is_err());
+        assert!("jo@example.com|en|+25:00".parse::<Recipient>().is_err());
+        assert!("|en".parse::<Recipient>().is_err());
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(feature = "mysql")]
+use diesel::sql_types::Nullable;
+use diesel::sql_types::{Integer, Text};
+#[cfg(feature = "postgres")]
+use diesel::{
+    deserialize::{self, QueryableByName},
+    pg::Pg,
+    row::NamedRow,
+};
+
+#[derive(Debug, QueryableByName)]
+pub(crate) struct IntegrityCheckRow {
+    #[sql_type = "Text"]
+    pub(crate) integrity_check: String,
+}
+
+#[derive(Debug, QueryableByName)]
+pub(crate) struct AutoVacuumRow {
+    #[sql_type = "Integer"]
+    pub(crate) auto_vacuum: i32,
+}
+
+#[derive(Debug, QueryableByName)]
+pub(crate) struct QueryPlanRow {
+    #[sql_type = "Text"]
+    pub(crate) detail: String,
+}
+
+#[cfg(feature = "mysql")]
+#[derive(Debug, QueryableByName)]
+pub(crate) struct MysqlQueryPlanRow {
+    #[sql_type = "Nullable<Text>"]
+    pub(crate) table: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub(crate) key: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    #[column_name = "Extra"]
+    pub(crate) extra: Option<String>,
+}
+
+#[cfg(feature = "mysql")]
+impl MysqlQueryPlanRow {
+    pub(crate) fn detail(&self) -> String {
+        let table = self.table.as_deref().unwrap_or_default();
+
+        match (&self.key, &self.extra) {
+            (Some(key), _) => format!("SEARCH {} USING INDEX {}", table, key),
+            (None, Some(extra)) if !extra.is_empty() => format!("SCAN {} ({})", table, extra),
+            _ => format!("SCAN {}", table),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[derive(Debug)]
+pub(crate) struct PostgresQueryPlanRow {
+    pub(crate) query_plan: String,
+}
+
+#[cfg(feature = "postgres")]
+impl QueryableByName<Pg> for PostgresQueryPlanRow {
+    fn build<R: NamedRow<Pg>>(row: &R) -> deserialize::Result<Self> {
+        Ok(Self {
+            query_plan: row.get::<Text, String>("QUERY PLAN")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaintenanceReport {
+    pub problems: Vec<String>,
+    pub converted_to_incremental: bool,
+}
+
+impl MaintenanceReport {
+    pub fn new(integrity: Vec<String>, converted_to_incremental: bool) -> Self {
+        let problems = integrity
+            .into_iter()
+            .filter(|line| line != "ok")
+            .collect::<Vec<String>>();
+
+        Self {
+            problems,
+            converted_to_incremental,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn status(&self) -> &'static str {
+        if self.is_healthy() {
+            "ok"
+        } else {
+            "corrupt"
+        }
+    }
+
+    pub fn details(&self) -> String {
+        if self.is_healthy() {
+            if self.converted_to_incremental {
+                "Integrity ok, converted to incremental vacuum".to_string()
+            } else {
+                "Integrity ok".to_string()
+            }
+        } else {
+            self.problems.join("\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MaintenanceReport;
+
+    #[test]
+    fn maintenance_report_should_collect_problems() {
+        let healthy = MaintenanceReport::new(vec!["ok".to_string()], false);
+        assert!(healthy.is_healthy());
+        assert_eq!(healthy.status(), "ok");
+
+        let corrupt = MaintenanceReport::new(
+            vec![
+                "row 3 missing from index comments_idx".to_string(),
+                "wrong # of entries in index comments_idx".to_string(),
+            ],
+            false,
+        );
+        assert!(!corrupt.is_healthy());
+        assert_eq!(corrupt.status(), "corrupt");
+        assert_eq!(corrupt.details().lines().count(), 2);
+    }
+}
@@ -0,0 +1,329 @@
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use std::{
+    collections::HashMap,
+    net::UdpSocket,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{ApiError, ApiTrace};
+
+const LATENCY_BUCKETS_SECS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunMetrics {
+    pub new_comments: usize,
+    pub failed_requests: usize,
+    pub duration: Duration,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl RunMetrics {
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("new_comments", self.new_comments.to_string()),
+            ("failed_requests", self.failed_requests.to_string()),
+            (
+                "run_duration_seconds",
+                format!("{:.3}", self.duration.as_secs_f64()),
+            ),
+            (
+                "run_success",
+                if self.failed_requests > 0 { "0" } else { "1" }.to_string(),
+            ),
+            (
+                "last_run_timestamp_seconds",
+                self.finished_at.timestamp().to_string(),
+            ),
+        ]
+    }
+
+    pub fn render_prometheus(&self, prefix: &str) -> String {
+        self.values()
+            .into_iter()
+            .map(|(name, value)| {
+                format!("# TYPE {0}_{1} gauge\n{0}_{1} {2}\n", prefix, name, value)
+            })
+            .collect::<String>()
+    }
+
+    pub fn render_statsd(&self, prefix: &str) -> String {
+        self.values()
+            .into_iter()
+            .map(|(name, value)| format!("{}.{}:{}|g", prefix, name, value))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MethodMetrics {
+    pub requests: u64,
+    pub errors: u64,
+    pub latency_buckets: Vec<(f64, u64)>,
+    pub latency_sum: Duration,
+}
+
+impl MethodMetrics {
+    fn record(&mut self, trace: &ApiTrace) {
+        let seconds = trace.duration.as_secs_f64();
+
+        if self.latency_buckets.is_empty() {
+            self.latency_buckets = LATENCY_BUCKETS_SECS
+                .iter()
+                .map(|bucket| (*bucket, 0))
+                .collect();
+        }
+
+        self.requests += 1;
+        self.latency_sum += trace.duration;
+
+        if trace.error.is_some() {
+            self.errors += 1;
+        }
+
+        self.latency_buckets
+            .iter_mut()
+            .filter(|(bucket, _)| seconds <= *bucket)
+            .for_each(|(_, count)| *count += 1);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ApiMetrics {
+    methods: Arc<Mutex<HashMap<String, MethodMetrics>>>,
+}
+
+impl ApiMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record(&self, trace: &ApiTrace) {
+        self.methods
+            .lock()
+            .expect("Unable to get lock")
+            .entry(trace.method.clone())
+            .or_default()
+            .record(trace);
+    }
+
+    pub fn snapshot(&self) -> Vec<(String, MethodMetrics)> {
+        let mut methods = self
+            .methods
+            .lock()
+            .expect("Unable to get lock")
+            .iter()
+            .map(|(method, metrics)| (method.clone(), metrics.clone()))
+            .collect::<Vec<_>>();
+        methods.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        methods
+    }
+
+    pub fn render_prometheus(&self, prefix: &str) -> String {
+        let methods = self.snapshot();
+        let mut lines = vec![
+            format!("# TYPE {}_api_requests_total counter", prefix),
+            format!("# TYPE {}_api_errors_total counter", prefix),
+            format!("# TYPE {}_api_latency_seconds histogram", prefix),
+        ];
+
+        for (method, metrics) in &methods {
+            lines.push(format!(
+                "{}_api_requests_total{{method=\"{}\"}} {}",
+                prefix, method, metrics.requests
+            ));
+            lines.push(format!(
+                "{}_api_errors_total{{method=\"{}\"}} {}",
+                prefix, method, metrics.errors
+            ));
+
+            for (bucket, count) in &metrics.latency_buckets {
+                lines.push(format!(
+                    "{}_api_latency_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    prefix, method, bucket, count
+                ));
+            }
+
+            lines.push(format!(
+                "{}_api_latency_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                prefix, method, metrics.requests
+            ));
+            lines.push(format!(
+                "{}_api_latency_seconds_sum{{method=\"{}\"}} {:.3}",
+                prefix,
+                method,
+                metrics.latency_sum.as_secs_f64()
+            ));
+            lines.push(format!(
+                "{}_api_latency_seconds_count{{method=\"{}\"}} {}",
+                prefix, method, metrics.requests
+            ));
+        }
+
+        lines
+            .into_iter()
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricsPusher {
+    client: Client,
+    job: String,
+    pushgateway_url: Option<String>,
+    statsd_address: Option<String>,
+}
+
+impl MetricsPusher {
+    pub fn new(job: String) -> Self {
+        Self {
+            client: Client::new(),
+            job,
+            pushgateway_url: None,
+            statsd_address: None,
+        }
+    }
+
+    pub fn with_pushgateway(self, url: String) -> Self {
+        Self {
+            pushgateway_url: Some(url),
+            ..self
+        }
+    }
+
+    pub fn with_statsd(self, address: String) -> Self {
+        Self {
+            statsd_address: Some(address),
+            ..self
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.pushgateway_url.is_some() || self.statsd_address.is_some()
+    }
+
+    pub async fn push(&self, metrics: &RunMetrics) {
+        if let Some(url) = &self.pushgateway_url {
+            if let Err(err) = self.push_gateway(url, metrics).await {
+                error!("Unable to push metrics to {}: {}", url, err);
+            }
+        }
+
+        if let Some(address) = &self.statsd_address {
+            if let Err(err) = self.send_statsd(address, metrics) {
+                error!("Unable to send metrics to {}: {}", address, err);
+            }
+        }
+    }
+
+    async fn push_gateway(&self, url: &str, metrics: &RunMetrics) -> Result<(), ApiError> {
+        self.client
+            .put(&format!(
+                "{}/metrics/job/{}",
+                url.trim_end_matches('/'),
+                self.job
+            ))
+            .body(metrics.render_prometheus(&self.job))
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map(|_| ())
+            .map_err(ApiError::NetworkError)
+    }
+
+    fn send_statsd(&self, address: &str, metrics: &RunMetrics) -> std::io::Result<()> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+
+        socket
+            .send_to(metrics.render_statsd(&self.job).as_bytes(), address)
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+
+    use super::{ApiMetrics, RunMetrics};
+    use crate::ApiTrace;
+
+    #[test]
+    fn run_metrics_should_render_prometheus_and_statsd() {
+        let metrics = RunMetrics {
+            new_comments: 3,
+            failed_requests: 1,
+            duration: Duration::from_millis(1500),
+            finished_at: Utc.ymd(2020, 9, 5).and_hms(10, 0, 0),
+        };
+
+        let prometheus = metrics.render_prometheus("notifier");
+        assert!(
+            prometheus.contains("# TYPE notifier_new_comments gauge\nnotifier_new_comments 3\n")
+        );
+        assert!(prometheus.contains("notifier_run_duration_seconds 1.500\n"));
+        assert!(prometheus.contains("notifier_run_success 0\n"));
+        assert!(prometheus.contains("notifier_last_run_timestamp_seconds 1599300000\n"));
+
+        assert_eq!(
+            metrics
+                .render_statsd("notifier")
+                .lines()
+                .collect::<Vec<&str>>(),
+            vec![
+                "notifier.new_comments:3|g",
+                "notifier.failed_requests:1|g",
+                "notifier.run_duration_seconds:1.500|g",
+                "notifier.run_success:0|g",
+                "notifier.last_run_timestamp_seconds:1599300000|g",
+            ]
+        );
+    }
+
+    #[test]
+    fn api_metrics_should_count_methods() {
+        let trace = |method: &str, millis: u64, error: Option<&str>| ApiTrace {
+            method: method.to_string(),
+            claim_id: None,
+            page: None,
+            duration: Duration::from_millis(millis),
+            attempts: 1,
+            batched: false,
+            result_count: None,
+            error: error.map(String::from),
+        };
+
+        let metrics = ApiMetrics::new();
+        metrics.record(&trace("comment_list", 80, None));
+        metrics.record(&trace("comment_list", 1200, Some("Deadline exceeded")));
+        metrics.clone().record(&trace("account_list", 20, None));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].0, "account_list");
+
+        let comment_list = &snapshot[1].1;
+        assert_eq!(comment_list.requests, 2);
+        assert_eq!(comment_list.errors, 1);
+        assert_eq!(comment_list.latency_sum, Duration::from_millis(1280));
+        assert_eq!(comment_list.latency_buckets[0], (0.05, 0));
+        assert_eq!(comment_list.latency_buckets[1], (0.1, 1));
+        assert_eq!(comment_list.latency_buckets[5], (2.5, 2));
+
+        let prometheus = metrics.render_prometheus("notifier");
+        assert!(prometheus.contains("# TYPE notifier_api_latency_seconds histogram\n"));
+        assert!(prometheus.contains("notifier_api_errors_total{method=\"comment_list\"} 1\n"));
+        assert!(prometheus.contains(
+            "notifier_api_latency_seconds_bucket{method=\"comment_list\",le=\"0.25\"} 1\n"
+        ));
+        assert!(prometheus.contains(
+            "notifier_api_latency_seconds_bucket{method=\"account_list\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(prometheus
+            .contains("notifier_api_latency_seconds_sum{method=\"comment_list\"} 1.280\n"));
+    }
+}
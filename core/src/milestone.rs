@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use crate::CommentEntity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MilestoneAction {
+    Email,
+    Reply,
+    Webhook,
+}
+
+impl FromStr for MilestoneAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "email" => Ok(Self::Email),
+            "reply" => Ok(Self::Reply),
+            "webhook" => Ok(Self::Webhook),
+            _ => Err(format!("Unknown milestone action {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Milestones {
+    pub counts: Vec<i64>,
+    pub actions: Vec<MilestoneAction>,
+    pub reply_channel: Option<String>,
+    pub reply_template: String,
+    pub webhook_urls: Vec<String>,
+}
+
+impl Milestones {
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty() || self.actions.is_empty()
+    }
+
+    pub fn reached(&self, comment_count: i64) -> Option<i64> {
+        self.counts
+            .iter()
+            .cloned()
+            .find(|count| *count == comment_count)
+    }
+
+    pub fn has_action(&self, action: MilestoneAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    pub fn render_reply(&self, comment: &CommentEntity, milestone: i64) -> String {
+        self.reply_template
+            .replace("{commenter_name}", &comment.commenter_name)
+            .replace("{milestone}", &milestone.to_string())
+            .replace("{claim_name}", comment.claim_display_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MilestoneAction, Milestones};
+
+    #[test]
+    fn milestones_should_be_reached_exactly() {
+        let milestones = Milestones {
+            counts: vec![10, 50],
+            actions: vec!["email".parse::<MilestoneAction>().expect("Unable to parse")],
+            reply_channel: None,
+            reply_template: "Thanks {commenter_name}".to_string(),
+            webhook_urls: vec![],
+        };
+
+        assert_eq!(milestones.reached(10), Some(10));
+        assert_eq!(milestones.reached(11), None);
+        assert!(milestones.has_action(MilestoneAction::Email));
+        assert!(!milestones.has_action(MilestoneAction::Reply));
+        assert!("tweet".parse::<MilestoneAction>().is_err());
+    }
+}
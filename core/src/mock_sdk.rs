@@ -0,0 +1,331 @@
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::oneshot;
+
+const MOCK_TIMESTAMP: i64 = 1596276000;
+
+#[derive(Clone, Debug, Default)]
+pub struct MockSdk {
+    accounts: Vec<Value>,
+    channels: HashMap<String, Vec<Value>>,
+    claims: HashMap<String, Vec<Value>>,
+    channel_claims: HashMap<String, Vec<Value>>,
+    comments: HashMap<String, Vec<Value>>,
+}
+
+impl MockSdk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_account(mut self, account_id: &str, name: &str) -> Self {
+        self.accounts.push(json!({
+            "id": account_id,
+            "name": name,
+            "is_default": self.accounts.is_empty(),
+        }));
+
+        self
+    }
+
+    pub fn with_claim(mut self, account_id: &str, claim_id: &str, name: &str) -> Self {
+        self.claims
+            .entry(account_id.to_string())
+            .or_default()
+            .push(json!({
+                "claim_id": claim_id,
+                "name": name,
+                "timestamp": MOCK_TIMESTAMP,
+            }));
+
+        self
+    }
+
+    pub fn with_channel(mut self, account_id: &str, channel_id: &str, name: &str) -> Self {
+        self.channels
+            .entry(account_id.to_string())
+            .or_default()
+            .push(json!({
+                "claim_id": channel_id,
+                "name": name,
+                "has_signing_key": true,
+            }));
+
+        self
+    }
+
+    pub fn with_channel_claim(mut self, channel_id: &str, claim_id: &str, name: &str) -> Self {
+        self.channel_claims
+            .entry(channel_id.to_string())
+            .or_default()
+            .push(json!({
+                "claim_id": claim_id,
+                "name": name,
+                "timestamp": MOCK_TIMESTAMP,
+                "signing_channel": { "claim_id": channel_id },
+            }));
+
+        self
+    }
+
+    pub fn with_comment(mut self, claim_id: &str, comment_id: &str, text: &str) -> Self {
+        self.comments
+            .entry(claim_id.to_string())
+            .or_default()
+            .push(json!({
+                "comment_id": comment_id,
+                "claim_id": claim_id,
+                "comment": text,
+                "channel_id": "commenter_id",
+                "channel_name": "@commenter",
+                "channel_url": "lbry://@commenter#commenter_id",
+                "is_hidden": false,
+                "timestamp": MOCK_TIMESTAMP,
+                "support_amount": "0.0",
+            }));
+
+        self
+    }
+
+    fn find<'a>(groups: &'a HashMap<String, Vec<Value>>, claim_id: &str) -> Option<&'a Value> {
+        groups
+            .values()
+            .flatten()
+            .find(|item| item["claim_id"].as_str() == Some(claim_id))
+    }
+
+    fn items(&self, method: &str, params: &Value) -> Option<&[Value]> {
+        let param = |key: &str| params.get(key).and_then(|value| value.as_str());
+        let empty: &[Value] = &[];
+
+        match method {
+            "account_list" => Some(&self.accounts),
+            "channel_list" if param("claim_id").is_some() => Some(
+                param("claim_id")
+                    .and_then(|channel_id| Self::find(&self.channels, channel_id))
+                    .map(std::slice::from_ref)
+                    .unwrap_or(empty),
+            ),
+            "claim_list" if param("claim_id").is_some() => Some(
+                param("claim_id")
+                    .and_then(|claim_id| {
+                        Self::find(&self.claims, claim_id)
+                            .or_else(|| Self::find(&self.channel_claims, claim_id))
+                    })
+                    .map(std::slice::from_ref)
+                    .unwrap_or(empty),
+            ),
+            "channel_list" => Some(
+                param("account_id")
+                    .and_then(|account_id| self.channels.get(account_id))
+                    .map(Vec::as_slice)
+                    .unwrap_or(empty),
+            ),
+            "claim_list" => Some(
+                param("account_id")
+                    .and_then(|account_id| self.claims.get(account_id))
+                    .map(Vec::as_slice)
+                    .unwrap_or(empty),
+            ),
+            "claim_search" => Some(
+                params
+                    .get("channel_ids")
+                    .and_then(|channel_ids| channel_ids.get(0))
+                    .and_then(|channel_id| channel_id.as_str())
+                    .and_then(|channel_id| self.channel_claims.get(channel_id))
+                    .map(Vec::as_slice)
+                    .unwrap_or(empty),
+            ),
+            "comment_list" | "comment.List" => Some(
+                param("claim_id")
+                    .and_then(|claim_id| self.comments.get(claim_id))
+                    .map(Vec::as_slice)
+                    .unwrap_or(empty),
+            ),
+            _ => None,
+        }
+    }
+
+    fn respond(&self, payload: &Value) -> Value {
+        let method = payload
+            .get("method")
+            .and_then(|method| method.as_str())
+            .unwrap_or_default();
+        let params = payload.get("params").cloned().unwrap_or(Value::Null);
+        let number = |key: &str, default: usize| {
+            params
+                .get(key)
+                .and_then(|value| value.as_u64())
+                .map(|value| value as usize)
+                .unwrap_or(default)
+                .max(1)
+        };
+
+        if method == "status" {
+            return json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "is_running": true,
+                    "startup_status": { "wallet": true },
+                    "wallet": { "blocks": 1, "blocks_behind": 0, "connected": "mock" },
+                }
+            });
+        }
+
+        if method == "comment_hide" || method == "comment_abandon" {
+            let (key, ids) = match method {
+                "comment_hide" => ("hidden", params["comment_ids"].clone()),
+                _ => ("abandoned", json!([params["comment_id"].clone()])),
+            };
+
+            return json!({
+                "jsonrpc": "2.0",
+                "result": ids
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|id| id.as_str())
+                    .map(|id| (id.to_string(), json!({ key: true })))
+                    .collect::<serde_json::Map<String, Value>>(),
+            });
+        }
+
+        match self.items(method, &params) {
+            Some(items) => {
+                let page = number("page", 1);
+                let page_size = number("page_size", 20);
+
+                json!({
+                    "jsonrpc": "2.0",
+                    "result": {
+                        "items": items
+                            .iter()
+                            .skip((page - 1) * page_size)
+                            .take(page_size)
+                            .collect::<Vec<&Value>>(),
+                        "page": page,
+                        "page_size": page_size,
+                        "total_items": items.len(),
+                        "total_pages": items.len().div_ceil(page_size),
+                    }
+                })
+            }
+            None => json!({
+                "jsonrpc": "2.0",
+                "error": {
+                    "code": -32601,
+                    "message": format!("Invalid method requested: {}.", method),
+                }
+            }),
+        }
+    }
+
+    pub fn start(self) -> MockSdkServer {
+        let sdk = Arc::new(self);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let server_requests = requests.clone();
+        let server_payloads = payloads.clone();
+
+        let make_service = make_service_fn(move |_| {
+            let sdk = sdk.clone();
+            let requests = server_requests.clone();
+            let payloads = server_payloads.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let sdk = sdk.clone();
+                    let payloads = payloads.clone();
+
+                    requests.fetch_add(1, Ordering::SeqCst);
+
+                    async move {
+                        let body = hyper::body::to_bytes(req.into_body())
+                            .await
+                            .unwrap_or_default();
+                        let payload = serde_json::from_slice::<Value>(&body).unwrap_or_default();
+
+                        payloads
+                            .lock()
+                            .expect("Unable to get lock")
+                            .push(payload.clone());
+
+                        let response = match payload.as_array() {
+                            Some(batch) => Value::Array(
+                                batch
+                                    .iter()
+                                    .map(|payload| {
+                                        let mut response = sdk.respond(payload);
+                                        response["id"] = payload["id"].clone();
+
+                                        response
+                                    })
+                                    .collect(),
+                            ),
+                            None => sdk.respond(&payload),
+                        };
+
+                        Ok::<_, Infallible>(Response::new(Body::from(response.to_string())))
+                    }
+                }))
+            }
+        });
+
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let url = format!("http://{}", server.local_addr());
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        tokio::spawn(server.with_graceful_shutdown(async {
+            signal.await.ok();
+        }));
+
+        MockSdkServer {
+            url,
+            requests,
+            payloads,
+            shutdown: Some(shutdown),
+        }
+    }
+}
+
+pub struct MockSdkServer {
+    url: String,
+    requests: Arc<AtomicUsize>,
+    payloads: Arc<Mutex<Vec<Value>>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockSdkServer {
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn payloads(&self) -> Vec<Value> {
+        self.payloads.lock().expect("Unable to get lock").clone()
+    }
+}
+
+impl Drop for MockSdkServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
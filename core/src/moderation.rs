@@ -0,0 +1,125 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{Api, ApiError};
+
+#[derive(Debug)]
+pub enum ModerationError {
+    ClaimNotOwned(String),
+    UnsignedClaim(String),
+    NoSigningKey(String),
+    Unverified(String, ApiError),
+    Api(ApiError),
+}
+
+impl From<ApiError> for ModerationError {
+    fn from(error: ApiError) -> Self {
+        Self::Api(error)
+    }
+}
+
+impl std::fmt::Display for ModerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::ClaimNotOwned(ref claim_id) => {
+                write!(f, "Claim {} is not owned by a configured account", claim_id)
+            }
+            Self::UnsignedClaim(ref claim_id) => {
+                write!(f, "Claim {} is not published by a channel", claim_id)
+            }
+            Self::NoSigningKey(ref channel) => {
+                write!(f, "No signing key is available for channel {}", channel)
+            }
+            Self::Unverified(ref target, ref error) => {
+                write!(f, "Unable to verify {}: {}", target, error)
+            }
+            Self::Api(ref error) => error.fmt(f),
+        }
+    }
+}
+
+impl Api {
+    pub async fn verify_ownership(
+        &self,
+        claim_ids: &[String],
+        channel_name: Option<&str>,
+    ) -> Result<(), ModerationError> {
+        for claim_id in claim_ids {
+            match self.is_claim_owned(claim_id.clone()).await {
+                Ok(true) => {}
+                Ok(false) => return Err(ModerationError::ClaimNotOwned(claim_id.clone())),
+                Err(err) => {
+                    return Err(ModerationError::Unverified(
+                        format!("claim {}", claim_id),
+                        err,
+                    ))
+                }
+            }
+        }
+
+        if let Some(channel_name) = channel_name {
+            match self.has_channel_signing_key(channel_name.to_string()).await {
+                Ok(true) => {}
+                Ok(false) => return Err(ModerationError::NoSigningKey(channel_name.to_string())),
+                Err(err) => {
+                    return Err(ModerationError::Unverified(
+                        format!("channel {}", channel_name),
+                        err,
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn verify_moderation(&self, claim_ids: &[String]) -> Result<(), ModerationError> {
+        self.verify_ownership(claim_ids, None).await?;
+
+        for claim_id in claim_ids {
+            let channel_id = match self.get_signing_channel_id(claim_id.clone()).await {
+                Ok(Some(channel_id)) => channel_id,
+                Ok(None) => return Err(ModerationError::UnsignedClaim(claim_id.clone())),
+                Err(err) => {
+                    return Err(ModerationError::Unverified(
+                        format!("claim {}", claim_id),
+                        err,
+                    ))
+                }
+            };
+
+            match self.has_channel_id_signing_key(channel_id.clone()).await {
+                Ok(true) => {}
+                Ok(false) => return Err(ModerationError::NoSigningKey(channel_id)),
+                Err(err) => {
+                    return Err(ModerationError::Unverified(
+                        format!("channel {}", channel_id),
+                        err,
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn hide_owned_comments(
+        &self,
+        claim_ids: &[String],
+        comment_ids: Vec<String>,
+    ) -> Result<HashMap<String, Value>, ModerationError> {
+        self.verify_moderation(claim_ids).await?;
+
+        Ok(self.hide_comments(comment_ids).await?)
+    }
+
+    pub async fn abandon_owned_comment(
+        &self,
+        claim_id: String,
+        comment_id: String,
+    ) -> Result<HashMap<String, Value>, ModerationError> {
+        self.verify_moderation(&[claim_id]).await?;
+
+        Ok(self.abandon_comment(comment_id).await?)
+    }
+}
@@ -0,0 +1,368 @@
+use chrono::Utc;
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    time::delay_for,
+};
+
+use crate::{
+    Api, ClaimMetadata, CommentEntity, RetentionPolicy, RuleBundle, SearchQuery, Storage,
+    SyncDelta, SyncError, Syncer, Webhook, Webhooks, DEFAULT_POOL_SIZE,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Clone, Debug)]
+pub enum NotifierEvent {
+    NewComment {
+        comment: CommentEntity,
+        filters: Vec<String>,
+    },
+    EditedComment {
+        previous: Box<CommentEntity>,
+        comment: CommentEntity,
+    },
+    DeletedComment(CommentEntity),
+}
+
+pub struct NotifierBuilder {
+    api: Api,
+    database_url: String,
+    page_size: usize,
+    pool_size: u32,
+    channels: Vec<String>,
+    webhooks: Vec<Webhook>,
+    rules: RuleBundle,
+}
+
+impl NotifierBuilder {
+    pub fn new(api_url: String, database_url: String) -> Self {
+        Self {
+            api: Api::new(api_url),
+            database_url,
+            page_size: DEFAULT_PAGE_SIZE,
+            pool_size: DEFAULT_POOL_SIZE,
+            channels: Vec::new(),
+            webhooks: Vec::new(),
+            rules: RuleBundle::default(),
+        }
+    }
+
+    pub fn with_api(self, api: Api) -> Self {
+        Self { api, ..self }
+    }
+
+    pub fn with_page_size(self, page_size: usize) -> Self {
+        Self { page_size, ..self }
+    }
+
+    pub fn with_pool_size(self, pool_size: u32) -> Self {
+        Self { pool_size, ..self }
+    }
+
+    pub fn with_channels(self, channels: Vec<String>) -> Self {
+        Self { channels, ..self }
+    }
+
+    pub fn with_webhook(mut self, webhook: Webhook) -> Self {
+        self.webhooks.push(webhook);
+
+        self
+    }
+
+    pub fn with_rules(self, rules: RuleBundle) -> Self {
+        Self { rules, ..self }
+    }
+
+    pub fn build(self) -> Result<Notifier, diesel::ConnectionError> {
+        let storage = Storage::open_with_pool_size(self.database_url, self.pool_size)?;
+
+        let filters = self
+            .rules
+            .filters
+            .iter()
+            .filter_map(|search| match search.query.parse::<SearchQuery>() {
+                Ok(query) => Some((search.name.clone(), query)),
+                Err(err) => {
+                    error!("Skipping invalid filter {}: {}", &search.name, err);
+
+                    None
+                }
+            })
+            .collect::<Vec<(String, SearchQuery)>>();
+
+        Ok(Notifier {
+            api: self.api,
+            storage,
+            page_size: self.page_size,
+            channels: self.channels,
+            webhooks: Webhooks::new(self.webhooks),
+            retention: RetentionPolicy::new(None, &self.rules.claims),
+            filters,
+            rules: self.rules,
+            subscribers: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+pub struct Notifier {
+    api: Api,
+    storage: Storage,
+    page_size: usize,
+    channels: Vec<String>,
+    webhooks: Webhooks,
+    retention: RetentionPolicy,
+    filters: Vec<(String, SearchQuery)>,
+    rules: RuleBundle,
+    subscribers: Mutex<Vec<UnboundedSender<NotifierEvent>>>,
+}
+
+impl Notifier {
+    pub fn api(&self) -> &Api {
+        &self.api
+    }
+
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    pub fn subscribe_events(&self) -> UnboundedReceiver<NotifierEvent> {
+        let (sender, receiver) = unbounded_channel();
+
+        self.subscribers
+            .lock()
+            .expect("Unable to get lock")
+            .push(sender);
+
+        receiver
+    }
+
+    fn publish(&self, event: &NotifierEvent) {
+        self.subscribers
+            .lock()
+            .expect("Unable to get lock")
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    fn is_blocked(&self, comment: &CommentEntity) -> bool {
+        let now = Utc::now().naive_utc();
+
+        self.rules
+            .blocklist
+            .iter()
+            .any(|snooze| snooze.until > now && snooze.matches(comment))
+    }
+
+    fn matching_filters(&self, comment: &CommentEntity) -> Vec<String> {
+        self.filters
+            .iter()
+            .filter(|(_, query)| query.matches(comment))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    async fn resolve_metadata(&self, delta: &SyncDelta) -> HashMap<String, ClaimMetadata> {
+        let mut urls = delta
+            .new
+            .iter()
+            .map(|(_, claim, _)| claim.permanent_url())
+            .collect::<Vec<String>>();
+        urls.sort();
+        urls.dedup();
+
+        if urls.is_empty() {
+            return HashMap::new();
+        }
+
+        self.api
+            .resolve_claims(urls)
+            .await
+            .map_err(|err| {
+                error!("Unable to resolve claims: {}", err);
+            })
+            .unwrap_or_default()
+    }
+
+    async fn notify_webhooks(&self, comment: &CommentEntity) {
+        for webhook in self.webhooks.webhooks() {
+            if let Err(err) = self.webhooks.send(&webhook, comment).await {
+                error!("Unable to send webhook to {}: {}", &webhook.url, err);
+            }
+        }
+    }
+
+    pub async fn run_once(&self) -> Result<Vec<NotifierEvent>, SyncError> {
+        let delta = Syncer::new(&self.api, &self.storage, self.page_size)
+            .with_channels(&self.channels)
+            .delta()
+            .await?;
+        let claim_metadata = self.resolve_metadata(&delta).await;
+        let now = Utc::now().naive_utc();
+        let mut events = Vec::new();
+
+        for (account, mut claim, comment) in delta.new {
+            if self
+                .retention
+                .is_expired(&comment.claim_id, comment.timestamp.naive_utc(), now)
+            {
+                continue;
+            }
+
+            claim.metadata = claim_metadata.get(&claim.id).cloned().unwrap_or_default();
+
+            let comment = self.storage.save_comment(account, claim, comment)?;
+
+            if self.is_blocked(&comment) {
+                debug!("Skipping event for blocked {}", &comment.id);
+
+                continue;
+            }
+
+            self.notify_webhooks(&comment).await;
+
+            events.push(NotifierEvent::NewComment {
+                filters: self.matching_filters(&comment),
+                comment,
+            });
+        }
+
+        for (previous, comment) in delta.edited {
+            let comment = self.storage.update_comment(previous.clone(), &comment)?;
+
+            events.push(NotifierEvent::EditedComment {
+                previous: Box::new(previous),
+                comment,
+            });
+        }
+
+        for comment in delta.deleted {
+            self.storage.delete_comment_by_id(comment.id.clone())?;
+
+            events.push(NotifierEvent::DeletedComment(comment));
+        }
+
+        events.iter().for_each(|event| self.publish(event));
+
+        Ok(events)
+    }
+
+    pub async fn run_scheduled(&self, interval: Duration) {
+        loop {
+            match self.run_once().await {
+                Ok(events) => info!("Notifier run produced {} events", events.len()),
+                Err(err) => error!("Notifier run failed: {}", err),
+            }
+
+            delay_for(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{NotifierBuilder, NotifierEvent};
+    use crate::{testing, CommentEntity, RuleBundle, SavedSearch, Snooze};
+
+    fn comment(commenter_name: &str, text: &str) -> CommentEntity {
+        CommentEntity {
+            claim_name: "claim".to_string(),
+            commenter_name: commenter_name.to_string(),
+            commenter_url: format!("lbry://{}", commenter_name),
+            comment: text.to_string(),
+            ..testing::comment_entity("comment_id", "claim_id")
+        }
+    }
+
+    #[test]
+    fn notifier_should_apply_rules_and_publish_events() {
+        let notifier =
+            NotifierBuilder::new("http://localhost:5279".to_string(), ":memory:".to_string())
+                .with_rules(RuleBundle {
+                    filters: vec![SavedSearch {
+                        name: "questions".to_string(),
+                        query: "how".to_string(),
+                        recipient: None,
+                    }],
+                    blocklist: vec![Snooze {
+                        kind: "commenter".to_string(),
+                        target: "@spammer".to_string(),
+                        until: NaiveDate::from_ymd(2100, 1, 1).and_hms(0, 0, 0),
+                    }],
+                    claims: Vec::new(),
+                })
+                .build()
+                .expect("Unable to build notifier");
+
+        assert!(notifier.is_blocked(&comment("@spammer", "Hello")));
+        assert!(!notifier.is_blocked(&comment("@fan", "Hello")));
+        assert_eq!(
+            notifier.matching_filters(&comment("@fan", "How did you do this?")),
+            vec!["questions".to_string()]
+        );
+
+        let mut events = notifier.subscribe_events();
+        let dropped = notifier.subscribe_events();
+        drop(dropped);
+
+        notifier.publish(&NotifierEvent::DeletedComment(comment("@fan", "Hello")));
+
+        match events.try_recv() {
+            Ok(NotifierEvent::DeletedComment(comment)) => assert_eq!(comment.comment, "Hello"),
+            res => panic!("Expected a deleted comment event, got {:?}", res),
+        }
+        assert_eq!(notifier.subscribers.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "test-support")]
+    #[tokio::test]
+    async fn notifier_should_run_once_on_watched_channels() {
+        let server = crate::testing::MockSdk::new()
+            .with_account("account", "Account")
+            .with_channel("account", "watched_id", "@watched")
+            .with_channel("account", "ignored_id", "@ignored")
+            .with_channel_claim("watched_id", "watched", "watched-video")
+            .with_channel_claim("ignored_id", "ignored", "ignored-video")
+            .with_comment("watched", "first", "Hello")
+            .with_comment("watched", "second", "How did you do this?")
+            .with_comment("ignored", "third", "Hi")
+            .start();
+        let notifier = NotifierBuilder::new(server.url(), ":memory:".to_string())
+            .with_channels(vec!["@watched".to_string()])
+            .with_rules(RuleBundle {
+                filters: vec![SavedSearch {
+                    name: "questions".to_string(),
+                    query: "how".to_string(),
+                    recipient: None,
+                }],
+                ..RuleBundle::default()
+            })
+            .build()
+            .expect("Unable to build notifier");
+
+        let events = notifier
+            .run_once()
+            .await
+            .expect("Unable to run notifier")
+            .into_iter()
+            .map(|event| match event {
+                NotifierEvent::NewComment { comment, filters } => (comment.id, filters),
+                event => panic!("Expected a new comment event, got {:?}", event),
+            })
+            .collect::<Vec<(String, Vec<String>)>>();
+
+        assert_eq!(
+            events,
+            vec![
+                ("first".to_string(), Vec::new()),
+                ("second".to_string(), vec!["questions".to_string()]),
+            ]
+        );
+        assert!(notifier
+            .run_once()
+            .await
+            .expect("Unable to run notifier")
+            .is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use futures::prelude::Future;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+#[derive(Clone, Debug, Default)]
+pub struct ClaimLocks {
+    locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+}
+
+impl ClaimLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locks.lock().expect("Unable to get lock").is_empty()
+    }
+
+    pub async fn run_in_order<F: Future>(&self, claim_id: &str, f: F) -> F::Output {
+        let lock = self
+            .locks
+            .lock()
+            .expect("Unable to get lock")
+            .entry(claim_id.to_string())
+            .or_default()
+            .clone();
+
+        let output = {
+            let _guard = lock.lock().await;
+
+            f.await
+        };
+
+        let mut locks = self.locks.lock().expect("Unable to get lock");
+
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(claim_id);
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::{self, StreamExt};
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+    use tokio::time::delay_for;
+
+    use super::ClaimLocks;
+
+    #[tokio::test]
+    async fn run_in_order_should_serialize_each_claim() {
+        let locks = ClaimLocks::new();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+
+        let comments = vec![
+            ("claim_a", "a1", 30),
+            ("claim_a", "a2", 10),
+            ("claim_b", "b1", 5),
+            ("claim_a", "a3", 0),
+        ];
+
+        stream::iter(comments)
+            .for_each_concurrent(None, |(claim_id, comment_id, delay)| {
+                let locks = locks.clone();
+                let sent = sent.clone();
+
+                async move {
+                    locks
+                        .run_in_order(claim_id, async {
+                            delay_for(Duration::from_millis(delay)).await;
+
+                            sent.lock().unwrap().push(comment_id);
+                        })
+                        .await;
+                }
+            })
+            .await;
+
+        assert_eq!(*sent.lock().unwrap(), vec!["b1", "a1", "a2", "a3"]);
+        assert!(locks.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use toml::Value;
+
+#[derive(Debug, Default, Deserialize)]
+struct ProfileFile {
+    #[serde(default)]
+    profile: HashMap<String, BTreeMap<String, Value>>,
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(format_value)
+            .collect::<Vec<String>>()
+            .join(","),
+        _ => value.to_string(),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigProfiles {
+    profiles: HashMap<String, Vec<(String, String)>>,
+}
+
+impl ConfigProfiles {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file = toml::from_str::<ProfileFile>(text).map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            profiles: file
+                .profile
+                .into_iter()
+                .map(|(name, vars)| {
+                    let vars = vars
+                        .iter()
+                        .map(|(key, value)| (key.to_string(), format_value(value)))
+                        .collect::<Vec<(String, String)>>();
+
+                    (name, vars)
+                })
+                .collect(),
+        })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self
+            .profiles
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+        names.sort();
+
+        names
+    }
+
+    pub fn vars(&self, name: &str) -> Option<&[(String, String)]> {
+        self.profiles.get(name).map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigProfiles;
+
+    #[test]
+    fn parse_should_read_profile_vars() {
+        let profiles = ConfigProfiles::parse(
+            r#"
+            [profile.staging]
+            API_URL = "http://staging.local:5279"
+            SMTP_RECIPIENTS = ["qa@staging.local", "dev@staging.local|es"]
+            NOTIFY_PING = true
+
+            [profile.prod]
+            DATABASE_URL = "/var/lib/notifier/data.db"
+            "#,
+        )
+        .expect("Invalid profiles");
+
+        assert_eq!(profiles.names(), vec!["prod", "staging"]);
+        assert_eq!(
+            profiles.vars("staging").map(|vars| vars.to_vec()),
+            Some(vec![
+                (
+                    "API_URL".to_string(),
+                    "http://staging.local:5279".to_string()
+                ),
+                ("NOTIFY_PING".to_string(), "true".to_string()),
+                (
+                    "SMTP_RECIPIENTS".to_string(),
+                    "qa@staging.local,dev@staging.local|es".to_string()
+                ),
+            ])
+        );
+        assert!(profiles.vars("dev").is_none());
+        assert!(ConfigProfiles::parse("[profile.dev]\nAPI_URL =").is_err());
+        assert_eq!(ConfigProfiles::parse(""), Ok(ConfigProfiles::default()));
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::delay_for,
+};
+
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Arc<Mutex<Option<Instant>>>,
+    paused_until: Arc<Mutex<Option<Instant>>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: Option<f64>, max_in_flight: Option<usize>) -> Self {
+        Self {
+            interval: requests_per_second
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| Duration::from_secs_f64(1.0 / rate)),
+            next_slot: Arc::new(Mutex::new(None)),
+            paused_until: Arc::new(Mutex::new(None)),
+            in_flight: max_in_flight
+                .filter(|max| *max > 0)
+                .map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    pub fn pause(&self, duration: Duration) {
+        self.pause_at(Instant::now(), duration);
+    }
+
+    fn pause_at(&self, now: Instant, duration: Duration) {
+        let mut paused_until = self.paused_until.lock().expect("Unable to get lock");
+
+        *paused_until = Some(paused_until.unwrap_or(now).max(now + duration));
+    }
+
+    fn reserve(&self, now: Instant) -> Duration {
+        let resumes_at = self
+            .paused_until
+            .lock()
+            .expect("Unable to get lock")
+            .unwrap_or(now)
+            .max(now);
+
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return resumes_at - now,
+        };
+
+        let mut next_slot = self.next_slot.lock().expect("Unable to get lock");
+        let slot = next_slot
+            .map(|next| next.max(resumes_at))
+            .unwrap_or(resumes_at);
+
+        *next_slot = Some(slot + interval);
+
+        slot - now
+    }
+
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        let permit = match &self.in_flight {
+            Some(semaphore) => Some(semaphore.acquire().await),
+            None => None,
+        };
+
+        let delay = self.reserve(Instant::now());
+
+        if delay > Duration::from_secs(0) {
+            delay_for(delay).await;
+        }
+
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    #[test]
+    fn reserve_should_space_requests() {
+        let limiter = RateLimiter::new(Some(4.0), None);
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(250));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(500));
+        assert_eq!(
+            limiter.reserve(now + Duration::from_secs(2)),
+            Duration::from_secs(0)
+        );
+
+        let unlimited = RateLimiter::default();
+        assert_eq!(unlimited.reserve(now), Duration::from_secs(0));
+        assert_eq!(unlimited.reserve(now), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn reserve_should_wait_out_pauses() {
+        let limiter = RateLimiter::new(Some(4.0), None);
+        let now = Instant::now();
+
+        limiter.pause_at(now, Duration::from_secs(2));
+        limiter.pause_at(now, Duration::from_secs(1));
+
+        assert_eq!(limiter.reserve(now), Duration::from_secs(2));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(2250));
+        assert_eq!(
+            limiter.reserve(now + Duration::from_secs(5)),
+            Duration::from_secs(0)
+        );
+
+        let unlimited = RateLimiter::default();
+        unlimited.pause_at(now, Duration::from_secs(3));
+        assert_eq!(unlimited.reserve(now), Duration::from_secs(3));
+        assert_eq!(
+            unlimited.reserve(now + Duration::from_secs(4)),
+            Duration::from_secs(0)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::Reaction;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ReactionCounts {
+    #[serde(default)]
+    pub like: i32,
+    #[serde(default)]
+    pub dislike: i32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ReactionList {
+    #[serde(default)]
+    pub others_reactions: HashMap<String, ReactionCounts>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionChange {
+    pub comment_id: String,
+    pub likes: i32,
+    pub dislikes: i32,
+}
+
+pub fn reaction_changes(previous: &[Reaction], current: &[Reaction]) -> Vec<ReactionChange> {
+    let previous = previous
+        .iter()
+        .map(|reaction| (reaction.comment_id.as_str(), reaction))
+        .collect::<HashMap<&str, &Reaction>>();
+
+    current
+        .iter()
+        .filter_map(|reaction| {
+            let (likes, dislikes) = previous
+                .get(reaction.comment_id.as_str())
+                .map(|old| (old.likes, old.dislikes))
+                .unwrap_or((0, 0));
+
+            let change = ReactionChange {
+                comment_id: reaction.comment_id.clone(),
+                likes: reaction.likes - likes,
+                dislikes: reaction.dislikes - dislikes,
+            };
+
+            if change.likes > 0 || change.dislikes > 0 {
+                Some(change)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reaction_changes, ReactionChange};
+    use crate::Reaction;
+
+    fn reaction(comment_id: &str, likes: i32, dislikes: i32) -> Reaction {
+        Reaction {
+            comment_id: comment_id.to_string(),
+            likes,
+            dislikes,
+        }
+    }
+
+    #[test]
+    fn reaction_changes_should_report_new_reactions() {
+        let previous = vec![reaction("a", 1, 0), reaction("b", 2, 2)];
+        let current = vec![
+            reaction("a", 3, 1),
+            reaction("b", 1, 2),
+            reaction("c", 0, 1),
+        ];
+
+        assert_eq!(
+            reaction_changes(&previous, &current),
+            vec![
+                ReactionChange {
+                    comment_id: "a".to_string(),
+                    likes: 2,
+                    dislikes: 1
+                },
+                ReactionChange {
+                    comment_id: "c".to_string(),
+                    likes: 0,
+                    dislikes: 1
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use reqwest::Url;
+use sha1::{Digest, Sha1};
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, sync::Arc};
+
+use crate::Storage;
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct ReadLinks {
+    base_url: String,
+    secret: String,
+}
+
+impl ReadLinks {
+    pub fn new(base_url: String, secret: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret,
+        }
+    }
+
+    pub fn token(&self, comment_ids: &[String]) -> String {
+        let mut key = self.secret.as_bytes().to_vec();
+
+        if key.len() > HMAC_BLOCK_SIZE {
+            key = Sha1::digest(&key).to_vec();
+        }
+
+        key.resize(HMAC_BLOCK_SIZE, 0);
+
+        let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+        let inner = Sha1::new()
+            .chain(pad(0x36))
+            .chain(comment_ids.join(","))
+            .finalize();
+
+        Sha1::new()
+            .chain(pad(0x5c))
+            .chain(inner)
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    pub fn verify(&self, comment_ids: &[String], token: &str) -> bool {
+        let expected = self.token(comment_ids);
+
+        expected.len() == token.len()
+            && expected
+                .bytes()
+                .zip(token.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    pub fn url(&self, comment_ids: &[String]) -> String {
+        Url::parse_with_params(
+            &format!("{}/read", self.base_url),
+            &[
+                ("ids", comment_ids.join(",")),
+                ("token", self.token(comment_ids)),
+            ],
+        )
+        .map(|url| url.to_string())
+        .expect("Invalid read link URL")
+    }
+
+    fn respond(&self, req: &Request<Body>, storage: &Storage) -> Response<Body> {
+        let reply = |status: StatusCode, text: String| {
+            Response::builder()
+                .status(status)
+                .body(Body::from(text))
+                .expect("Could not build response")
+        };
+
+        if req.method() != Method::GET || !req.uri().path().ends_with("/read") {
+            return reply(StatusCode::NOT_FOUND, "Not found".to_string());
+        }
+
+        let params = Url::parse(&format!(
+            "http://localhost/?{}",
+            req.uri().query().unwrap_or_default()
+        ))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_else(|_| HashMap::new());
+        let comment_ids = params
+            .get("ids")
+            .map(|ids| {
+                ids.split(',')
+                    .filter(|id| !id.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        let token = params.get("token").map(String::as_str).unwrap_or_default();
+
+        if comment_ids.is_empty() || !self.verify(&comment_ids, token) {
+            return reply(StatusCode::FORBIDDEN, "Invalid read link".to_string());
+        }
+
+        match storage.mark_comments_as_read(comment_ids) {
+            Ok(count) => reply(StatusCode::OK, format!("Marked {} comments as read", count)),
+            Err(err) => reply(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Unable to mark comments as read: {}", err),
+            ),
+        }
+    }
+
+    pub async fn serve(self, addr: SocketAddr, storage: Arc<Storage>) -> Result<(), hyper::Error> {
+        let links = Arc::new(self);
+
+        let make_service = make_service_fn(move |_| {
+            let links = links.clone();
+            let storage = storage.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let response = links.respond(&req, &storage);
+
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        Server::bind(&addr).serve(make_service).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{Body, Request, StatusCode};
+
+    use super::ReadLinks;
+    use crate::{testing, Storage};
+
+    #[test]
+    fn read_links_should_sign_comment_ids() {
+        let links = ReadLinks::new("https://example.com/".to_string(), "secret".to_string());
+        let ids = vec!["a".to_string(), "b".to_string()];
+        let token = links.token(&ids);
+
+        assert_eq!(
+            ReadLinks::new("".to_string(), "key".to_string())
+                .token(&["The quick brown fox jumps over the lazy dog".to_string()]),
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+        assert!(links.verify(&ids, &token));
+        assert!(!links.verify(&ids[..1], &token));
+        assert!(!ReadLinks::new("".to_string(), "other".to_string()).verify(&ids, &token));
+        assert_eq!(
+            links.url(&ids),
+            format!("https://example.com/read?ids=a%2Cb&token={}", token)
+        );
+    }
+
+    #[test]
+    fn read_links_should_mark_comments_as_read() {
+        let storage = Storage::open(":memory:".to_string()).expect("Unable to open storage");
+        let links = ReadLinks::new("https://example.com".to_string(), "secret".to_string());
+        let ids = vec!["id".to_string()];
+
+        storage
+            .save_comment(
+                testing::account("account"),
+                testing::claim("claim_id"),
+                testing::comment("id", "claim_id"),
+            )
+            .expect("Unable to save comment");
+
+        let request = |uri: String| {
+            Request::get(uri)
+                .body(Body::empty())
+                .expect("Unable to build request")
+        };
+
+        let forged = links.respond(&request("/read?ids=id&token=forged".to_string()), &storage);
+        assert_eq!(forged.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            storage
+                .get_unread_comments()
+                .expect("Unable to get unread comments")
+                .len(),
+            1
+        );
+
+        let uri = links.url(&ids).replacen("https://example.com", "", 1);
+        let response = links.respond(&request(uri), &storage);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(storage
+            .get_unread_comments()
+            .expect("Unable to get unread comments")
+            .is_empty());
+    }
+}
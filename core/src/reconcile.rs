@@ -0,0 +1,106 @@
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimCount {
+    pub claim_id: String,
+    pub claim_name: String,
+    pub remote: usize,
+    pub local: usize,
+}
+
+impl ClaimCount {
+    pub fn is_consistent(&self) -> bool {
+        self.remote == self.local
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconcileReport {
+    pub checked: usize,
+    pub mismatches: Vec<ClaimCount>,
+}
+
+impl ReconcileReport {
+    pub fn new(counts: Vec<ClaimCount>) -> Self {
+        let checked = counts.len();
+        let mut mismatches = counts
+            .into_iter()
+            .filter(|count| !count.is_consistent())
+            .collect::<Vec<ClaimCount>>();
+
+        mismatches.sort_by(|a, b| a.claim_id.cmp(&b.claim_id));
+
+        Self {
+            checked,
+            mismatches,
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn status(&self) -> &'static str {
+        if self.is_consistent() {
+            "ok"
+        } else {
+            "mismatch"
+        }
+    }
+
+    pub fn details(&self) -> String {
+        if self.is_consistent() {
+            return format!("{} claims consistent", self.checked);
+        }
+
+        let mismatches = self
+            .mismatches
+            .iter()
+            .map(|count| {
+                format!(
+                    "{} ({}): {} remote, {} local",
+                    count.claim_name, count.claim_id, count.remote, count.local
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("; ");
+
+        format!(
+            "{} of {} claims mismatched: {}",
+            self.mismatches.len(),
+            self.checked,
+            mismatches
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClaimCount, ReconcileReport};
+
+    fn count(claim_id: &str, remote: usize, local: usize) -> ClaimCount {
+        ClaimCount {
+            claim_id: claim_id.to_string(),
+            claim_name: format!("{}-name", claim_id),
+            remote,
+            local,
+        }
+    }
+
+    #[test]
+    fn report_should_flag_mismatched_claims() {
+        let report =
+            ReconcileReport::new(vec![count("c", 4, 5), count("a", 3, 3), count("b", 7, 2)]);
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.mismatches, vec![count("b", 7, 2), count("c", 4, 5)]);
+        assert_eq!(report.status(), "mismatch");
+        assert_eq!(
+            report.details(),
+            "2 of 3 claims mismatched: b-name (b): 7 remote, 2 local; c-name (c): 4 remote, 5 local"
+        );
+
+        let report = ReconcileReport::new(vec![count("a", 3, 3)]);
+
+        assert_eq!(report.status(), "ok");
+        assert_eq!(report.details(), "1 claims consistent");
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{Datelike, NaiveDate};
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::CommentEntity;
+
+pub const DEFAULT_REPORT_TEMPLATE: &str = "Comment report for {month}
+
+      Top claims
+      ---
+{top_claims}
+
+      Top commenters
+      ---
+{top_commenters}
+
+      Biggest tippers
+      ---
+{top_tippers}
+
+      Comments by source
+      ---
+{sources}
+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonthlyReport {
+    pub month: NaiveDate,
+    pub top_claims: Vec<(String, usize)>,
+    pub top_commenters: Vec<(String, usize)>,
+    pub top_tippers: Vec<(String, f64)>,
+    pub sources: Vec<(String, usize)>,
+}
+
+pub fn previous_month(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let end = NaiveDate::from_ymd(today.year(), today.month(), 1);
+    let start = if end.month() == 1 {
+        NaiveDate::from_ymd(end.year() - 1, 12, 1)
+    } else {
+        NaiveDate::from_ymd(end.year(), end.month() - 1, 1)
+    };
+
+    (start, end)
+}
+
+fn top_by<T: Copy + PartialOrd>(totals: HashMap<&str, T>, limit: usize) -> Vec<(String, T)> {
+    let mut totals = totals
+        .into_iter()
+        .map(|(name, total)| (name.to_string(), total))
+        .collect::<Vec<(String, T)>>();
+
+    totals.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+    totals.truncate(limit);
+
+    totals
+}
+
+impl MonthlyReport {
+    pub fn new(month: NaiveDate, comments: &[CommentEntity], limit: usize) -> Self {
+        let mut claims: HashMap<&str, usize> = HashMap::new();
+        let mut commenters: HashMap<&str, usize> = HashMap::new();
+        let mut tippers: HashMap<&str, f64> = HashMap::new();
+        let mut sources: HashMap<&str, usize> = HashMap::new();
+
+        for comment in comments {
+            *sources.entry(&comment.source).or_default() += 1;
+            *claims.entry(comment.claim_display_name()).or_default() += 1;
+            *commenters.entry(&comment.commenter_name).or_default() += 1;
+
+            if comment.support_amount > 0.0 {
+                *tippers.entry(&comment.commenter_name).or_default() += comment.support_amount;
+            }
+        }
+
+        Self {
+            month,
+            top_claims: top_by(claims, limit),
+            top_commenters: top_by(commenters, limit),
+            top_tippers: top_by(tippers, limit),
+            sources: top_by(sources, usize::MAX),
+        }
+    }
+
+    pub fn render(&self, template: &str) -> String {
+        fn render_rows<T: std::fmt::Display>(rows: &[(String, T)], unit: &str) -> String {
+            if rows.is_empty() {
+                return "      None".to_string();
+            }
+
+            rows.iter()
+                .enumerate()
+                .map(|(index, (name, total))| {
+                    format!("      {}. {} ({} {})", index + 1, name, total, unit)
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+
+        template
+            .replace("{month}", &self.month.format("%B %Y").to_string())
+            .replace("{top_claims}", &render_rows(&self.top_claims, "comments"))
+            .replace(
+                "{top_commenters}",
+                &render_rows(&self.top_commenters, "comments"),
+            )
+            .replace("{top_tippers}", &render_rows(&self.top_tippers, "LBC"))
+            .replace("{sources}", &render_rows(&self.sources, "comments"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{previous_month, MonthlyReport, DEFAULT_REPORT_TEMPLATE};
+    use crate::{testing, CommentEntity};
+
+    fn comment(claim_name: &str, commenter_name: &str, support_amount: f64) -> CommentEntity {
+        CommentEntity {
+            claim_name: claim_name.to_string(),
+            commenter_id: format!("{}_id", commenter_name),
+            commenter_name: commenter_name.to_string(),
+            commenter_url: "commenter_url".to_string(),
+            comment: "comment".to_string(),
+            timestamp: NaiveDate::from_ymd(2020, 6, 15).and_hms(0, 0, 0),
+            support_amount,
+            ..testing::comment_entity("id", &format!("{}_id", claim_name))
+        }
+    }
+
+    #[test]
+    fn previous_month_should_wrap_years() {
+        assert_eq!(
+            previous_month(NaiveDate::from_ymd(2020, 7, 1)),
+            (
+                NaiveDate::from_ymd(2020, 6, 1),
+                NaiveDate::from_ymd(2020, 7, 1)
+            )
+        );
+        assert_eq!(
+            previous_month(NaiveDate::from_ymd(2020, 1, 20)).0,
+            NaiveDate::from_ymd(2019, 12, 1)
+        );
+    }
+
+    #[test]
+    fn monthly_report_should_rank() {
+        let comments = vec![
+            comment("video", "@a", 0.0),
+            comment("video", "@b", 5.0),
+            comment("stream", "@b", 1.0),
+            comment("video", "@c", 10.0),
+        ];
+
+        let mut comments = comments;
+        comments[3].source = "odysee".to_string();
+
+        let report = MonthlyReport::new(NaiveDate::from_ymd(2020, 6, 1), &comments, 2);
+
+        assert_eq!(
+            report.top_claims,
+            vec![("video".to_string(), 3), ("stream".to_string(), 1)]
+        );
+        assert_eq!(
+            report.top_commenters,
+            vec![("@b".to_string(), 2), ("@a".to_string(), 1)]
+        );
+        assert_eq!(
+            report.top_tippers,
+            vec![("@c".to_string(), 10.0), ("@b".to_string(), 6.0)]
+        );
+
+        assert_eq!(
+            report.sources,
+            vec![("lbry".to_string(), 3), ("odysee".to_string(), 1)]
+        );
+
+        let rendered = report.render(DEFAULT_REPORT_TEMPLATE);
+        assert!(rendered.starts_with("Comment report for June 2020"));
+        assert!(rendered.contains("1. video (3 comments)"));
+        assert!(rendered.contains("1. @c (10 LBC)"));
+        assert!(rendered.contains("2. odysee (1 comments)"));
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{Duration, NaiveDateTime};
+use std::collections::HashMap;
+
+use crate::{parse_duration, ClaimSetting};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetentionPolicy {
+    default_days: Option<i32>,
+    claim_days: HashMap<String, Option<i32>>,
+}
+
+impl RetentionPolicy {
+    pub fn new(default_days: Option<i32>, settings: &[ClaimSetting]) -> Self {
+        let claim_days = settings
+            .iter()
+            .map(|setting| (setting.claim_id.clone(), setting.retention_days))
+            .collect::<HashMap<String, Option<i32>>>();
+
+        Self {
+            default_days,
+            claim_days,
+        }
+    }
+
+    pub fn retention_days(&self, claim_id: &str) -> Option<i32> {
+        self.claim_days
+            .get(claim_id)
+            .cloned()
+            .unwrap_or(self.default_days)
+    }
+
+    pub fn claim_cutoffs(&self, now: NaiveDateTime) -> Vec<(String, NaiveDateTime)> {
+        self.claim_days
+            .iter()
+            .filter_map(|(claim_id, days)| {
+                days.map(|days| (claim_id.clone(), now - Duration::days(days as i64)))
+            })
+            .collect()
+    }
+
+    pub fn default_cutoff(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.default_days
+            .map(|days| now - Duration::days(days as i64))
+    }
+
+    pub fn configured_claim_ids(&self) -> Vec<String> {
+        self.claim_days.keys().cloned().collect()
+    }
+
+    pub fn is_expired(&self, claim_id: &str, timestamp: NaiveDateTime, now: NaiveDateTime) -> bool {
+        self.retention_days(claim_id)
+            .map(|days| timestamp < now - Duration::days(days as i64))
+            .unwrap_or(false)
+    }
+}
+
+pub fn parse_retention(value: &str) -> Result<Option<i32>, String> {
+    if value == "forever" {
+        return Ok(None);
+    }
+
+    let days = parse_duration(value)?.num_days();
+
+    if days < 1 {
+        return Err(format!("Retention {} is shorter than a day", value));
+    }
+
+    Ok(Some(days as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{parse_retention, RetentionPolicy};
+    use crate::ClaimSetting;
+
+    #[test]
+    fn retention_policy_should_prefer_claim_settings() {
+        let now = NaiveDate::from_ymd(2020, 6, 10).and_hms(0, 0, 0);
+        let policy = RetentionPolicy::new(
+            Some(30),
+            &[
+                ClaimSetting {
+                    claim_id: "livestream".to_string(),
+                    retention_days: Some(7),
+                },
+                ClaimSetting {
+                    claim_id: "video".to_string(),
+                    retention_days: None,
+                },
+            ],
+        );
+
+        let old = NaiveDate::from_ymd(2020, 6, 1).and_hms(0, 0, 0);
+        let ancient = NaiveDate::from_ymd(2019, 6, 1).and_hms(0, 0, 0);
+
+        assert!(policy.is_expired("livestream", old, now));
+        assert!(!policy.is_expired("video", ancient, now));
+        assert!(!policy.is_expired("other", old, now));
+        assert!(policy.is_expired("other", ancient, now));
+
+        assert_eq!(
+            policy.claim_cutoffs(now),
+            vec![(
+                "livestream".to_string(),
+                NaiveDate::from_ymd(2020, 6, 3).and_hms(0, 0, 0)
+            )]
+        );
+        assert_eq!(RetentionPolicy::new(None, &[]).default_cutoff(now), None);
+    }
+
+    #[test]
+    fn parse_retention_should_work() {
+        assert_eq!(parse_retention("forever"), Ok(None));
+        assert_eq!(parse_retention("7d"), Ok(Some(7)));
+        assert_eq!(parse_retention("2w"), Ok(Some(14)));
+        assert!(parse_retention("12h").is_err());
+        assert!(parse_retention("soon").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = (attempt.max(1) - 1).min(16) as u32;
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.pow(exponent))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        if self.jitter && backoff.as_millis() > 1 {
+            let millis = backoff.as_millis() as u64;
+
+            Duration::from_millis(rand::thread_rng().gen_range(millis / 2, millis))
+        } else {
+            backoff
+        }
+    }
+}
+
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => DateTime::parse_from_rfc2822(value).ok().map(|date| {
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or_default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+
+    use super::{parse_retry_after, RetryPolicy};
+
+    #[test]
+    fn parse_retry_after_should_read_seconds_and_dates() {
+        let now = Utc.ymd(2020, 9, 5).and_hms(10, 0, 0);
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 05 Sep 2020 10:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 05 Sep 2020 09:00:00 GMT", now),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn backoff_should_grow_exponentially() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            jitter: false,
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn backoff_should_jitter_within_half() {
+        let policy = RetryPolicy {
+            jitter: true,
+            ..RetryPolicy::default()
+        };
+
+        (1..10).for_each(|attempt| {
+            let backoff = policy.backoff(attempt);
+            let ceiling = RetryPolicy {
+                jitter: false,
+                ..policy.clone()
+            }
+            .backoff(attempt);
+
+            assert!(backoff >= ceiling / 2 && backoff <= ceiling);
+        });
+    }
+}
@@ -1,3 +1,61 @@
+table! {
+    canned_replies (name) {
+        name -> Text,
+        body -> Text,
+        channel_name -> Nullable<Text>,
+    }
+}
+
+table! {
+    claim_settings (claim_id) {
+        claim_id -> Text,
+        retention_days -> Nullable<Integer>,
+    }
+}
+
+table! {
+    claims (id) {
+        id -> Text,
+        name -> Text,
+        title -> Nullable<Text>,
+        view_count -> Nullable<BigInt>,
+        effective_amount -> Nullable<Double>,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    comment_cursors (claim_id) {
+        claim_id -> Text,
+        next_page -> Integer,
+        total_pages -> Integer,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    comment_reactions (comment_id) {
+        comment_id -> Text,
+        likes -> Integer,
+        dislikes -> Integer,
+    }
+}
+
+table! {
+    comment_tags (comment_id, tag) {
+        comment_id -> Text,
+        tag -> Text,
+    }
+}
+
+table! {
+    commenter_milestones (commenter_id, milestone) {
+        commenter_id -> Text,
+        milestone -> BigInt,
+        reached_at -> Timestamp,
+    }
+}
+
 table! {
     comments (id) {
         id -> Text,
@@ -10,5 +68,114 @@ table! {
         comment -> Text,
         is_hidden -> Bool,
         timestamp -> Timestamp,
+        support_amount -> Double,
+        is_read -> Bool,
+        parent_id -> Nullable<Text>,
+        claim_title -> Nullable<Text>,
+        claim_thumbnail_url -> Nullable<Text>,
+        claim_canonical_url -> Nullable<Text>,
+        source -> Text,
+        is_signature_valid -> Nullable<Bool>,
+    }
+}
+
+table! {
+    comments_archive (id) {
+        id -> Text,
+        account_id -> Text,
+        claim_id -> Text,
+        claim_name -> Text,
+        commenter_id -> Text,
+        commenter_name -> Text,
+        commenter_url -> Text,
+        comment -> Text,
+        is_hidden -> Bool,
+        timestamp -> Timestamp,
+        support_amount -> Double,
+        is_read -> Bool,
+        parent_id -> Nullable<Text>,
+        claim_title -> Nullable<Text>,
+        claim_thumbnail_url -> Nullable<Text>,
+        claim_canonical_url -> Nullable<Text>,
+        source -> Text,
+        is_signature_valid -> Nullable<Bool>,
+    }
+}
+
+table! {
+    deliveries (comment_id, target) {
+        comment_id -> Text,
+        target -> Text,
+        status -> Text,
+        attempts -> Integer,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    notification_channels (name) {
+        name -> Text,
+        kind -> Text,
+        target -> Text,
+        token_env -> Nullable<Text>,
+    }
+}
+
+table! {
+    notification_variants (comment_id) {
+        comment_id -> Text,
+        variant -> Text,
+        sent_at -> Timestamp,
+    }
+}
+
+table! {
+    saved_searches (name) {
+        name -> Text,
+        query -> Text,
+        recipient -> Nullable<Text>,
+    }
+}
+
+table! {
+    snoozes (kind, target) {
+        kind -> Text,
+        target -> Text,
+        until -> Timestamp,
+    }
+}
+
+table! {
+    supports (id) {
+        id -> Text,
+        account_id -> Text,
+        claim_id -> Text,
+        claim_name -> Text,
+        amount -> Double,
+        is_tip -> Bool,
+        received_at -> Timestamp,
+    }
+}
+
+table! {
+    sync_runs (id) {
+        id -> Integer,
+        kind -> Text,
+        status -> Text,
+        details -> Text,
+        started_at -> Timestamp,
+        finished_at -> Timestamp,
+    }
+}
+
+table! {
+    wallet_transactions (txid) {
+        txid -> Text,
+        account_id -> Text,
+        amount -> Double,
+        timestamp -> Nullable<Timestamp>,
+        received_at -> Timestamp,
     }
 }
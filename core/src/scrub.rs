@@ -0,0 +1,105 @@
+use regex::Regex;
+use std::str::FromStr;
+
+pub const SCRUBBED_TEXT: &str = "[redacted]";
+
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+const PHONE_PATTERN: &str = r"\+?\(?\d[\d\s().-]{6,}\d";
+const ADDRESS_PATTERN: &str = r"(?i)\b\d{1,5}(?:\s+[A-Za-z0-9.]+){1,4}\s+(?:street|st|avenue|ave|road|rd|boulevard|blvd|lane|ln|drive|dr|court|ct|way)\b\.?";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrubPattern {
+    Email,
+    Phone,
+    Address,
+}
+
+impl ScrubPattern {
+    fn regex(&self) -> Regex {
+        let pattern = match self {
+            Self::Email => EMAIL_PATTERN,
+            Self::Phone => PHONE_PATTERN,
+            Self::Address => ADDRESS_PATTERN,
+        };
+
+        Regex::new(pattern).expect("Invalid scrub pattern")
+    }
+}
+
+impl FromStr for ScrubPattern {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "email" => Ok(Self::Email),
+            "phone" => Ok(Self::Phone),
+            "address" => Ok(Self::Address),
+            _ => Err(format!("Unknown scrub pattern {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scrubber {
+    regexes: Vec<Regex>,
+}
+
+impl Scrubber {
+    pub fn new(patterns: &[ScrubPattern]) -> Self {
+        Self {
+            regexes: patterns.iter().map(ScrubPattern::regex).collect(),
+        }
+    }
+
+    pub fn with_regex(self, pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            regexes: self.regexes.into_iter().chain(vec![regex]).collect(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regexes.is_empty()
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        self.regexes.iter().fold(text.to_string(), |text, regex| {
+            regex.replace_all(&text, SCRUBBED_TEXT).into_owned()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScrubPattern, Scrubber};
+
+    #[test]
+    fn scrubber_should_redact_patterns() {
+        let scrubber = Scrubber::new(&[
+            ScrubPattern::Email,
+            ScrubPattern::Phone,
+            ScrubPattern::Address,
+        ]);
+
+        assert_eq!(
+            scrubber.scrub("Mail me at jo.doe+lbry@example.com or call +1 (555) 010-0199"),
+            "Mail me at [redacted] or call [redacted]"
+        );
+        assert_eq!(
+            scrubber.scrub("I live at 221 Baker Street, come by"),
+            "I live at [redacted], come by"
+        );
+        assert_eq!(
+            scrubber.scrub("Part 2 at 10:30, 5 stars"),
+            "Part 2 at 10:30, 5 stars"
+        );
+
+        let scrubber = Scrubber::new(&[])
+            .with_regex(r"(?i)discord\.gg/\w+")
+            .expect("Invalid regex");
+        assert_eq!(scrubber.scrub("Join discord.gg/abc123"), "Join [redacted]");
+        assert!(Scrubber::new(&[]).with_regex("(").is_err());
+        assert_eq!("phone".parse::<ScrubPattern>(), Ok(ScrubPattern::Phone));
+    }
+}
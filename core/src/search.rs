@@ -0,0 +1,177 @@
+use std::str::FromStr;
+
+use crate::CommentEntity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchTerm {
+    Commenter(String),
+    Claim(String),
+    Contains(String),
+}
+
+impl SearchTerm {
+    pub fn parse(token: &str) -> Result<Self, String> {
+        let (key, value) = match token.find(':') {
+            Some(index) => (&token[..index], &token[index + 1..]),
+            None => ("contains", token),
+        };
+
+        let value = value.trim_matches('"').to_lowercase();
+
+        if value.is_empty() {
+            return Err(format!("Empty search term {}", token));
+        }
+
+        match key {
+            "commenter" => Ok(Self::Commenter(value.trim_start_matches('@').to_string())),
+            "claim" => Ok(Self::Claim(value)),
+            "contains" => Ok(Self::Contains(value)),
+            _ => Err(format!("Unknown search term {}", key)),
+        }
+    }
+
+    pub fn matches(&self, comment: &CommentEntity) -> bool {
+        match self {
+            Self::Commenter(name) => {
+                comment
+                    .commenter_name
+                    .trim_start_matches('@')
+                    .to_lowercase()
+                    == *name
+            }
+            Self::Claim(name) => {
+                comment.claim_name.to_lowercase() == *name || comment.claim_id == *name
+            }
+            Self::Contains(text) => comment.comment.to_lowercase().contains(text),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchQuery {
+    clauses: Vec<Vec<SearchTerm>>,
+}
+
+impl SearchQuery {
+    pub fn matches(&self, comment: &CommentEntity) -> bool {
+        self.clauses
+            .iter()
+            .any(|terms| terms.iter().all(|term| term.matches(comment)))
+    }
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut is_quoted = false;
+
+    for ch in query.chars() {
+        match ch {
+            '"' => {
+                is_quoted = !is_quoted;
+                token.push(ch);
+            }
+            ch if ch.is_whitespace() && !is_quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            ch => token.push(ch),
+        }
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+impl FromStr for SearchQuery {
+    type Err = String;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut clauses = vec![Vec::new()];
+
+        for token in tokenize(query) {
+            match token.as_str() {
+                "OR" => clauses.push(Vec::new()),
+                "AND" => {}
+                _ => clauses
+                    .last_mut()
+                    .expect("Clauses are never empty")
+                    .push(SearchTerm::parse(&token)?),
+            }
+        }
+
+        if clauses.iter().any(|terms| terms.is_empty()) {
+            return Err(format!("Incomplete search query {}", query));
+        }
+
+        Ok(Self { clauses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{SearchQuery, SearchTerm};
+    use crate::{testing, CommentEntity};
+
+    fn comment(commenter_name: &str, text: &str) -> CommentEntity {
+        CommentEntity {
+            commenter_name: commenter_name.to_string(),
+            commenter_url: "commenter_url".to_string(),
+            comment: text.to_string(),
+            timestamp: Utc::now().naive_utc(),
+            ..testing::comment_entity("id", "claim_id")
+        }
+    }
+
+    #[test]
+    fn search_query_should_parse() {
+        let query = "commenter:@friend OR contains:\"let's collab\" claim:My-Video"
+            .parse::<SearchQuery>()
+            .expect("Unable to parse");
+
+        assert_eq!(
+            query.clauses,
+            vec![
+                vec![SearchTerm::Commenter("friend".to_string())],
+                vec![
+                    SearchTerm::Contains("let's collab".to_string()),
+                    SearchTerm::Claim("my-video".to_string())
+                ]
+            ]
+        );
+
+        assert!("".parse::<SearchQuery>().is_err());
+        assert!("commenter:a OR".parse::<SearchQuery>().is_err());
+        assert!("author:a".parse::<SearchQuery>().is_err());
+    }
+
+    #[test]
+    fn search_query_should_match() {
+        let query = "commenter:@friend OR contains:\"collab\""
+            .parse::<SearchQuery>()
+            .expect("Unable to parse");
+
+        assert!(query.matches(&comment("@Friend", "Nice video")));
+        assert!(query.matches(&comment("@stranger", "Want to COLLAB?")));
+        assert!(!query.matches(&comment("@stranger", "Nice video")));
+
+        let query = "commenter:@friend collab"
+            .parse::<SearchQuery>()
+            .expect("Unable to parse");
+
+        assert!(query.matches(&comment("@friend", "collab?")));
+        assert!(!query.matches(&comment("@friend", "Nice video")));
+
+        let query = "claim:claim_id"
+            .parse::<SearchQuery>()
+            .expect("Unable to parse");
+
+        assert!(query.matches(&comment("@friend", "Nice video")));
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::Duration;
+use std::str::FromStr;
+
+use crate::{CommentEntity, Snooze};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SnoozeKind {
+    Claim,
+    Commenter,
+}
+
+impl SnoozeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Claim => "claim",
+            Self::Commenter => "commenter",
+        }
+    }
+}
+
+impl FromStr for SnoozeKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "claim" => Ok(Self::Claim),
+            "commenter" => Ok(Self::Commenter),
+            _ => Err(format!("Unknown snooze kind {}", value)),
+        }
+    }
+}
+
+impl Snooze {
+    pub fn matches(&self, comment: &CommentEntity) -> bool {
+        match self.kind.parse::<SnoozeKind>() {
+            Ok(SnoozeKind::Claim) => {
+                self.target == comment.claim_id || self.target == comment.claim_name
+            }
+            Ok(SnoozeKind::Commenter) => {
+                self.target == comment.commenter_id || self.target == comment.commenter_name
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let unit_index = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_index);
+
+    let amount = amount
+        .parse::<i64>()
+        .map_err(|_| format!("Invalid duration {}", value))?;
+
+    match unit {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" | "" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(format!("Unknown duration unit {}", unit)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{parse_duration, SnoozeKind};
+    use crate::{testing, CommentEntity, Snooze};
+
+    #[test]
+    fn parse_duration_should_work() {
+        assert_eq!(parse_duration("30m"), Ok(Duration::minutes(30)));
+        assert_eq!(parse_duration("6"), Ok(Duration::hours(6)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::days(2)));
+        assert_eq!(parse_duration("1w"), Ok(Duration::weeks(1)));
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("2y").is_err());
+    }
+
+    #[test]
+    fn snooze_should_match() {
+        let comment = CommentEntity {
+            commenter_name: "@noisy".to_string(),
+            commenter_url: "commenter_url".to_string(),
+            comment: "comment".to_string(),
+            timestamp: Utc::now().naive_utc(),
+            ..testing::comment_entity("id", "claim_id")
+        };
+
+        let snooze = |kind: SnoozeKind, target: &str| Snooze {
+            kind: kind.as_str().to_string(),
+            target: target.to_string(),
+            until: Utc::now().naive_utc(),
+        };
+
+        assert!(snooze(SnoozeKind::Claim, "claim_id").matches(&comment));
+        assert!(snooze(SnoozeKind::Claim, "my-video").matches(&comment));
+        assert!(snooze(SnoozeKind::Commenter, "@noisy").matches(&comment));
+        assert!(!snooze(SnoozeKind::Commenter, "my-video").matches(&comment));
+    }
+}
@@ -0,0 +1,67 @@
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommentSort {
+    Newest,
+    Oldest,
+    Controversial,
+    Top,
+}
+
+impl CommentSort {
+    pub(crate) fn apply(&self, payload: &mut Value) {
+        let params = &mut payload["params"];
+
+        match self {
+            Self::Newest => {}
+            Self::Oldest => {
+                params["sort_by"] = json!(1);
+            }
+            Self::Controversial => {
+                params["sort_by"] = json!(2);
+            }
+            Self::Top => {
+                params["sort_by"] = json!(3);
+            }
+        }
+    }
+}
+
+impl FromStr for CommentSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "newest" => Ok(Self::Newest),
+            "oldest" => Ok(Self::Oldest),
+            "controversial" => Ok(Self::Controversial),
+            "top" => Ok(Self::Top),
+            _ => Err(format!("Unknown comment sort {}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::CommentSort;
+
+    #[test]
+    fn apply_should_set_sort_param() {
+        let payload = json!({ "method": "comment_list", "params": { "claim_id": "claim_id" } });
+
+        let mut newest = payload.clone();
+        CommentSort::Newest.apply(&mut newest);
+        assert_eq!(newest, payload);
+
+        let mut top = payload;
+        "top"
+            .parse::<CommentSort>()
+            .expect("Invalid sort")
+            .apply(&mut top);
+        assert_eq!(top["params"]["sort_by"], json!(3));
+        assert!("popular".parse::<CommentSort>().is_err());
+    }
+}
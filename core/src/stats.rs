@@ -0,0 +1,74 @@
+use futures::{future::TryFutureExt, prelude::Future};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::ApiError;
+
+pub const DEFAULT_VIEW_COUNT_URL: &str = "https://api.odysee.com/file/view_count";
+
+fn parse_view_counts(claim_ids: &[String], value: &Value) -> Option<HashMap<String, i64>> {
+    let counts = value.get("data")?.as_array()?;
+
+    Some(
+        claim_ids
+            .iter()
+            .zip(counts)
+            .filter_map(|(claim_id, count)| Some((claim_id.to_string(), count.as_i64()?)))
+            .collect(),
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct ViewCounts {
+    client: Client,
+    url: String,
+}
+
+impl ViewCounts {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: Client::new(),
+            url,
+        }
+    }
+
+    pub fn fetch<'a, 'r: 'a>(
+        &'a self,
+        claim_ids: Vec<String>,
+    ) -> impl Future<Output = Result<HashMap<String, i64>, ApiError>> + 'r {
+        self.client
+            .get(&self.url)
+            .query(&[("claim_id", claim_ids.join(","))])
+            .send()
+            .and_then(|resp| async { resp.error_for_status()?.json::<Value>().await })
+            .map_err(ApiError::NetworkError)
+            .and_then(move |value| async move {
+                parse_view_counts(&claim_ids, &value).ok_or(ApiError::InvalidResponse)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::parse_view_counts;
+
+    #[test]
+    fn parse_view_counts_should_match_claim_ids() {
+        let claim_ids = vec!["claim_a".to_string(), "claim_b".to_string()];
+
+        let counts = parse_view_counts(
+            &claim_ids,
+            &json!({ "success": true, "error": null, "data": [120, 3] }),
+        )
+        .expect("Invalid view counts");
+        assert_eq!(counts.get("claim_a"), Some(&120));
+        assert_eq!(counts.get("claim_b"), Some(&3));
+
+        assert!(
+            parse_view_counts(&claim_ids, &json!({ "success": false, "error": "x" })).is_none()
+        );
+    }
+}
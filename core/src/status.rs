@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const MAX_BLOCKS_BEHIND: i64 = 10;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct WalletStatus {
+    #[serde(default)]
+    pub blocks: i64,
+    #[serde(default)]
+    pub blocks_behind: i64,
+    #[serde(default)]
+    pub connected: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SdkStatus {
+    #[serde(default)]
+    pub is_running: bool,
+    #[serde(default)]
+    pub startup_status: HashMap<String, bool>,
+    #[serde(default)]
+    pub wallet: Option<WalletStatus>,
+}
+
+impl SdkStatus {
+    pub fn problems(&self, requires_wallet: bool) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.is_running {
+            problems.push("SDK is still starting up".to_string());
+        }
+
+        let mut stopped_components = self
+            .startup_status
+            .iter()
+            .filter(|(component, started)| {
+                !**started && (requires_wallet || component.as_str() != "wallet")
+            })
+            .map(|(component, _)| component.as_str())
+            .collect::<Vec<&str>>();
+        stopped_components.sort();
+
+        if !stopped_components.is_empty() {
+            problems.push(format!(
+                "Components not started: {}",
+                stopped_components.join(", ")
+            ));
+        }
+
+        if requires_wallet {
+            match &self.wallet {
+                Some(wallet) if wallet.connected.is_none() => {
+                    problems.push("Wallet is not connected to a server".to_string())
+                }
+                Some(wallet) if wallet.blocks_behind > MAX_BLOCKS_BEHIND => {
+                    problems.push(format!("Wallet is {} blocks behind", wallet.blocks_behind))
+                }
+                Some(_) => {}
+                None => problems.push("Wallet is unavailable".to_string()),
+            }
+        }
+
+        problems
+    }
+
+    pub fn is_ready(&self, requires_wallet: bool) -> bool {
+        self.problems(requires_wallet).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::SdkStatus;
+
+    #[test]
+    fn problems_should_explain_unready_sdk() {
+        let status = serde_json::from_value::<SdkStatus>(json!({
+            "is_running": true,
+            "startup_status": { "wallet": true, "blob_manager": true },
+            "wallet": { "blocks": 800000, "blocks_behind": 0, "connected": "spv11.lbry.com:50001" },
+        }))
+        .expect("Invalid status");
+        assert!(status.is_ready(true));
+
+        let status = serde_json::from_value::<SdkStatus>(json!({
+            "is_running": false,
+            "startup_status": { "wallet": false, "database": false },
+            "wallet": { "blocks": 1000, "blocks_behind": 799000, "connected": "spv11.lbry.com:50001" },
+        }))
+        .expect("Invalid status");
+        assert_eq!(
+            status.problems(true),
+            vec![
+                "SDK is still starting up",
+                "Components not started: database, wallet",
+                "Wallet is 799000 blocks behind",
+            ]
+        );
+        assert_eq!(
+            status.problems(false),
+            vec![
+                "SDK is still starting up",
+                "Components not started: database"
+            ]
+        );
+
+        let status = serde_json::from_value::<SdkStatus>(json!({ "is_running": true }))
+            .expect("Invalid status");
+        assert_eq!(status.problems(true), vec!["Wallet is unavailable"]);
+    }
+}
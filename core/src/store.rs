@@ -0,0 +1,301 @@
+use chrono::NaiveDateTime;
+use diesel::result::{DatabaseErrorKind, Error};
+use std::{collections::HashMap, sync::RwLock};
+
+use crate::{Account, Claim, Comment, CommentEntity, Storage};
+
+#[derive(Debug)]
+pub enum StoreError {
+    AlreadyExists(String),
+    Database(Error),
+}
+
+impl From<Error> for StoreError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                Self::AlreadyExists(info.message().to_string())
+            }
+            error => Self::Database(error),
+        }
+    }
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::AlreadyExists(ref message) => write!(f, "Already exists: {}", message),
+            Self::Database(ref error) => error.fmt(f),
+        }
+    }
+}
+
+pub trait CommentStore {
+    fn save_comment(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+    ) -> Result<CommentEntity, StoreError>;
+
+    fn save_comment_with_deliveries(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+        _targets: Vec<String>,
+    ) -> Result<CommentEntity, StoreError> {
+        self.save_comment(account, claim, comment)
+    }
+
+    fn get_comment_by_id(&self, comment_id: String) -> Option<CommentEntity>;
+
+    fn update_comment(
+        &self,
+        comment_entity: CommentEntity,
+        comment: &Comment,
+    ) -> Result<CommentEntity, StoreError>;
+
+    fn delete_comment_by_id(&self, comment_id: String) -> Result<(), StoreError>;
+
+    fn get_comments_by_claim_id(&self, claim_id: String) -> Result<Vec<CommentEntity>, StoreError>;
+
+    fn get_comments_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<CommentEntity>, StoreError>;
+}
+
+impl CommentStore for Storage {
+    fn save_comment(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+    ) -> Result<CommentEntity, StoreError> {
+        Ok(Storage::save_comment(self, account, claim, comment)?)
+    }
+
+    fn save_comment_with_deliveries(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+        targets: Vec<String>,
+    ) -> Result<CommentEntity, StoreError> {
+        Ok(Storage::save_comment_with_deliveries(
+            self, account, claim, comment, targets,
+        )?)
+    }
+
+    fn get_comment_by_id(&self, comment_id: String) -> Option<CommentEntity> {
+        Storage::get_comment_by_id(self, comment_id)
+    }
+
+    fn update_comment(
+        &self,
+        comment_entity: CommentEntity,
+        comment: &Comment,
+    ) -> Result<CommentEntity, StoreError> {
+        Ok(Storage::update_comment(self, comment_entity, comment)?)
+    }
+
+    fn delete_comment_by_id(&self, comment_id: String) -> Result<(), StoreError> {
+        Ok(Storage::delete_comment_by_id(self, comment_id)?)
+    }
+
+    fn get_comments_by_claim_id(&self, claim_id: String) -> Result<Vec<CommentEntity>, StoreError> {
+        Ok(Storage::get_comments_by_claim_id(self, claim_id)?)
+    }
+
+    fn get_comments_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<CommentEntity>, StoreError> {
+        Ok(Storage::get_comments_since(self, since)?)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    comments: RwLock<HashMap<String, CommentEntity>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn filter_comments<F>(&self, f: F) -> Vec<CommentEntity>
+    where
+        F: Fn(&CommentEntity) -> bool,
+    {
+        let mut comments = self
+            .comments
+            .read()
+            .expect("Unable to get lock")
+            .values()
+            .filter(|comment_entity| f(comment_entity))
+            .cloned()
+            .collect::<Vec<CommentEntity>>();
+
+        comments.sort_by_key(|comment_entity| comment_entity.timestamp);
+
+        comments
+    }
+}
+
+impl CommentStore for MemoryStore {
+    fn save_comment(
+        &self,
+        account: Account,
+        claim: Claim,
+        comment: Comment,
+    ) -> Result<CommentEntity, StoreError> {
+        let comment_entity = CommentEntity::new(account, claim, comment);
+        let mut comments = self.comments.write().expect("Unable to get lock");
+
+        if comments.contains_key(&comment_entity.id) {
+            return Err(StoreError::AlreadyExists(format!(
+                "Comment {}",
+                comment_entity.id
+            )));
+        }
+
+        comments.insert(comment_entity.id.clone(), comment_entity.clone());
+
+        Ok(comment_entity)
+    }
+
+    fn get_comment_by_id(&self, comment_id: String) -> Option<CommentEntity> {
+        self.comments
+            .read()
+            .expect("Unable to get lock")
+            .get(&comment_id)
+            .cloned()
+    }
+
+    fn update_comment(
+        &self,
+        comment_entity: CommentEntity,
+        comment: &Comment,
+    ) -> Result<CommentEntity, StoreError> {
+        let updated = CommentEntity {
+            comment: comment.comment.clone(),
+            is_hidden: comment.is_hidden,
+            ..comment_entity
+        };
+
+        if let Some(stored) = self
+            .comments
+            .write()
+            .expect("Unable to get lock")
+            .get_mut(&updated.id)
+        {
+            stored.comment = updated.comment.clone();
+            stored.is_hidden = updated.is_hidden;
+        }
+
+        Ok(updated)
+    }
+
+    fn delete_comment_by_id(&self, comment_id: String) -> Result<(), StoreError> {
+        self.comments
+            .write()
+            .expect("Unable to get lock")
+            .remove(&comment_id);
+
+        Ok(())
+    }
+
+    fn get_comments_by_claim_id(&self, claim_id: String) -> Result<Vec<CommentEntity>, StoreError> {
+        Ok(self.filter_comments(|comment_entity| comment_entity.claim_id == claim_id))
+    }
+
+    fn get_comments_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<CommentEntity>, StoreError> {
+        Ok(self.filter_comments(|comment_entity| {
+            since
+                .map(|since| comment_entity.timestamp >= since)
+                .unwrap_or(true)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{CommentStore, MemoryStore, StoreError};
+    use crate::{testing, Comment, CommentEntity};
+
+    fn comment(id: &str, claim_id: &str, day: u32) -> Comment {
+        Comment {
+            timestamp: Utc.ymd(2020, 8, day).and_hms(0, 0, 0),
+            ..testing::comment(id, claim_id)
+        }
+    }
+
+    #[test]
+    fn memory_store_should_behave_like_storage() {
+        let store = MemoryStore::new();
+        let account = testing::account("account");
+        let claim = testing::claim("claim");
+
+        let store: &dyn CommentStore = &store;
+
+        for &(id, claim_id, day) in &[("b", "claim", 3), ("a", "claim", 1), ("c", "other", 2)] {
+            store
+                .save_comment(account.clone(), claim.clone(), comment(id, claim_id, day))
+                .expect("Unable to save");
+        }
+
+        assert!(matches!(
+            store.save_comment(account, claim, comment("a", "claim", 1)),
+            Err(StoreError::AlreadyExists(_))
+        ));
+
+        let ids = |comments: Vec<CommentEntity>| {
+            comments
+                .into_iter()
+                .map(|comment_entity| comment_entity.id)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            ids(store
+                .get_comments_by_claim_id("claim".to_string())
+                .expect("Unable to fetch")),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            ids(store
+                .get_comments_since(Some(Utc.ymd(2020, 8, 2).and_hms(0, 0, 0).naive_utc()))
+                .expect("Unable to fetch")),
+            vec!["c", "b"]
+        );
+
+        let entity = store
+            .get_comment_by_id("a".to_string())
+            .expect("Unable to fetch");
+        let mut edited = comment("a", "claim", 1);
+        edited.comment = "Edited".to_string();
+
+        store
+            .update_comment(entity, &edited)
+            .expect("Unable to update");
+        assert_eq!(
+            store
+                .get_comment_by_id("a".to_string())
+                .map(|comment_entity| comment_entity.comment),
+            Some("Edited".to_string())
+        );
+
+        store
+            .delete_comment_by_id("a".to_string())
+            .expect("Unable to delete");
+        assert!(store.get_comment_by_id("a".to_string()).is_none());
+    }
+}
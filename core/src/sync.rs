@@ -0,0 +1,243 @@
+use futures::{
+    future::TryFutureExt,
+    stream::{self, StreamExt, TryStreamExt},
+};
+use std::collections::HashMap;
+
+use crate::{
+    Account, Api, ApiError, Channel, Claim, Comment, CommentEntity, CommentStore, StoreError,
+};
+
+#[derive(Debug)]
+pub enum SyncError {
+    Api(ApiError),
+    Store(StoreError),
+}
+
+impl From<ApiError> for SyncError {
+    fn from(error: ApiError) -> Self {
+        Self::Api(error)
+    }
+}
+
+impl From<StoreError> for SyncError {
+    fn from(error: StoreError) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<diesel::result::Error> for SyncError {
+    fn from(error: diesel::result::Error) -> Self {
+        Self::Store(StoreError::from(error))
+    }
+}
+
+impl std::fmt::Display for SyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::Api(ref error) => error.fmt(f),
+            Self::Store(ref error) => error.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SyncDelta {
+    pub new: Vec<(Account, Claim, Comment)>,
+    pub edited: Vec<(CommentEntity, Comment)>,
+    pub deleted: Vec<CommentEntity>,
+}
+
+#[derive(Debug, Default)]
+pub struct CommentDelta {
+    pub new: Vec<Comment>,
+    pub edited: Vec<(CommentEntity, Comment)>,
+    pub deleted: Vec<CommentEntity>,
+}
+
+pub fn diff_comments(stored: Vec<CommentEntity>, remote: Vec<Comment>) -> CommentDelta {
+    let mut stored = stored
+        .into_iter()
+        .map(|comment_entity| (comment_entity.id.clone(), comment_entity))
+        .collect::<HashMap<String, CommentEntity>>();
+    let mut delta = CommentDelta::default();
+
+    for comment in remote {
+        match stored.remove(&comment.id) {
+            Some(comment_entity) => {
+                if comment_entity.comment != comment.comment
+                    || comment_entity.is_hidden != comment.is_hidden
+                {
+                    delta.edited.push((comment_entity, comment));
+                }
+            }
+            None => delta.new.push(comment),
+        }
+    }
+
+    delta.deleted = stored.into_values().collect();
+    delta
+        .deleted
+        .sort_by_key(|comment_entity| comment_entity.timestamp);
+
+    delta
+}
+
+pub struct Syncer<'a> {
+    api: &'a Api,
+    storage: &'a dyn CommentStore,
+    page_size: usize,
+    channels: &'a [String],
+}
+
+fn is_synced_channel(channels: &[String], channel: &Channel) -> bool {
+    channels
+        .iter()
+        .any(|synced| synced == "*" || *synced == channel.id || *synced == channel.name)
+}
+
+impl<'a> Syncer<'a> {
+    pub fn new(api: &'a Api, storage: &'a dyn CommentStore, page_size: usize) -> Self {
+        Self {
+            api,
+            storage,
+            page_size,
+            channels: &[],
+        }
+    }
+
+    pub fn with_channels(self, channels: &'a [String]) -> Self {
+        Self { channels, ..self }
+    }
+
+    async fn claims(&self, account: &Account) -> Result<Vec<Claim>, ApiError> {
+        if self.channels.is_empty() {
+            return self
+                .api
+                .try_stream_claims_by_account_id(account.id.clone(), self.page_size)
+                .try_collect::<Vec<Claim>>()
+                .await;
+        }
+
+        let channels = self
+            .api
+            .try_stream_channels(account.id.clone(), self.page_size)
+            .try_collect::<Vec<Channel>>()
+            .await?;
+        let mut claims = Vec::new();
+
+        for channel in channels {
+            if !is_synced_channel(self.channels, &channel) {
+                continue;
+            }
+
+            claims.extend(
+                self.api
+                    .try_stream_claims_by_channel_id(channel.id, self.page_size)
+                    .try_collect::<Vec<Claim>>()
+                    .await?,
+            );
+        }
+
+        Ok(claims)
+    }
+
+    async fn account_claims(&self) -> Result<Vec<(Account, Claim)>, ApiError> {
+        let accounts = self
+            .api
+            .try_stream_accounts(self.page_size)
+            .try_collect::<Vec<Account>>()
+            .await?;
+
+        let mut account_claims = Vec::new();
+
+        for account in accounts {
+            let claims = self.claims(&account).await?;
+
+            account_claims.extend(claims.into_iter().map(|claim| (account.clone(), claim)));
+        }
+
+        Ok(account_claims)
+    }
+
+    pub async fn delta(&self) -> Result<SyncDelta, SyncError> {
+        let account_claims = self.account_claims().await?;
+
+        let claim_comments = stream::iter(account_claims)
+            .map(move |(account, claim)| {
+                self.api
+                    .try_stream_comments_by_claim_id(claim.id.clone(), self.page_size)
+                    .try_collect::<Vec<Comment>>()
+                    .map_ok(move |comments| (account, claim, comments))
+            })
+            .buffer_unordered(self.api.page_concurrency.max(1))
+            .try_collect::<Vec<(Account, Claim, Vec<Comment>)>>()
+            .await?;
+
+        let mut delta = SyncDelta::default();
+
+        for (account, claim, comments) in claim_comments {
+            let stored = self.storage.get_comments_by_claim_id(claim.id.clone())?;
+
+            let CommentDelta {
+                new,
+                edited,
+                deleted,
+            } = diff_comments(stored, comments);
+
+            delta.new.extend(
+                new.into_iter()
+                    .map(|comment| (account.clone(), claim.clone(), comment)),
+            );
+            delta.edited.extend(edited);
+            delta.deleted.extend(deleted);
+        }
+
+        Ok(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_comments;
+    use crate::{testing, Comment, CommentEntity};
+
+    fn comment(id: &str, text: &str) -> Comment {
+        Comment {
+            comment: text.to_string(),
+            ..testing::comment(id, "claim_id")
+        }
+    }
+
+    fn comment_entity(id: &str, text: &str) -> CommentEntity {
+        CommentEntity {
+            claim_name: "claim".to_string(),
+            comment: text.to_string(),
+            ..testing::comment_entity(id, "claim_id")
+        }
+    }
+
+    #[test]
+    fn diff_comments_should_classify_changes() {
+        let delta = diff_comments(
+            vec![
+                comment_entity("kept", "Same"),
+                comment_entity("edited", "Typo"),
+                comment_entity("deleted", "Gone"),
+            ],
+            vec![
+                comment("kept", "Same"),
+                comment("edited", "Fixed"),
+                comment("new", "Hello"),
+            ],
+        );
+
+        assert_eq!(delta.new.len(), 1);
+        assert_eq!(delta.new[0].id, "new");
+        assert_eq!(delta.edited.len(), 1);
+        assert_eq!(delta.edited[0].0.comment, "Typo");
+        assert_eq!(delta.edited[0].1.comment, "Fixed");
+        assert_eq!(delta.deleted.len(), 1);
+        assert_eq!(delta.deleted[0].id, "deleted");
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{ApiError, ApiTrace};
+
+type TimedCall = (DateTime<Utc>, ApiTrace);
+type ClaimSpan = (String, DateTime<Utc>, DateTime<Utc>, usize);
+
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_CLIENT: u8 = 3;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+#[derive(Clone, Debug, Default)]
+pub struct SpanCollector {
+    calls: Arc<Mutex<Vec<TimedCall>>>,
+}
+
+impl SpanCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, trace: &ApiTrace) {
+        self.calls
+            .lock()
+            .expect("Unable to get lock")
+            .push((Utc::now(), trace.clone()));
+    }
+
+    pub fn take(&self) -> Vec<TimedCall> {
+        self.calls
+            .lock()
+            .expect("Unable to get lock")
+            .drain(..)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RunSpans {
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub attributes: Vec<(String, Value)>,
+    pub error: Option<String>,
+    pub calls: Vec<TimedCall>,
+}
+
+fn random_id(bytes: usize) -> String {
+    (0..bytes)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect()
+}
+
+fn attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(value) => json!({ "boolValue": value }),
+        Value::Number(value) if value.is_i64() || value.is_u64() => {
+            json!({ "intValue": value.to_string() })
+        }
+        Value::Number(value) => json!({ "doubleValue": value }),
+        Value::String(value) => json!({ "stringValue": value }),
+        value => json!({ "stringValue": value.to_string() }),
+    };
+
+    json!({ "key": key, "value": value })
+}
+
+struct Span<'a> {
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    kind: u8,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    attributes: Vec<(String, Value)>,
+    error: Option<&'a str>,
+}
+
+impl<'a> Span<'a> {
+    fn render(&self, trace_id: &str) -> Value {
+        json!({
+            "traceId": trace_id,
+            "spanId": self.span_id,
+            "parentSpanId": self.parent_span_id.clone().unwrap_or_default(),
+            "name": self.name,
+            "kind": self.kind,
+            "startTimeUnixNano": self.started_at.timestamp_nanos().to_string(),
+            "endTimeUnixNano": self.finished_at.timestamp_nanos().to_string(),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| attribute(key, value))
+                .collect::<Vec<Value>>(),
+            "status": match self.error {
+                Some(message) => json!({ "code": STATUS_CODE_ERROR, "message": message }),
+                None => json!({ "code": STATUS_CODE_OK }),
+            },
+        })
+    }
+}
+
+impl RunSpans {
+    pub fn render_spans(&self) -> Vec<Value> {
+        let trace_id = random_id(16);
+        let root_span_id = random_id(8);
+
+        let mut claim_spans: HashMap<&str, ClaimSpan> = HashMap::new();
+        let mut spans = Vec::new();
+
+        for (finished_at, call) in &self.calls {
+            let started_at = *finished_at
+                - Duration::from_std(call.duration).unwrap_or_else(|_| Duration::zero());
+            let parent_span_id = match &call.claim_id {
+                Some(claim_id) => {
+                    let claim_span = claim_spans
+                        .entry(claim_id.as_str())
+                        .or_insert_with(|| (random_id(8), started_at, *finished_at, 0));
+                    claim_span.1 = claim_span.1.min(started_at);
+                    claim_span.2 = claim_span.2.max(*finished_at);
+
+                    if call.error.is_some() {
+                        claim_span.3 += 1;
+                    }
+
+                    claim_span.0.clone()
+                }
+                None => root_span_id.clone(),
+            };
+
+            let mut attributes = vec![
+                ("rpc.method".to_string(), json!(call.method)),
+                ("attempts".to_string(), json!(call.attempts)),
+                ("batched".to_string(), json!(call.batched)),
+            ];
+            if let Some(claim_id) = &call.claim_id {
+                attributes.push(("claim_id".to_string(), json!(claim_id)));
+            }
+
+            if let Some(page) = call.page {
+                attributes.push(("page".to_string(), json!(page)));
+            }
+
+            if let Some(result_count) = call.result_count {
+                attributes.push(("result_count".to_string(), json!(result_count)));
+            }
+
+            if call.error.is_some() {
+                attributes.push(("error".to_string(), json!(true)));
+            }
+
+            spans.push(Span {
+                span_id: random_id(8),
+                parent_span_id: Some(parent_span_id),
+                name: call.method.clone(),
+                kind: SPAN_KIND_CLIENT,
+                started_at,
+                finished_at: *finished_at,
+                attributes,
+                error: call.error.as_deref(),
+            });
+        }
+
+        let mut claim_spans = claim_spans.into_iter().collect::<Vec<_>>();
+        claim_spans.sort_by_key(|(_, (_, started_at, _, _))| *started_at);
+
+        for (claim_id, (span_id, started_at, finished_at, errors)) in claim_spans {
+            spans.push(Span {
+                span_id,
+                parent_span_id: Some(root_span_id.clone()),
+                name: format!("claim {}", claim_id),
+                kind: SPAN_KIND_INTERNAL,
+                started_at,
+                finished_at,
+                attributes: vec![
+                    ("claim_id".to_string(), json!(claim_id)),
+                    ("failed_requests".to_string(), json!(errors)),
+                ],
+                error: if errors > 0 {
+                    Some("Requests failed")
+                } else {
+                    None
+                },
+            });
+        }
+
+        spans.push(Span {
+            span_id: root_span_id,
+            parent_span_id: None,
+            name: self.name.clone(),
+            kind: SPAN_KIND_INTERNAL,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            attributes: self.attributes.clone(),
+            error: self.error.as_deref(),
+        });
+
+        spans.iter().map(|span| span.render(&trace_id)).collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OtlpExporter {
+    client: Client,
+    endpoint: String,
+    service_name: String,
+}
+
+impl OtlpExporter {
+    pub fn new(endpoint: String, service_name: String) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint,
+            service_name,
+        }
+    }
+
+    pub fn render(&self, run: &RunSpans) -> Value {
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [attribute("service.name", &json!(self.service_name))],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "lbry-comment-notifier" },
+                    "spans": run.render_spans(),
+                }],
+            }],
+        })
+    }
+
+    pub async fn export(&self, run: &RunSpans) {
+        let url = format!("{}/v1/traces", self.endpoint.trim_end_matches('/'));
+
+        let result = self
+            .client
+            .post(&url)
+            .json(&self.render(run))
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map(|_| ())
+            .map_err(ApiError::NetworkError);
+
+        if let Err(err) = result {
+            error!("Unable to export traces to {}: {}", url, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    use std::time::Duration;
+
+    use super::{OtlpExporter, RunSpans, SpanCollector};
+    use crate::ApiTrace;
+
+    fn trace(claim_id: Option<&str>, error: Option<&str>) -> ApiTrace {
+        ApiTrace {
+            method: "comment_list".to_string(),
+            claim_id: claim_id.map(String::from),
+            page: Some(1),
+            duration: Duration::from_millis(250),
+            attempts: 1,
+            batched: false,
+            result_count: Some(2),
+            error: error.map(String::from),
+        }
+    }
+
+    #[test]
+    fn render_should_nest_claim_and_request_spans() {
+        let collector = SpanCollector::new();
+        collector.record(&trace(None, None));
+        collector.record(&trace(Some("claim_a"), None));
+        collector.record(&trace(Some("claim_a"), Some("Deadline exceeded")));
+
+        let run = RunSpans {
+            name: "sync_run".to_string(),
+            started_at: Utc.ymd(2020, 8, 1).and_hms(0, 0, 0),
+            finished_at: Utc::now(),
+            attributes: vec![("new_comments".to_string(), json!(3))],
+            error: None,
+            calls: collector.take(),
+        };
+        assert!(collector.take().is_empty());
+
+        let exporter =
+            OtlpExporter::new("http://localhost:4318".to_string(), "notifier".to_string());
+        let payload = exporter.render(&run);
+        let spans = payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .expect("Missing spans");
+
+        assert_eq!(spans.len(), 5);
+        assert_eq!(
+            payload["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            json!("notifier")
+        );
+
+        let root = &spans[4];
+        let claim = &spans[3];
+        assert_eq!(root["name"], json!("sync_run"));
+        assert_eq!(root["parentSpanId"], json!(""));
+        assert_eq!(root["attributes"][0]["value"]["intValue"], json!("3"));
+        assert_eq!(claim["name"], json!("claim claim_a"));
+        assert_eq!(claim["parentSpanId"], root["spanId"]);
+        assert_eq!(claim["status"]["code"], json!(2));
+
+        assert_eq!(spans[0]["parentSpanId"], root["spanId"]);
+        assert_eq!(spans[1]["parentSpanId"], claim["spanId"]);
+        assert_eq!(spans[2]["parentSpanId"], claim["spanId"]);
+        assert_eq!(spans[2]["status"]["message"], json!("Deadline exceeded"));
+        assert!(spans.iter().all(|span| span["traceId"] == root["traceId"]
+            && span["traceId"].as_str().map(str::len) == Some(32)));
+    }
+}
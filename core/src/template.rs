@@ -0,0 +1,141 @@
+use rand::Rng;
+use std::str::FromStr;
+
+use crate::{CannedReply, CommentEntity};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    pub subject: String,
+    pub text: String,
+}
+
+impl Template {
+    pub fn render(&self, comment: &CommentEntity) -> (String, String) {
+        (
+            render_placeholders(&self.subject, comment),
+            render_placeholders(&self.text, comment),
+        )
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.splitn(2, '\n');
+        let subject = parts.next().unwrap_or_default().trim().to_string();
+        let text = parts.next().unwrap_or_default().to_string();
+
+        if subject.is_empty() {
+            return Err("Template is missing a subject line".to_string());
+        }
+
+        Ok(Self { subject, text })
+    }
+}
+
+impl CannedReply {
+    pub fn render(&self, comment: &CommentEntity) -> String {
+        render_placeholders(&self.body, comment)
+    }
+}
+
+fn render_placeholders(template: &str, comment: &CommentEntity) -> String {
+    template
+        .replace("{claim_name}", &comment.claim_name)
+        .replace("{claim_title}", comment.claim_display_name())
+        .replace(
+            "{claim_url}",
+            comment.claim_canonical_url.as_deref().unwrap_or_default(),
+        )
+        .replace("{claim_web_url}", &comment.claim_web_url())
+        .replace(
+            "{claim_thumbnail_url}",
+            comment.claim_thumbnail_url.as_deref().unwrap_or_default(),
+        )
+        .replace("{source}", &comment.source)
+        .replace("{commenter_name}", &comment.commenter_name)
+        .replace("{commenter_url}", &comment.commenter_url)
+        .replace("{timestamp}", &comment.timestamp.to_string())
+        .replace("{comment}", &comment.comment)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateSplit {
+    pub variant_a: Template,
+    pub variant_b: Template,
+    pub percent_a: u8,
+}
+
+impl TemplateSplit {
+    pub fn choose_with(&self, roll: u8) -> (&'static str, &Template) {
+        if roll < self.percent_a {
+            ("a", &self.variant_a)
+        } else {
+            ("b", &self.variant_b)
+        }
+    }
+
+    pub fn choose(&self) -> (&'static str, &Template) {
+        self.choose_with(rand::thread_rng().gen_range(0, 100))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Template, TemplateSplit};
+    use crate::{testing, CannedReply, CommentEntity};
+
+    #[test]
+    fn template_should_render() {
+        let template = "{commenter_name} replied on {claim_name}\n\n{comment}"
+            .parse::<Template>()
+            .expect("Unable to parse");
+
+        let comment = CommentEntity {
+            commenter_name: "@friend".to_string(),
+            commenter_url: "lbry://@friend".to_string(),
+            comment: "Nice video".to_string(),
+            timestamp: NaiveDate::from_ymd(2020, 7, 1).and_hms(0, 0, 0),
+            ..testing::comment_entity("id", "claim_id")
+        };
+
+        assert_eq!(
+            template.render(&comment),
+            (
+                "@friend replied on my-video".to_string(),
+                "\nNice video".to_string()
+            )
+        );
+        assert!("".parse::<Template>().is_err());
+
+        let canned_reply = CannedReply {
+            name: "schedule".to_string(),
+            body: "Thanks {commenter_name}, new videos drop every Friday".to_string(),
+            channel_name: None,
+        };
+
+        assert_eq!(
+            canned_reply.render(&comment),
+            "Thanks @friend, new videos drop every Friday"
+        );
+    }
+
+    #[test]
+    fn template_split_should_choose_by_percent() {
+        let variant_a = "A".parse::<Template>().expect("Unable to parse");
+        let variant_b = "B".parse::<Template>().expect("Unable to parse");
+        let split = TemplateSplit {
+            variant_a,
+            variant_b,
+            percent_a: 30,
+        };
+
+        assert_eq!(split.choose_with(0).0, "a");
+        assert_eq!(split.choose_with(29).0, "a");
+        assert_eq!(split.choose_with(30).0, "b");
+        assert_eq!(split.choose_with(99).0, "b");
+    }
+}
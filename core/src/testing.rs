@@ -0,0 +1,251 @@
+use chrono::{TimeZone, Utc};
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    stream::{self, BoxStream, LocalBoxStream, StreamExt},
+};
+use std::collections::HashMap;
+
+use crate::{
+    Account, ApiError, Channel, Claim, ClaimMetadata, Comment, CommentCursors, CommentEntity,
+    CommentStop, LbryApi,
+};
+
+#[cfg(feature = "test-support")]
+pub use crate::mock_sdk::{MockSdk, MockSdkServer};
+
+#[derive(Clone, Debug, Default)]
+pub struct FakeApi {
+    accounts: Vec<Account>,
+    channels: HashMap<String, Vec<Channel>>,
+    claims: HashMap<String, Vec<Claim>>,
+    comments: HashMap<String, Vec<Comment>>,
+}
+
+impl FakeApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_account(mut self, account: Account) -> Self {
+        self.accounts.push(account);
+
+        self
+    }
+
+    pub fn with_channel(mut self, account_id: &str, channel: Channel) -> Self {
+        self.channels
+            .entry(account_id.to_string())
+            .or_default()
+            .push(channel);
+
+        self
+    }
+
+    pub fn with_claim(mut self, account_id: &str, claim: Claim) -> Self {
+        self.claims
+            .entry(account_id.to_string())
+            .or_default()
+            .push(claim);
+
+        self
+    }
+
+    pub fn with_comment(mut self, comment: Comment) -> Self {
+        self.comments
+            .entry(comment.claim_id.clone())
+            .or_default()
+            .push(comment);
+
+        self
+    }
+
+    fn claims(&self, owner_id: &str) -> Vec<Claim> {
+        self.claims.get(owner_id).cloned().unwrap_or_default()
+    }
+
+    fn comments(&self, claim_id: &str) -> Vec<Comment> {
+        self.comments.get(claim_id).cloned().unwrap_or_default()
+    }
+}
+
+impl LbryApi for FakeApi {
+    fn stream_accounts(&self, _page_size: usize) -> LocalBoxStream<'_, Account> {
+        stream::iter(self.accounts.clone()).boxed_local()
+    }
+
+    fn stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        _page_size: usize,
+    ) -> LocalBoxStream<'_, Claim> {
+        stream::iter(self.claims(&account_id)).boxed_local()
+    }
+
+    fn stream_comments_by_claim_id(
+        &self,
+        claim_id: String,
+        _page_size: usize,
+    ) -> LocalBoxStream<'_, Comment> {
+        stream::iter(self.comments(&claim_id)).boxed_local()
+    }
+
+    fn try_stream_accounts(
+        &self,
+        _page_size: usize,
+    ) -> BoxStream<'static, Result<Account, ApiError>> {
+        stream::iter(self.accounts.clone().into_iter().map(Ok)).boxed()
+    }
+
+    fn try_stream_channels(
+        &self,
+        account_id: String,
+        _page_size: usize,
+    ) -> BoxStream<'static, Result<Channel, ApiError>> {
+        let channels = self.channels.get(&account_id).cloned().unwrap_or_default();
+
+        stream::iter(channels.into_iter().map(Ok)).boxed()
+    }
+
+    fn try_stream_claims_by_account_id(
+        &self,
+        account_id: String,
+        _page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>> {
+        stream::iter(self.claims(&account_id).into_iter().map(Ok)).boxed()
+    }
+
+    fn try_stream_claims_by_channel_id(
+        &self,
+        channel_id: String,
+        _page_size: usize,
+    ) -> BoxStream<'static, Result<Claim, ApiError>> {
+        stream::iter(self.claims(&channel_id).into_iter().map(Ok)).boxed()
+    }
+
+    fn try_stream_comments_by_claim_id_from(
+        &self,
+        claim_id: String,
+        _page_size: usize,
+        _cursors: CommentCursors,
+    ) -> BoxStream<'static, Result<Comment, ApiError>> {
+        stream::iter(self.comments(&claim_id).into_iter().map(Ok)).boxed()
+    }
+
+    fn try_stream_comments_by_claim_id_until(
+        &self,
+        claim_id: String,
+        _page_size: usize,
+        stop: CommentStop,
+    ) -> BoxStream<'static, Result<Comment, ApiError>> {
+        let comments = self
+            .comments(&claim_id)
+            .into_iter()
+            .take_while(move |comment| !stop.is_reached(comment))
+            .map(Ok);
+
+        stream::iter(comments).boxed()
+    }
+
+    fn resolve_channel(&self, url: &str) -> BoxFuture<'static, Result<Channel, ApiError>> {
+        let channel = self
+            .channels
+            .values()
+            .flatten()
+            .find(|channel| url.trim_start_matches("lbry://") == channel.name)
+            .cloned()
+            .ok_or_else(|| ApiError::Rpc {
+                code: -32500,
+                message: format!("Could not find channel {}", url),
+                data: None,
+            });
+
+        future::ready(channel).boxed()
+    }
+
+    fn resolve_claims(
+        &self,
+        _urls: Vec<String>,
+    ) -> BoxFuture<'static, Result<HashMap<String, ClaimMetadata>, ApiError>> {
+        future::ready(Ok(HashMap::new())).boxed()
+    }
+}
+
+pub fn account(id: &str) -> Account {
+    Account {
+        id: id.to_string(),
+        name: "Account".to_string(),
+        is_default: true,
+    }
+}
+
+pub fn claim(id: &str) -> Claim {
+    Claim {
+        id: id.to_string(),
+        name: id.to_string(),
+        timestamp: Utc.ymd(2020, 8, 1).and_hms(0, 0, 0),
+        metadata: ClaimMetadata::default(),
+    }
+}
+
+pub fn comment(id: &str, claim_id: &str) -> Comment {
+    Comment {
+        id: id.to_string(),
+        claim_id: claim_id.to_string(),
+        parent_id: None,
+        comment: "Hello".to_string(),
+        commenter_id: "commenter_id".to_string(),
+        commenter_name: "@commenter".to_string(),
+        commenter_url: "lbry://@commenter".to_string(),
+        is_hidden: false,
+        timestamp: Utc.ymd(2020, 8, 1).and_hms(0, 0, 0),
+        support_amount: 0.0,
+        source: "lbry".to_string(),
+        is_signature_valid: None,
+    }
+}
+
+pub fn comment_entity(id: &str, claim_id: &str) -> CommentEntity {
+    CommentEntity::new(
+        account("account_id"),
+        Claim {
+            name: "my-video".to_string(),
+            ..claim(claim_id)
+        },
+        comment(id, claim_id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::StreamExt;
+
+    use super::{account, claim, comment, FakeApi};
+    use crate::stream_all_comments;
+
+    #[tokio::test]
+    async fn stream_all_comments_should_walk_fake_api() {
+        let api = FakeApi::new()
+            .with_account(account("account"))
+            .with_claim("account", claim("first"))
+            .with_claim("account", claim("second"))
+            .with_claim("other", claim("unlisted"))
+            .with_comment(comment("a", "first"))
+            .with_comment(comment("b", "second"))
+            .with_comment(comment("c", "second"))
+            .with_comment(comment("d", "unlisted"));
+
+        let comments = stream_all_comments(&api, 50)
+            .map(|(account, claim, comment)| (account.id, claim.id, comment.id))
+            .collect::<Vec<(String, String, String)>>()
+            .await;
+
+        assert_eq!(
+            comments,
+            vec![
+                ("account".to_string(), "first".to_string(), "a".to_string()),
+                ("account".to_string(), "second".to_string(), "b".to_string()),
+                ("account".to_string(), "second".to_string(), "c".to_string()),
+            ]
+        );
+    }
+}
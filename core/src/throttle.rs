@@ -0,0 +1,149 @@
+use chrono::{Duration, NaiveDateTime};
+use std::collections::{HashMap, VecDeque};
+
+use crate::CommentEntity;
+
+#[derive(Clone, Debug)]
+pub struct ThrottledCommenter {
+    pub commenter_name: String,
+    pub comments: Vec<CommentEntity>,
+}
+
+#[derive(Clone, Debug)]
+struct Activity {
+    commenter_name: String,
+    timestamps: VecDeque<NaiveDateTime>,
+    suppressed: Vec<CommentEntity>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Throttle {
+    limit: usize,
+    window: Duration,
+    activities: HashMap<String, Activity>,
+}
+
+impl Throttle {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            activities: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.limit > 0
+    }
+
+    pub fn allow(&mut self, comment: &CommentEntity) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let window = self.window;
+        let activity = self
+            .activities
+            .entry(comment.commenter_id.clone())
+            .or_insert_with(|| Activity {
+                commenter_name: comment.commenter_name.clone(),
+                timestamps: VecDeque::new(),
+                suppressed: Vec::new(),
+            });
+
+        activity.timestamps.push_back(comment.timestamp);
+
+        let latest = activity
+            .timestamps
+            .iter()
+            .max()
+            .cloned()
+            .unwrap_or(comment.timestamp);
+
+        activity
+            .timestamps
+            .retain(|timestamp| *timestamp > latest - window);
+
+        if activity.timestamps.len() > self.limit {
+            activity.suppressed.push(comment.clone());
+
+            false
+        } else {
+            true
+        }
+    }
+
+    pub fn subside(&mut self, now: NaiveDateTime) -> Vec<ThrottledCommenter> {
+        let cutoff = now - self.window;
+        let mut throttled = Vec::new();
+
+        self.activities.retain(|_, activity| {
+            let is_active = activity
+                .timestamps
+                .iter()
+                .any(|timestamp| *timestamp > cutoff);
+
+            if !is_active && !activity.suppressed.is_empty() {
+                throttled.push(ThrottledCommenter {
+                    commenter_name: activity.commenter_name.clone(),
+                    comments: std::mem::take(&mut activity.suppressed),
+                });
+            }
+
+            is_active
+        });
+
+        throttled.sort_by(|a, b| a.commenter_name.cmp(&b.commenter_name));
+
+        throttled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::Throttle;
+    use crate::{testing, CommentEntity};
+
+    fn comment(commenter: &str, minute: u32) -> CommentEntity {
+        CommentEntity {
+            commenter_id: format!("{}_id", commenter),
+            commenter_name: format!("@{}", commenter),
+            commenter_url: format!("lbry://@{}", commenter),
+            timestamp: NaiveDate::from_ymd(2020, 6, 1).and_hms(0, minute, 0),
+            ..testing::comment_entity(&format!("{}-{}", commenter, minute), "claim_id")
+        }
+    }
+
+    #[test]
+    fn throttle_should_collapse_bursts() {
+        let mut throttle = Throttle::new(3, Duration::minutes(60));
+
+        let allowed = (0..20)
+            .map(|minute| throttle.allow(&comment("chatty", minute)))
+            .filter(|allowed| *allowed)
+            .count();
+
+        assert_eq!(allowed, 3);
+        assert!(throttle.allow(&comment("quiet", 5)));
+
+        let during = NaiveDate::from_ymd(2020, 6, 1).and_hms(0, 30, 0);
+        assert!(throttle.subside(during).is_empty());
+
+        let after = NaiveDate::from_ymd(2020, 6, 1).and_hms(2, 0, 0);
+        let throttled = throttle.subside(after);
+        assert_eq!(throttled.len(), 1);
+        assert_eq!(throttled[0].commenter_name, "@chatty");
+        assert_eq!(throttled[0].comments.len(), 17);
+        assert!(throttle.subside(after).is_empty());
+        assert!(throttle.allow(&comment("chatty", 0)));
+    }
+
+    #[test]
+    fn throttle_should_be_disabled_without_limit() {
+        let mut throttle = Throttle::new(0, Duration::minutes(60));
+
+        assert!((0..20).all(|minute| throttle.allow(&comment("chatty", minute))));
+    }
+}
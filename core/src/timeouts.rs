@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiTimeouts {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub page_deadline: Duration,
+}
+
+impl Default for ApiTimeouts {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(30),
+            page_deadline: Duration::from_secs(120),
+        }
+    }
+}
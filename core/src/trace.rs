@@ -0,0 +1,184 @@
+use serde_json::Value;
+use std::{fmt, sync::Arc, time::Duration};
+
+use crate::ApiError;
+
+type TraceHook = Arc<dyn Fn(&ApiTrace) + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiTrace {
+    pub method: String,
+    pub claim_id: Option<String>,
+    pub page: Option<usize>,
+    pub duration: Duration,
+    pub attempts: usize,
+    pub batched: bool,
+    pub result_count: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl ApiTrace {
+    pub(crate) fn new(
+        payload: &Value,
+        duration: Duration,
+        attempts: usize,
+        result: Result<&Value, &ApiError>,
+    ) -> Self {
+        let param = |key: &str| payload.get("params").and_then(|params| params.get(key));
+        let result_count = result.ok().and_then(|response| {
+            let result = response.get("result")?;
+
+            match result.get("items").unwrap_or(result) {
+                Value::Array(items) => Some(items.len()),
+                Value::Object(fields) => Some(fields.len()),
+                _ => None,
+            }
+        });
+        let error = match result {
+            Ok(response) => response.get("error").map(|error| {
+                error
+                    .get("message")
+                    .and_then(|message| message.as_str())
+                    .map(String::from)
+                    .unwrap_or_else(|| error.to_string())
+            }),
+            Err(err) => Some(err.to_string()),
+        };
+
+        Self {
+            method: payload
+                .get("method")
+                .and_then(|method| method.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            claim_id: param("claim_id")
+                .and_then(|claim_id| claim_id.as_str())
+                .map(String::from),
+            page: param("page")
+                .and_then(|page| page.as_u64())
+                .map(|page| page as usize),
+            duration,
+            attempts,
+            batched: false,
+            result_count,
+            error,
+        }
+    }
+
+    pub(crate) fn batched(self) -> Self {
+        Self {
+            batched: true,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ApiTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.method)?;
+
+        if let Some(claim_id) = &self.claim_id {
+            write!(f, " claim={}", claim_id)?;
+        }
+
+        if let Some(page) = self.page {
+            write!(f, " page={}", page)?;
+        }
+
+        write!(f, " took={}ms", self.duration.as_millis())?;
+
+        if self.attempts > 1 {
+            write!(f, " attempts={}", self.attempts)?;
+        }
+
+        if self.batched {
+            write!(f, " batched")?;
+        }
+
+        if let Some(result_count) = self.result_count {
+            write!(f, " results={}", result_count)?;
+        }
+
+        match &self.error {
+            Some(error) => write!(f, " error={}", error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ApiTracer {
+    hooks: Vec<TraceHook>,
+}
+
+impl fmt::Debug for ApiTracer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ApiTracer")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl ApiTracer {
+    pub fn with_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ApiTrace) + Send + Sync + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+
+        self
+    }
+
+    pub(crate) fn record(&self, trace: impl FnOnce() -> ApiTrace) {
+        if self.hooks.is_empty() {
+            return;
+        }
+
+        let trace = trace();
+
+        self.hooks.iter().for_each(|hook| hook(&trace));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::time::Duration;
+
+    use super::ApiTrace;
+    use crate::ApiError;
+
+    #[test]
+    fn api_trace_should_describe_call() {
+        let payload = json!({
+            "method": "comment_list",
+            "params": { "claim_id": "claim_id", "page": 2, "page_size": 50 },
+        });
+        let response = json!({ "result": { "items": [{}, {}], "page": 2 } });
+
+        let trace = ApiTrace::new(&payload, Duration::from_millis(120), 1, Ok(&response));
+        assert_eq!(trace.result_count, Some(2));
+        assert_eq!(trace.error, None);
+        assert_eq!(
+            trace.to_string(),
+            "comment_list claim=claim_id page=2 took=120ms results=2"
+        );
+
+        let error = json!({ "error": { "code": -32601, "message": "Invalid method" } });
+        let trace = ApiTrace::new(&payload, Duration::from_millis(5), 1, Ok(&error)).batched();
+        assert_eq!(trace.error.as_deref(), Some("Invalid method"));
+        assert!(trace.batched);
+
+        let trace = ApiTrace::new(
+            &payload,
+            Duration::from_secs(1),
+            3,
+            Err(&ApiError::DeadlineExceeded),
+        );
+        assert_eq!(trace.result_count, None);
+        assert_eq!(
+            trace.to_string(),
+            "comment_list claim=claim_id page=2 took=1000ms attempts=3 error=Deadline exceeded"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use futures::{future::TryFutureExt, prelude::Future};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+use crate::{ApiError, Scrubber};
+
+pub const DEFAULT_LIBRETRANSLATE_URL: &str = "https://libretranslate.com";
+pub const DEFAULT_DEEPL_URL: &str = "https://api-free.deepl.com";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TranslationProvider {
+    LibreTranslate,
+    DeepL,
+}
+
+impl TranslationProvider {
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            Self::LibreTranslate => DEFAULT_LIBRETRANSLATE_URL,
+            Self::DeepL => DEFAULT_DEEPL_URL,
+        }
+    }
+}
+
+impl FromStr for TranslationProvider {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "libretranslate" => Ok(Self::LibreTranslate),
+            "deepl" => Ok(Self::DeepL),
+            _ => Err(format!("Unknown translation provider {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Translation {
+    pub language: String,
+    pub text: String,
+}
+
+fn language_code(language: &str) -> String {
+    language
+        .split(&['-', '_'][..])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn parse_translation(provider: TranslationProvider, value: &Value) -> Option<Translation> {
+    let (language, text) = match provider {
+        TranslationProvider::LibreTranslate => (
+            value.get("detectedLanguage")?.get("language")?,
+            value.get("translatedText")?,
+        ),
+        TranslationProvider::DeepL => {
+            let translation = value.get("translations")?.get(0)?;
+
+            (
+                translation.get("detected_source_language")?,
+                translation.get("text")?,
+            )
+        }
+    };
+
+    Some(Translation {
+        language: language_code(language.as_str()?),
+        text: text.as_str()?.to_string(),
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct Translator {
+    client: Client,
+    provider: TranslationProvider,
+    url: String,
+    api_key: Option<String>,
+    target: String,
+    scrubber: Option<Scrubber>,
+}
+
+impl Translator {
+    pub fn new(provider: TranslationProvider, target: String) -> Self {
+        Self {
+            client: Client::new(),
+            provider,
+            url: provider.default_url().to_string(),
+            api_key: None,
+            target,
+            scrubber: None,
+        }
+    }
+
+    pub fn with_url(self, url: String) -> Self {
+        Self { url, ..self }
+    }
+
+    pub fn with_api_key(self, api_key: Option<String>) -> Self {
+        Self { api_key, ..self }
+    }
+
+    pub fn with_scrubber(self, scrubber: Option<Scrubber>) -> Self {
+        Self { scrubber, ..self }
+    }
+
+    fn source_text(&self, text: String) -> String {
+        match &self.scrubber {
+            Some(scrubber) => scrubber.scrub(&text),
+            None => text,
+        }
+    }
+
+    pub fn translate<'a, 'r: 'a>(
+        &'a self,
+        text: String,
+    ) -> impl Future<Output = Result<Option<Translation>, ApiError>> + 'r {
+        let text = self.source_text(text);
+        let provider = self.provider;
+        let target = language_code(&self.target);
+        let url = self.url.trim_end_matches('/');
+
+        let request = match provider {
+            TranslationProvider::LibreTranslate => self
+                .client
+                .post(&format!("{}/translate", url))
+                .json(&json!({
+                    "q": text,
+                    "source": "auto",
+                    "target": self.target,
+                    "format": "text",
+                    "api_key": self.api_key,
+                })),
+            TranslationProvider::DeepL => self
+                .client
+                .post(&format!("{}/v2/translate", url))
+                .header(
+                    "Authorization",
+                    format!(
+                        "DeepL-Auth-Key {}",
+                        self.api_key.as_deref().unwrap_or_default()
+                    ),
+                )
+                .form(&[("text", text), ("target_lang", self.target.to_uppercase())]),
+        };
+
+        request
+            .send()
+            .and_then(|resp| async { resp.error_for_status()?.json::<Value>().await })
+            .map_err(ApiError::NetworkError)
+            .and_then(move |value| async move {
+                parse_translation(provider, &value)
+                    .map(|translation| Some(translation).filter(|it| it.language != target))
+                    .ok_or(ApiError::InvalidResponse)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse_translation, Translation, TranslationProvider, Translator};
+    use crate::{ScrubPattern, Scrubber};
+
+    #[test]
+    fn translator_should_scrub_source_text() {
+        let translator = Translator::new(TranslationProvider::DeepL, "en".to_string());
+        let text = "Write to jo@example.com".to_string();
+
+        assert_eq!(translator.source_text(text.clone()), text);
+        assert_eq!(
+            translator
+                .with_scrubber(Some(Scrubber::new(&[ScrubPattern::Email])))
+                .source_text(text),
+            "Write to [redacted]"
+        );
+    }
+
+    #[test]
+    fn parse_translation_should_read_providers() {
+        assert_eq!(
+            parse_translation(
+                TranslationProvider::LibreTranslate,
+                &json!({
+                    "translatedText": "Great video",
+                    "detectedLanguage": { "confidence": 90.0, "language": "es" },
+                })
+            ),
+            Some(Translation {
+                language: "es".to_string(),
+                text: "Great video".to_string(),
+            })
+        );
+
+        assert_eq!(
+            parse_translation(
+                TranslationProvider::DeepL,
+                &json!({
+                    "translations": [{ "detected_source_language": "PT-BR", "text": "Thanks" }],
+                })
+            ),
+            Some(Translation {
+                language: "pt".to_string(),
+                text: "Thanks".to_string(),
+            })
+        );
+
+        assert!(parse_translation(
+            TranslationProvider::DeepL,
+            &json!({ "message": "Wrong endpoint" })
+        )
+        .is_none());
+        assert_eq!(
+            "deepl".parse::<TranslationProvider>(),
+            Ok(TranslationProvider::DeepL)
+        );
+    }
+}
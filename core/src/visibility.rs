@@ -0,0 +1,73 @@
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommentVisibility {
+    All,
+    Visible,
+    Hidden,
+}
+
+impl CommentVisibility {
+    pub(crate) fn apply(&self, payload: &mut Value) {
+        let params = &mut payload["params"];
+
+        match self {
+            Self::All => {}
+            Self::Visible => {
+                params["visible"] = json!(true);
+            }
+            Self::Hidden => {
+                params["hidden"] = json!(true);
+            }
+        }
+    }
+
+    pub(crate) fn apply_commentron(&self, payload: &mut Value) {
+        if let Self::Hidden = self {
+            payload["params"]["hidden"] = json!(true);
+        }
+    }
+}
+
+impl FromStr for CommentVisibility {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "all" => Ok(Self::All),
+            "visible" => Ok(Self::Visible),
+            "hidden" => Ok(Self::Hidden),
+            _ => Err(format!("Unknown comment visibility {}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::CommentVisibility;
+
+    #[test]
+    fn apply_should_set_visibility_params() {
+        let payload = json!({ "method": "comment_list", "params": { "claim_id": "claim_id" } });
+
+        let mut all = payload.clone();
+        CommentVisibility::All.apply(&mut all);
+        assert_eq!(all, payload);
+
+        let mut visible = payload.clone();
+        "visible"
+            .parse::<CommentVisibility>()
+            .expect("Invalid visibility")
+            .apply(&mut visible);
+        assert_eq!(visible["params"]["visible"], json!(true));
+        assert_eq!(visible["params"].get("hidden"), None);
+
+        let mut hidden = payload;
+        CommentVisibility::Hidden.apply(&mut hidden);
+        assert_eq!(hidden["params"]["hidden"], json!(true));
+        assert!("shown".parse::<CommentVisibility>().is_err());
+    }
+}
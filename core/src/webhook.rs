@@ -0,0 +1,294 @@
+use chrono::NaiveDateTime;
+use futures::{future::FutureExt, prelude::Future};
+use reqwest::Client;
+#[cfg(feature = "schemas")]
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::value::Value;
+use std::{
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use crate::{ApiTimeouts, ClientConfig, CommentEntity, Scrubber};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PayloadVersion {
+    V1,
+    V2,
+}
+
+impl PayloadVersion {
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+        }
+    }
+}
+
+impl FromStr for PayloadVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim_start_matches('v') {
+            "1" => Ok(Self::V1),
+            "2" => Ok(Self::V2),
+            _ => Err(format!("Unknown payload version {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemas", derive(JsonSchema))]
+pub struct WebhookPayloadV1 {
+    pub payload_version: u8,
+    pub id: String,
+    pub claim_id: String,
+    pub claim_name: String,
+    pub commenter_id: String,
+    pub commenter_name: String,
+    pub commenter_url: String,
+    pub comment: String,
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemas", derive(JsonSchema))]
+pub struct WebhookPayloadV2 {
+    pub payload_version: u8,
+    pub id: String,
+    pub account_id: String,
+    pub claim_id: String,
+    pub claim_name: String,
+    pub parent_id: Option<String>,
+    pub commenter_id: String,
+    pub commenter_name: String,
+    pub commenter_url: String,
+    pub comment: String,
+    pub is_hidden: bool,
+    pub support_amount: f64,
+    pub timestamp: NaiveDateTime,
+    pub source: String,
+}
+
+pub fn webhook_payload(comment: &CommentEntity, version: PayloadVersion) -> Value {
+    let payload = match version {
+        PayloadVersion::V1 => serde_json::to_value(WebhookPayloadV1 {
+            payload_version: version.number(),
+            id: comment.id.clone(),
+            claim_id: comment.claim_id.clone(),
+            claim_name: comment.claim_name.clone(),
+            commenter_id: comment.commenter_id.clone(),
+            commenter_name: comment.commenter_name.clone(),
+            commenter_url: comment.commenter_url.clone(),
+            comment: comment.comment.clone(),
+            timestamp: comment.timestamp,
+        }),
+        PayloadVersion::V2 => serde_json::to_value(WebhookPayloadV2 {
+            payload_version: version.number(),
+            id: comment.id.clone(),
+            account_id: comment.account_id.clone(),
+            claim_id: comment.claim_id.clone(),
+            claim_name: comment.claim_name.clone(),
+            parent_id: comment.parent_id.clone(),
+            commenter_id: comment.commenter_id.clone(),
+            commenter_name: comment.commenter_name.clone(),
+            commenter_url: comment.commenter_url.clone(),
+            comment: comment.comment.clone(),
+            is_hidden: comment.is_hidden,
+            support_amount: comment.support_amount,
+            timestamp: comment.timestamp,
+            source: comment.source.clone(),
+        }),
+    };
+
+    payload.expect("Could not serialize webhook payload")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Webhook {
+    pub url: String,
+    pub version: PayloadVersion,
+    pub bearer_token: Option<String>,
+}
+
+impl FromStr for Webhook {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.rsplitn(2, '|');
+        let last = parts.next().unwrap_or_default();
+
+        match parts.next() {
+            Some(url) => Ok(Self {
+                url: url.to_string(),
+                version: last.parse::<PayloadVersion>()?,
+                bearer_token: None,
+            }),
+            None => Ok(Self {
+                url: last.to_string(),
+                version: PayloadVersion::V1,
+                bearer_token: None,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Webhooks {
+    client: Client,
+    webhooks: Vec<Webhook>,
+    channel_webhooks: Arc<RwLock<Vec<Webhook>>>,
+    scrubber: Option<Scrubber>,
+}
+
+impl Webhooks {
+    pub fn new(webhooks: Vec<Webhook>) -> Self {
+        Self::with_timeouts(webhooks, ApiTimeouts::default())
+    }
+
+    pub fn with_timeouts(webhooks: Vec<Webhook>, timeouts: ApiTimeouts) -> Self {
+        let client = ClientConfig::default()
+            .build_client(&timeouts)
+            .expect("Unable to build HTTP client");
+
+        Self {
+            client,
+            webhooks,
+            channel_webhooks: Arc::new(RwLock::new(Vec::new())),
+            scrubber: None,
+        }
+    }
+
+    pub fn with_scrubber(self, scrubber: Option<Scrubber>) -> Self {
+        Self { scrubber, ..self }
+    }
+
+    pub fn set_channel_webhooks(&self, webhooks: Vec<Webhook>) {
+        *self.channel_webhooks.write().expect("Unable to get lock") = webhooks;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+            && self
+                .channel_webhooks
+                .read()
+                .expect("Unable to get lock")
+                .is_empty()
+    }
+
+    pub fn send<'a, 'b, 'r: 'a>(
+        &'a self,
+        webhook: &'b Webhook,
+        comment: &'b CommentEntity,
+    ) -> impl Future<Output = Result<(), reqwest::Error>> + 'r {
+        let request = self
+            .client
+            .post(&webhook.url)
+            .header("X-Payload-Version", webhook.version.number().to_string());
+        let request = match &webhook.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+
+        request
+            .json(&self.payload(webhook, comment))
+            .send()
+            .map(|res| res.and_then(|resp| resp.error_for_status()).map(|_| ()))
+    }
+
+    pub fn payload(&self, webhook: &Webhook, comment: &CommentEntity) -> Value {
+        match &self.scrubber {
+            Some(scrubber) => webhook_payload(
+                &CommentEntity {
+                    comment: scrubber.scrub(&comment.comment),
+                    ..comment.clone()
+                },
+                webhook.version,
+            ),
+            None => webhook_payload(comment, webhook.version),
+        }
+    }
+
+    pub fn post_json<'a, 'b, 'r: 'a>(
+        &'a self,
+        url: &'b str,
+        payload: &'b Value,
+    ) -> impl Future<Output = Result<(), reqwest::Error>> + 'r {
+        self.client
+            .post(url)
+            .json(payload)
+            .send()
+            .map(|res| res.and_then(|resp| resp.error_for_status()).map(|_| ()))
+    }
+
+    pub fn webhooks(&self) -> Vec<Webhook> {
+        self.webhooks
+            .iter()
+            .cloned()
+            .chain(
+                self.channel_webhooks
+                    .read()
+                    .expect("Unable to get lock")
+                    .iter()
+                    .cloned(),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PayloadVersion, Webhook, Webhooks};
+    use crate::{testing, CommentEntity, ScrubPattern, Scrubber};
+
+    #[test]
+    fn webhook_should_parse() {
+        assert_eq!(
+            "https://example.com/hook|v2".parse::<Webhook>(),
+            Ok(Webhook {
+                url: "https://example.com/hook".to_string(),
+                version: PayloadVersion::V2,
+                bearer_token: None,
+            })
+        );
+        assert_eq!(
+            "https://example.com/hook".parse::<Webhook>(),
+            Ok(Webhook {
+                url: "https://example.com/hook".to_string(),
+                version: PayloadVersion::V1,
+                bearer_token: None,
+            })
+        );
+        assert!("https://example.com/hook|v9".parse::<Webhook>().is_err());
+    }
+
+    #[test]
+    fn webhooks_should_scrub_payloads() {
+        let comment = CommentEntity {
+            comment: "Mail jo@example.com".to_string(),
+            ..testing::comment_entity("id", "claim_id")
+        };
+        let webhook = |version| Webhook {
+            url: "https://example.com/hook".to_string(),
+            version,
+            bearer_token: None,
+        };
+        let webhooks =
+            Webhooks::new(vec![]).with_scrubber(Some(Scrubber::new(&[ScrubPattern::Email])));
+
+        let v1 = webhooks.payload(&webhook(PayloadVersion::V1), &comment);
+        let v2 = webhooks.payload(&webhook(PayloadVersion::V2), &comment);
+
+        assert_eq!(v1["payload_version"], 1);
+        assert_eq!(v1["comment"], "Mail [redacted]");
+        assert_eq!(v2["payload_version"], 2);
+        assert_eq!(v2["comment"], "Mail [redacted]");
+        assert_eq!(v2["account_id"], "account_id");
+        assert_eq!(
+            Webhooks::new(vec![]).payload(&webhook(PayloadVersion::V2), &comment)["comment"],
+            "Mail jo@example.com"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+CREATE TABLE comments_new (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  account_id VARCHAR NOT NULL,
+  claim_id VARCHAR NOT NULL,
+  claim_name VARCHAR NOT NULL,
+  commenter_id VARCHAR NOT NULL,
+  commenter_name VARCHAR NOT NULL,
+  commenter_url VARCHAR NOT NULL,
+  comment TEXT NOT NULL,
+  is_hidden BOOLEAN NOT NULL DEFAULT 'f',
+  timestamp TIMESTAMP NOT NULL
+);
+
+INSERT INTO comments_new (id, account_id, claim_id, claim_name, commenter_id, commenter_name, commenter_url, comment, is_hidden, timestamp)
+SELECT id, account_id, claim_id, claim_name, commenter_id, commenter_name, commenter_url, comment, is_hidden, timestamp FROM comments;
+
+DROP TABLE comments;
+ALTER TABLE comments_new RENAME TO comments;
@@ -0,0 +1,2 @@
+ALTER TABLE comments ADD COLUMN is_read BOOLEAN NOT NULL DEFAULT 0;
+UPDATE comments SET is_read = 1;
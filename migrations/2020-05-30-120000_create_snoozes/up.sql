@@ -0,0 +1,6 @@
+CREATE TABLE snoozes (
+  kind VARCHAR NOT NULL,
+  target VARCHAR NOT NULL,
+  until TIMESTAMP NOT NULL,
+  PRIMARY KEY (kind, target)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE claim_settings (
+  claim_id VARCHAR PRIMARY KEY NOT NULL,
+  retention_days INTEGER
+);
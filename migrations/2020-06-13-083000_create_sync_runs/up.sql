@@ -0,0 +1,8 @@
+CREATE TABLE sync_runs (
+  id INTEGER PRIMARY KEY NOT NULL,
+  kind VARCHAR NOT NULL,
+  status VARCHAR NOT NULL,
+  details TEXT NOT NULL,
+  started_at TIMESTAMP NOT NULL,
+  finished_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS comments_account_id_timestamp;
+DROP INDEX IF EXISTS comments_commenter_id;
+DROP INDEX IF EXISTS comments_claim_id_timestamp;
@@ -0,0 +1,5 @@
+CREATE TABLE notification_variants (
+  comment_id VARCHAR PRIMARY KEY NOT NULL,
+  variant VARCHAR NOT NULL,
+  sent_at TIMESTAMP NOT NULL
+);
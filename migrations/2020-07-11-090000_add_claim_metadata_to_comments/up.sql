@@ -0,0 +1,3 @@
+ALTER TABLE comments ADD COLUMN claim_title VARCHAR;
+ALTER TABLE comments ADD COLUMN claim_thumbnail_url VARCHAR;
+ALTER TABLE comments ADD COLUMN claim_canonical_url VARCHAR;
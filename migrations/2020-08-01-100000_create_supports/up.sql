@@ -0,0 +1,9 @@
+CREATE TABLE supports (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  account_id VARCHAR NOT NULL,
+  claim_id VARCHAR NOT NULL,
+  claim_name VARCHAR NOT NULL,
+  amount DOUBLE NOT NULL,
+  is_tip BOOLEAN NOT NULL,
+  received_at TIMESTAMP NOT NULL
+);
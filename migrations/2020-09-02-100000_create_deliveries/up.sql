@@ -0,0 +1,11 @@
+CREATE TABLE deliveries (
+  comment_id VARCHAR NOT NULL,
+  target VARCHAR NOT NULL,
+  status VARCHAR NOT NULL,
+  attempts INTEGER NOT NULL,
+  last_error TEXT,
+  created_at TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (comment_id, target)
+);
+CREATE INDEX deliveries_status ON deliveries (status);
@@ -0,0 +1,10 @@
+CREATE TABLE claims (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  title VARCHAR,
+  view_count BIGINT,
+  effective_amount DOUBLE,
+  updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX claims_view_count ON claims (view_count);
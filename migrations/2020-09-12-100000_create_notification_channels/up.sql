@@ -0,0 +1,6 @@
+CREATE TABLE notification_channels (
+  name VARCHAR PRIMARY KEY NOT NULL,
+  kind VARCHAR NOT NULL,
+  target VARCHAR NOT NULL,
+  token_env VARCHAR
+);
@@ -0,0 +1,29 @@
+CREATE TABLE comments_new (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  account_id VARCHAR NOT NULL,
+  claim_id VARCHAR NOT NULL,
+  claim_name VARCHAR NOT NULL,
+  commenter_id VARCHAR NOT NULL,
+  commenter_name VARCHAR NOT NULL,
+  commenter_url VARCHAR NOT NULL,
+  comment TEXT NOT NULL,
+  is_hidden BOOLEAN NOT NULL DEFAULT 'f',
+  timestamp TIMESTAMP NOT NULL,
+  support_amount DOUBLE NOT NULL DEFAULT 0,
+  is_read BOOLEAN NOT NULL DEFAULT 0,
+  parent_id VARCHAR,
+  claim_title VARCHAR,
+  claim_thumbnail_url VARCHAR,
+  claim_canonical_url VARCHAR,
+  source VARCHAR NOT NULL DEFAULT 'lbry'
+);
+
+INSERT INTO comments_new (id, account_id, claim_id, claim_name, commenter_id, commenter_name, commenter_url, comment, is_hidden, timestamp, support_amount, is_read, parent_id, claim_title, claim_thumbnail_url, claim_canonical_url, source)
+SELECT id, account_id, claim_id, claim_name, commenter_id, commenter_name, commenter_url, comment, is_hidden, timestamp, support_amount, is_read, parent_id, claim_title, claim_thumbnail_url, claim_canonical_url, source FROM comments;
+
+DROP TABLE comments;
+ALTER TABLE comments_new RENAME TO comments;
+
+CREATE INDEX comments_claim_id_timestamp ON comments (claim_id, timestamp);
+CREATE INDEX comments_commenter_id ON comments (commenter_id);
+CREATE INDEX comments_account_id_timestamp ON comments (account_id, timestamp);
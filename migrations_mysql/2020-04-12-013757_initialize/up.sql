@@ -0,0 +1 @@
+-- Your SQL goes here
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE comments (
+  id VARCHAR(255) PRIMARY KEY NOT NULL,
+  account_id VARCHAR(255) NOT NULL,
+  claim_id VARCHAR(255) NOT NULL,
+  claim_name VARCHAR(255) NOT NULL,
+  commenter_id VARCHAR(255) NOT NULL,
+  commenter_name VARCHAR(255) NOT NULL,
+  commenter_url TEXT NOT NULL,
+  comment TEXT NOT NULL,
+  is_hidden BOOLEAN NOT NULL DEFAULT FALSE,
+  timestamp DATETIME NOT NULL
+);
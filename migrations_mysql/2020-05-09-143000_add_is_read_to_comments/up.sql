@@ -0,0 +1,2 @@
+ALTER TABLE comments ADD COLUMN is_read BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE comments SET is_read = TRUE;
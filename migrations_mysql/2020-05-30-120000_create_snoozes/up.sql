@@ -0,0 +1,6 @@
+CREATE TABLE snoozes (
+  kind VARCHAR(255) NOT NULL,
+  target VARCHAR(255) NOT NULL,
+  until DATETIME NOT NULL,
+  PRIMARY KEY (kind, target)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE claim_settings (
+  claim_id VARCHAR(255) PRIMARY KEY NOT NULL,
+  retention_days INTEGER
+);
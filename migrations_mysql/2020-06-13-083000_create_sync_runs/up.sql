@@ -0,0 +1,8 @@
+CREATE TABLE sync_runs (
+  id INTEGER PRIMARY KEY NOT NULL AUTO_INCREMENT,
+  kind VARCHAR(255) NOT NULL,
+  status VARCHAR(255) NOT NULL,
+  details TEXT NOT NULL,
+  started_at DATETIME NOT NULL,
+  finished_at DATETIME NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP INDEX comments_account_id_timestamp ON comments;
+DROP INDEX comments_commenter_id ON comments;
+DROP INDEX comments_claim_id_timestamp ON comments;
@@ -0,0 +1,3 @@
+CREATE INDEX comments_claim_id_timestamp ON comments (claim_id, timestamp);
+CREATE INDEX comments_commenter_id ON comments (commenter_id);
+CREATE INDEX comments_account_id_timestamp ON comments (account_id, timestamp);
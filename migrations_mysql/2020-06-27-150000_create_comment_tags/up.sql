@@ -0,0 +1,5 @@
+CREATE TABLE comment_tags (
+  comment_id VARCHAR(255) NOT NULL,
+  tag VARCHAR(255) NOT NULL,
+  PRIMARY KEY (comment_id, tag)
+);
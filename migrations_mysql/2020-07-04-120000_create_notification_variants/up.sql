@@ -0,0 +1,5 @@
+CREATE TABLE notification_variants (
+  comment_id VARCHAR(255) PRIMARY KEY NOT NULL,
+  variant VARCHAR(255) NOT NULL,
+  sent_at DATETIME NOT NULL
+);
@@ -0,0 +1,3 @@
+ALTER TABLE comments DROP COLUMN claim_canonical_url;
+ALTER TABLE comments DROP COLUMN claim_thumbnail_url;
+ALTER TABLE comments DROP COLUMN claim_title;
@@ -0,0 +1,3 @@
+ALTER TABLE comments ADD COLUMN claim_title TEXT;
+ALTER TABLE comments ADD COLUMN claim_thumbnail_url TEXT;
+ALTER TABLE comments ADD COLUMN claim_canonical_url TEXT;
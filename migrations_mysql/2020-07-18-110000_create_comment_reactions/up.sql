@@ -0,0 +1,5 @@
+CREATE TABLE comment_reactions (
+  comment_id VARCHAR(255) PRIMARY KEY NOT NULL,
+  likes INTEGER NOT NULL,
+  dislikes INTEGER NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE supports (
+  id VARCHAR(255) PRIMARY KEY NOT NULL,
+  account_id VARCHAR(255) NOT NULL,
+  claim_id VARCHAR(255) NOT NULL,
+  claim_name VARCHAR(255) NOT NULL,
+  amount DOUBLE NOT NULL,
+  is_tip BOOLEAN NOT NULL,
+  received_at DATETIME NOT NULL
+);
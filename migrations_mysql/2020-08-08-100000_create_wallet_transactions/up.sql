@@ -0,0 +1,7 @@
+CREATE TABLE wallet_transactions (
+  txid VARCHAR(255) PRIMARY KEY NOT NULL,
+  account_id VARCHAR(255) NOT NULL,
+  amount DOUBLE NOT NULL,
+  timestamp DATETIME,
+  received_at DATETIME NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE canned_replies (
+  name VARCHAR(255) PRIMARY KEY NOT NULL,
+  body TEXT NOT NULL,
+  channel_name VARCHAR(255)
+);
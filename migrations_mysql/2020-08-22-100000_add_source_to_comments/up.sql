@@ -0,0 +1 @@
+ALTER TABLE comments ADD COLUMN source VARCHAR(255) NOT NULL DEFAULT 'lbry';
@@ -0,0 +1,6 @@
+CREATE TABLE comment_cursors (
+  claim_id VARCHAR(255) PRIMARY KEY NOT NULL,
+  next_page INTEGER NOT NULL,
+  total_pages INTEGER NOT NULL,
+  updated_at DATETIME NOT NULL
+);
@@ -0,0 +1,11 @@
+CREATE TABLE deliveries (
+  comment_id VARCHAR(255) NOT NULL,
+  target VARCHAR(255) NOT NULL,
+  status VARCHAR(255) NOT NULL,
+  attempts INTEGER NOT NULL,
+  last_error TEXT,
+  created_at DATETIME NOT NULL,
+  updated_at DATETIME NOT NULL,
+  PRIMARY KEY (comment_id, target)
+);
+CREATE INDEX deliveries_status ON deliveries (status);
@@ -0,0 +1,10 @@
+CREATE TABLE claims (
+  id VARCHAR(255) PRIMARY KEY NOT NULL,
+  name VARCHAR(255) NOT NULL,
+  title TEXT,
+  view_count BIGINT,
+  effective_amount DOUBLE,
+  updated_at DATETIME NOT NULL
+);
+
+CREATE INDEX claims_view_count ON claims (view_count);
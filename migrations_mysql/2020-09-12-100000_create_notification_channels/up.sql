@@ -0,0 +1,6 @@
+CREATE TABLE notification_channels (
+  name VARCHAR(255) PRIMARY KEY NOT NULL,
+  kind VARCHAR(255) NOT NULL,
+  target TEXT NOT NULL,
+  token_env VARCHAR(255)
+);
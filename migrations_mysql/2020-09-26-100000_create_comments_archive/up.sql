@@ -0,0 +1,23 @@
+CREATE TABLE comments_archive (
+  id VARCHAR(255) PRIMARY KEY NOT NULL,
+  account_id VARCHAR(255) NOT NULL,
+  claim_id VARCHAR(255) NOT NULL,
+  claim_name VARCHAR(255) NOT NULL,
+  commenter_id VARCHAR(255) NOT NULL,
+  commenter_name VARCHAR(255) NOT NULL,
+  commenter_url TEXT NOT NULL,
+  comment TEXT NOT NULL,
+  is_hidden BOOLEAN NOT NULL,
+  timestamp DATETIME NOT NULL,
+  support_amount DOUBLE NOT NULL,
+  is_read BOOLEAN NOT NULL,
+  parent_id VARCHAR(255),
+  claim_title TEXT,
+  claim_thumbnail_url TEXT,
+  claim_canonical_url TEXT,
+  source VARCHAR(255) NOT NULL,
+  is_signature_valid BOOLEAN
+);
+
+CREATE INDEX comments_archive_claim_id_timestamp ON comments_archive (claim_id, timestamp);
+CREATE INDEX comments_archive_commenter_id ON comments_archive (commenter_id);
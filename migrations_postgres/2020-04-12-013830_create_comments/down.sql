@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS comments;
+DROP INDEX IF EXISTS idx_comments_is_read;
@@ -0,0 +1 @@
+ALTER TABLE comments ADD COLUMN support_amount DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+CREATE TABLE saved_searches (
+  name VARCHAR PRIMARY KEY NOT NULL,
+  query TEXT NOT NULL,
+  recipient VARCHAR
+);
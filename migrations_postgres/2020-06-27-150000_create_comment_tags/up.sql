@@ -0,0 +1,5 @@
+CREATE TABLE comment_tags (
+  comment_id VARCHAR NOT NULL,
+  tag VARCHAR NOT NULL,
+  PRIMARY KEY (comment_id, tag)
+);
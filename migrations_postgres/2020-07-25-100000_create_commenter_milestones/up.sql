@@ -0,0 +1,6 @@
+CREATE TABLE commenter_milestones (
+  commenter_id VARCHAR NOT NULL,
+  milestone BIGINT NOT NULL,
+  reached_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (commenter_id, milestone)
+);
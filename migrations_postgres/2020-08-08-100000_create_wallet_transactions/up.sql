@@ -0,0 +1,7 @@
+CREATE TABLE wallet_transactions (
+  txid VARCHAR PRIMARY KEY NOT NULL,
+  account_id VARCHAR NOT NULL,
+  amount DOUBLE PRECISION NOT NULL,
+  timestamp TIMESTAMP,
+  received_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE comments ADD COLUMN source VARCHAR NOT NULL DEFAULT 'lbry';
@@ -0,0 +1,6 @@
+CREATE TABLE comment_cursors (
+  claim_id VARCHAR PRIMARY KEY NOT NULL,
+  next_page INTEGER NOT NULL,
+  total_pages INTEGER NOT NULL,
+  updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE comments ADD COLUMN is_signature_valid BOOLEAN;
@@ -0,0 +1,23 @@
+CREATE TABLE comments_archive (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  account_id VARCHAR NOT NULL,
+  claim_id VARCHAR NOT NULL,
+  claim_name VARCHAR NOT NULL,
+  commenter_id VARCHAR NOT NULL,
+  commenter_name VARCHAR NOT NULL,
+  commenter_url VARCHAR NOT NULL,
+  comment TEXT NOT NULL,
+  is_hidden BOOLEAN NOT NULL,
+  timestamp TIMESTAMP NOT NULL,
+  support_amount DOUBLE PRECISION NOT NULL,
+  is_read BOOLEAN NOT NULL,
+  parent_id VARCHAR,
+  claim_title VARCHAR,
+  claim_thumbnail_url VARCHAR,
+  claim_canonical_url VARCHAR,
+  source VARCHAR NOT NULL,
+  is_signature_valid BOOLEAN
+);
+
+CREATE INDEX comments_archive_claim_id_timestamp ON comments_archive (claim_id, timestamp);
+CREATE INDEX comments_archive_commenter_id ON comments_archive (commenter_id);
@@ -1,155 +1,4946 @@
+#![cfg_attr(not(all(feature = "email", feature = "scheduler")), allow(dead_code))]
+
 #[macro_use]
 extern crate log;
 
-use dotenv::dotenv;
-use futures::stream::{self, Stream, StreamExt};
-use job_scheduler::{Job, JobScheduler};
-use lettre::{
-    smtp::{extension::ClientId, ClientSecurity, ConnectionReuseParameters},
-    SendableEmail, SmtpClient, SmtpTransport, Transport,
-};
-use num_cpus;
-use std::{
-    env,
-    sync::{Arc, Mutex},
-};
-use tokio::runtime::Builder;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use dotenv::dotenv;
+use futures::{
+    future::{self, FutureExt},
+    stream::{self, BoxStream, Stream, StreamExt, TryStreamExt},
+};
+#[cfg(feature = "scheduler")]
+use job_scheduler::{Job, JobScheduler};
+#[cfg(feature = "email")]
+use lettre::{
+    smtp::{extension::ClientId, ClientSecurity, ConnectionReuseParameters},
+    EmailAddress, SendableEmail, SmtpClient, SmtpTransport, Transport,
+};
+use num_cpus;
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{channel, Receiver},
+        Arc, Mutex,
+    },
+    thread, time,
+};
+use tokio::{runtime::Builder, time::timeout_at};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+#[cfg(feature = "read-links")]
+use core::ReadLinks;
+#[cfg(all(feature = "email", feature = "scheduler"))]
+use core::DEFAULT_REPORT_TEMPLATE;
+use core::{
+    commenter_contacts, config_key, detect_anomalies, diff_comments, export_schemas,
+    import_odysee_export, import_youtube_csv, interleave_streams, is_server_url, parse_duration,
+    parse_retention, reaction_changes, render_commenters_csv, render_env_file,
+    render_markdown_thread, stream_event_comments, Account, Api, ApiError, ApiTimeouts, ApiTracer,
+    BundleMerge, CannedReply, Channel, ChannelTarget, Claim, ClaimCount, ClaimEntity, ClaimFeeds,
+    ClaimLocks, ClaimMetadata, ClaimSetting, ClientConfig, Comment, CommentBackend, CommentCursors,
+    CommentDelta, CommentEntity, CommentSort, CommentStop, CommentStore, CommentVisibility,
+    CommenterContact, CommenterMilestone, Commentron, ConfigProfiles, ConflictStrategy, Delivery,
+    DigestOrder, DiskGuard, Endpoints, EnvInterlock, EventSubscription, Exporter, ImportFormat,
+    LbryApi, LbryUrl, MetricsPusher, MilestoneAction, Milestones, ModerationError, NewSyncRun,
+    NotificationChannel, OtlpExporter, RateLimiter, Reaction, ReactionChange, Recipient,
+    ReconcileReport, RequestBatcher, RetentionPolicy, RetryPolicy, RuleBundle, RunMetrics,
+    RunSpans, SavedSearch, ScrubPattern, Scrubber, SearchQuery, SearchTerm, Snooze, SnoozeKind,
+    SpanCollector, Storage, Support, SupportEntity, Template, TemplateSplit, Throttle, Transaction,
+    TransactionEntity, Translation, TranslationProvider, Translator, ViewCounts, Webhook, Webhooks,
+    CONFIG_KEYS, DEFAULT_POOL_SIZE, DEFAULT_USER_AGENT, DEFAULT_VIEW_COUNT_URL, DELIVERY_DEAD,
+    DELIVERY_PENDING,
+};
+#[cfg(feature = "email")]
+use core::{
+    email_delivery_target, previous_month, ClaimCollections, Collection, Emails, MonthlyReport,
+    NotificationVariant, DELIVERY_EMAIL,
+};
+#[cfg(feature = "read-links")]
+use std::net::SocketAddr;
+#[cfg(feature = "email")]
+use std::slice;
+
+const EVENT_RECONNECT_SECS: u64 = 30;
+
+const DELIVERY_MAX_ATTEMPTS: i32 = 3;
+
+#[cfg(feature = "email")]
+macro_rules! mail {
+    ($context:expr, $method:ident($($arg:expr),*)) => {
+        send_emails(&$context.mailer, $context.emails.$method($($arg),*))
+    };
+}
+
+#[cfg(not(feature = "email"))]
+macro_rules! mail {
+    ($context:expr, $method:ident($($arg:expr),*)) => {{
+        let _ = ($context, $(&$arg,)*);
+    }};
+}
+
+#[cfg(feature = "email")]
+macro_rules! mail_comments {
+    ($context:expr, $method:ident($($arg:expr),*), $comment_entities:expr) => {
+        send_comment_emails(
+            $context,
+            $context.emails.$method($($arg),*),
+            $comment_entities,
+        )
+    };
+}
+
+#[cfg(not(feature = "email"))]
+macro_rules! mail_comments {
+    ($context:expr, $method:ident($($arg:expr),*), $comment_entities:expr) => {{
+        let _ = ($context, $(&$arg,)* $comment_entities);
+
+        Ok::<(), String>(())
+    }};
+}
+
+fn count_failures<A>(
+    failures: Arc<AtomicUsize>,
+    results: impl Stream<Item = Result<A, ApiError>>,
+) -> impl Stream<Item = A> {
+    results.filter_map(move |result| {
+        let item = match result {
+            Ok(item) => Some(item),
+            Err(err) => {
+                error!("Unable to fetch page: {}", err);
+
+                failures.fetch_add(1, Ordering::SeqCst);
+
+                None
+            }
+        };
+
+        future::ready(item)
+    })
+}
+
+struct CommentSources<A> {
+    api: Arc<A>,
+    commentron: Arc<Commentron>,
+    comment_backend: CommentBackend,
+    page_size: usize,
+    cursors: CommentCursors,
+    comment_stops: Arc<HashMap<String, CommentStop>>,
+    failures: Arc<AtomicUsize>,
+}
+
+impl<A> Clone for CommentSources<A> {
+    fn clone(&self) -> Self {
+        Self {
+            api: self.api.clone(),
+            commentron: self.commentron.clone(),
+            comment_backend: self.comment_backend,
+            page_size: self.page_size,
+            cursors: self.cursors.clone(),
+            comment_stops: self.comment_stops.clone(),
+            failures: self.failures.clone(),
+        }
+    }
+}
+
+fn claim_comments<A: LbryApi>(
+    sources: &CommentSources<A>,
+    claim_id: String,
+) -> BoxStream<'static, Comment> {
+    let stop = sources.comment_stops.get(&claim_id).cloned();
+    let failures = sources.failures.clone();
+    let page_size = sources.page_size;
+    let cursors = sources.cursors.clone();
+
+    match (sources.comment_backend, stop) {
+        (CommentBackend::Sdk, Some(stop)) => count_failures(
+            failures,
+            sources
+                .api
+                .try_stream_comments_by_claim_id_until(claim_id, page_size, stop),
+        )
+        .boxed(),
+        (CommentBackend::Sdk, None) => count_failures(
+            failures,
+            sources
+                .api
+                .try_stream_comments_by_claim_id_from(claim_id, page_size, cursors),
+        )
+        .boxed(),
+        (CommentBackend::Commentron, Some(stop)) => count_failures(
+            failures,
+            sources
+                .commentron
+                .try_stream_comments_by_claim_id_until(claim_id, page_size, stop),
+        )
+        .boxed(),
+        (CommentBackend::Commentron, None) => count_failures(
+            failures,
+            sources
+                .commentron
+                .try_stream_comments_by_claim_id_from(claim_id, page_size, cursors),
+        )
+        .boxed(),
+    }
+}
+
+fn until_deadline<'a, A: 'a>(
+    items: impl Stream<Item = A> + Unpin + 'a,
+    deadline: Option<time::Instant>,
+    expired: Arc<AtomicBool>,
+) -> impl Stream<Item = A> + 'a {
+    stream::unfold(items, move |mut items| {
+        let expired = expired.clone();
+
+        async move {
+            let deadline = match deadline {
+                Some(deadline) => deadline,
+                None => return items.next().await.map(|item| (item, items)),
+            };
+
+            match timeout_at(deadline.into(), items.next()).await {
+                Ok(item) => item.map(|item| (item, items)),
+                Err(_) => {
+                    if !expired.swap(true, Ordering::SeqCst) {
+                        warn!("Run deadline reached, committing the comments fetched so far");
+                    }
+
+                    None
+                }
+            }
+        }
+    })
+}
+
+fn is_watched_channel(watch_channels: &[String], channel: &Channel) -> bool {
+    watch_channels
+        .iter()
+        .any(|watched| watched == "*" || *watched == channel.id || *watched == channel.name)
+}
+
+fn account_claims<A: LbryApi + Send + Sync + 'static>(
+    api_ref: Arc<A>,
+    watch_channels_ref: Arc<Vec<String>>,
+    account_id: String,
+    page_size: usize,
+    failures: Arc<AtomicUsize>,
+) -> BoxStream<'static, Claim> {
+    if watch_channels_ref.is_empty() {
+        return count_failures(
+            failures,
+            api_ref.try_stream_claims_by_account_id(account_id, page_size),
+        )
+        .boxed();
+    }
+
+    let claim_api_ref = api_ref.clone();
+    let claim_failures = failures.clone();
+
+    count_failures(failures, api_ref.try_stream_channels(account_id, page_size))
+        .filter(move |channel| future::ready(is_watched_channel(&watch_channels_ref, channel)))
+        .map(move |channel| {
+            debug!("Watching channel {}", &channel.name);
+
+            count_failures(
+                claim_failures.clone(),
+                claim_api_ref.try_stream_claims_by_channel_id(channel.id, page_size),
+            )
+        })
+        .flatten()
+        .boxed()
+}
+
+async fn feed_claims<A: LbryApi>(
+    api: &A,
+    claim_feeds: &ClaimFeeds,
+    failures: &AtomicUsize,
+) -> Vec<(Claim, Account)> {
+    let mut claims = Vec::new();
+
+    for url in claim_feeds.urls() {
+        let items = match claim_feeds.fetch(url).await {
+            Ok(items) => items,
+            Err(err) => {
+                error!("Unable to fetch claim feed {}: {}", url, err);
+
+                failures.fetch_add(1, Ordering::SeqCst);
+
+                continue;
+            }
+        };
+
+        debug!("Found {} claims in feed {}", items.len(), url);
+
+        for item in items {
+            let claim = match item.claim() {
+                Some(claim) => Some(claim),
+                None => api
+                    .resolve_claims(vec![item.url.to_string()])
+                    .await
+                    .map_err(|err| {
+                        debug!("Unable to resolve feed claim {}: {}", &item.url, err);
+                    })
+                    .ok()
+                    .and_then(|resolved| resolved.into_iter().next())
+                    .and_then(|(claim_id, metadata)| {
+                        item.url.stream.as_ref().map(|stream| Claim {
+                            id: claim_id,
+                            name: stream.name.clone(),
+                            timestamp: item.timestamp,
+                            metadata,
+                        })
+                    }),
+            };
+
+            match claim {
+                Some(claim) => claims.push((claim, item.account())),
+                None => warn!("Skipping feed claim {} without a full claim id", &item.url),
+            }
+        }
+    }
+
+    claims
+}
+
+fn all_comments<A: LbryApi + Send + Sync + 'static>(
+    comment_sources: CommentSources<A>,
+    watch_channels_ref: Arc<Vec<String>>,
+    feed_claims: Vec<(Claim, Account)>,
+    claim_concurrency: usize,
+) -> impl Stream<Item = (Account, Claim, Comment)> {
+    let api_ref = comment_sources.api.clone();
+    let page_size = comment_sources.page_size;
+    let failures = comment_sources.failures.clone();
+
+    let claim_api_ref = api_ref.clone();
+    let claim_failures = failures.clone();
+
+    let buffer = num_cpus::get();
+    let seen_claims = Mutex::new(HashSet::new());
+
+    let account_claims = count_failures(failures, api_ref.try_stream_accounts(page_size))
+        .map(move |account| {
+            account_claims(
+                claim_api_ref.clone(),
+                watch_channels_ref.clone(),
+                account.id.clone(),
+                page_size,
+                claim_failures.clone(),
+            )
+            .zip(stream::repeat(account.clone()))
+        })
+        .flatten();
+
+    let claim_comments = stream::iter(feed_claims)
+        .chain(account_claims)
+        .filter(move |(claim, _)| {
+            future::ready(
+                seen_claims
+                    .lock()
+                    .expect("Unable to get lock")
+                    .insert(claim.id.clone()),
+            )
+        })
+        .map(|res| async { res })
+        .buffer_unordered(buffer)
+        .map(move |(claim, account)| {
+            claim_comments(&comment_sources, claim.id.clone())
+                .zip(stream::repeat((claim, account).clone()))
+                .map(|(comment, (claim, account))| (account, claim, comment))
+        });
+
+    interleave_streams(claim_comments, claim_concurrency)
+        .map(|res| async { res })
+        .buffer_unordered(buffer)
+}
+
+fn all_channel_comments<A: LbryApi + Send + Sync + 'static>(
+    comment_sources: CommentSources<A>,
+    channel_urls_ref: Arc<Vec<String>>,
+    claim_concurrency: usize,
+) -> impl Stream<Item = (Account, Claim, Comment)> {
+    let api_ref = comment_sources.api.clone();
+    let page_size = comment_sources.page_size;
+    let failures = comment_sources.failures.clone();
+
+    let claim_api_ref = api_ref.clone();
+    let claim_failures = failures.clone();
+
+    let buffer = num_cpus::get();
+
+    let claim_comments = stream::iter(channel_urls_ref.to_vec())
+        .then(move |channel_url| {
+            let failures = failures.clone();
+
+            api_ref.resolve_channel(&channel_url).map(move |res| {
+                res.map_err(|err| {
+                    error!("Unable to resolve channel {}: {}", channel_url, err);
+
+                    failures.fetch_add(1, Ordering::SeqCst);
+                })
+                .ok()
+            })
+        })
+        .filter_map(|res| async { res })
+        .map(move |channel| {
+            let account = Account {
+                id: channel.id.clone(),
+                name: channel.name,
+                is_default: false,
+            };
+
+            count_failures(
+                claim_failures.clone(),
+                claim_api_ref.try_stream_claims_by_channel_id(channel.id, page_size),
+            )
+            .zip(stream::repeat(account))
+        })
+        .flatten()
+        .map(|res| async { res })
+        .buffer_unordered(buffer)
+        .map(move |(claim, account)| {
+            claim_comments(&comment_sources, claim.id.clone())
+                .zip(stream::repeat((claim, account).clone()))
+                .map(|(comment, (claim, account))| (account, claim, comment))
+        });
+
+    interleave_streams(claim_comments, claim_concurrency)
+        .map(|res| async { res })
+        .buffer_unordered(buffer)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum HiddenComments {
+    Fetch,
+    Ignore,
+    Flag,
+}
+
+impl HiddenComments {
+    fn visibility(&self) -> CommentVisibility {
+        match self {
+            Self::Ignore => CommentVisibility::Visible,
+            Self::Fetch | Self::Flag => CommentVisibility::All,
+        }
+    }
+}
+
+impl FromStr for HiddenComments {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "fetch" => Ok(Self::Fetch),
+            "ignore" => Ok(Self::Ignore),
+            "flag" => Ok(Self::Flag),
+            _ => Err(format!("Unknown hidden comments mode {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SignatureCheck {
+    Off,
+    Flag,
+    Drop,
+}
+
+impl SignatureCheck {
+    fn drops(&self, comment: &Comment) -> bool {
+        *self == Self::Drop && !comment.has_valid_signature()
+    }
+}
+
+impl FromStr for SignatureCheck {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "off" => Ok(Self::Off),
+            "flag" => Ok(Self::Flag),
+            "drop" => Ok(Self::Drop),
+            _ => Err(format!("Unknown signature check mode {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IncrementalComments {
+    Off,
+    Timestamp,
+    Known,
+}
+
+impl FromStr for IncrementalComments {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "false" => Ok(Self::Off),
+            "true" | "timestamp" => Ok(Self::Timestamp),
+            "known" => Ok(Self::Known),
+            _ => Err(format!("Unknown incremental comments mode {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NotifyMode {
+    Instant,
+    Digest,
+    Away(NaiveDateTime),
+}
+
+impl FromStr for NotifyMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "instant" => Ok(Self::Instant),
+            "digest" => Ok(Self::Digest),
+            _ => Err(format!("Unknown notify mode {}", value)),
+        }
+    }
+}
+
+struct Context {
+    api: Arc<Api>,
+    commentron: Arc<Commentron>,
+    comment_backend: Option<CommentBackend>,
+    claim_feeds: Arc<ClaimFeeds>,
+    view_counts: Option<Arc<ViewCounts>>,
+    verify_links: bool,
+    incremental_comments: IncrementalComments,
+    hidden_comments: HiddenComments,
+    signature_check: SignatureCheck,
+    metrics: Arc<MetricsPusher>,
+    otlp: Option<Arc<OtlpExporter>>,
+    spans: SpanCollector,
+    storage: Arc<Storage>,
+    #[cfg(feature = "email")]
+    emails: Arc<Emails>,
+    #[cfg(feature = "email")]
+    mailer: Arc<Mutex<SmtpTransport>>,
+    exporter: Arc<Exporter>,
+    webhooks: Arc<Webhooks>,
+    throttle: Arc<Mutex<Throttle>>,
+    template_split: Arc<Option<TemplateSplit>>,
+    milestones: Arc<Milestones>,
+    channel_urls: Arc<Vec<String>>,
+    monitor_urls: Arc<Vec<String>>,
+    watch_channels: Arc<Vec<String>>,
+    monitor_notify: bool,
+    notify_max_age: Option<Duration>,
+    run_deadline: Option<Duration>,
+    notify_mode: NotifyMode,
+    downtime_threshold: Option<Duration>,
+    notify_ping: bool,
+    notify_sources: Arc<Vec<String>>,
+    source_templates: Arc<HashMap<String, Template>>,
+    digest_order: DigestOrder,
+    digest_max_bytes: usize,
+    retention_days: Option<i32>,
+    page_size: Arc<usize>,
+    claim_concurrency: usize,
+    preflight: bool,
+    preflight_notify: bool,
+    sdk_fallback: bool,
+    sdk_unready: Arc<AtomicBool>,
+    disk_guard: Option<DiskGuard>,
+    disk_low: Arc<AtomicBool>,
+    own_channel_ids: Arc<Mutex<HashSet<String>>>,
+    translator: Option<Arc<Translator>>,
+}
+
+async fn verify_canonical_url(context: &Context, claim: &Claim) -> Option<String> {
+    let canonical_url = claim.metadata.canonical_url.clone()?;
+    let is_resolved = context
+        .api
+        .resolve_claims(vec![canonical_url.clone()])
+        .await
+        .map(|resolved| resolved.contains_key(&claim.id))
+        .unwrap_or_else(|err| {
+            debug!("Unable to verify link {}: {}", &canonical_url, err);
+
+            true
+        });
+
+    if is_resolved {
+        Some(canonical_url)
+    } else {
+        warn!(
+            "Link {} does not resolve to claim {}, using the claim page instead",
+            &canonical_url, &claim.id
+        );
+
+        None
+    }
+}
+
+async fn resolve_claim_metadata(
+    context: &Context,
+    claim_metadata: &Mutex<HashMap<String, ClaimMetadata>>,
+    mut claim: Claim,
+) -> Claim {
+    let cached_metadata = claim_metadata
+        .lock()
+        .expect("Unable to get lock")
+        .get(&claim.id)
+        .cloned();
+
+    if let Some(metadata) = cached_metadata {
+        claim.metadata = metadata;
+
+        return claim;
+    }
+
+    claim.metadata = context
+        .api
+        .resolve_claims(vec![claim.permanent_url()])
+        .await
+        .map_err(|err| {
+            error!("Unable to resolve claim {}: {}", &claim.id, err);
+        })
+        .ok()
+        .and_then(|mut resolved| resolved.remove(&claim.id))
+        .unwrap_or_default();
+
+    if context.verify_links {
+        claim.metadata.canonical_url = verify_canonical_url(context, &claim).await;
+    }
+
+    if let Some(view_counts) = &context.view_counts {
+        claim.metadata.view_count = view_counts
+            .fetch(vec![claim.id.clone()])
+            .await
+            .map_err(|err| {
+                error!("Unable to fetch views of claim {}: {}", &claim.id, err);
+            })
+            .ok()
+            .and_then(|mut counts| counts.remove(&claim.id));
+
+        context
+            .storage
+            .save_claim(ClaimEntity::new(&claim, Utc::now().naive_utc()))
+            .expect("Could not save claim");
+    }
+
+    claim_metadata
+        .lock()
+        .expect("Unable to get lock")
+        .insert(claim.id.clone(), claim.metadata.clone());
+
+    claim
+}
+
+async fn celebrate_milestone(context: &Context, comment_entity: &CommentEntity) {
+    let milestones = &context.milestones;
+
+    if milestones.is_empty() {
+        return;
+    }
+
+    let comment_count = context
+        .storage
+        .count_comments_by_commenter_id(comment_entity.commenter_id.clone())
+        .expect("Could not count comments");
+
+    let milestone = match milestones.reached(comment_count) {
+        Some(milestone) => milestone,
+        None => return,
+    };
+
+    let is_new_milestone = context
+        .storage
+        .save_commenter_milestone(CommenterMilestone {
+            commenter_id: comment_entity.commenter_id.clone(),
+            milestone,
+            reached_at: Utc::now().naive_utc(),
+        })
+        .expect("Could not save milestone");
+
+    if !is_new_milestone {
+        return;
+    }
+
+    info!(
+        "Commenter {} reached {} comments",
+        &comment_entity.commenter_name, milestone
+    );
+
+    if milestones.has_action(MilestoneAction::Email) {
+        mail!(context, milestone_emails(comment_entity, milestone));
+    }
+
+    if milestones.has_action(MilestoneAction::Reply) {
+        match &milestones.reply_channel {
+            Some(reply_channel) => {
+                let claim_ids = vec![comment_entity.claim_id.clone()];
+                let ownership = context
+                    .api
+                    .verify_ownership(&claim_ids, Some(reply_channel))
+                    .await
+                    .map_err(|err| err.to_string());
+
+                let reply = match ownership {
+                    Ok(_) => context
+                        .api
+                        .create_comment(
+                            milestones.render_reply(comment_entity, milestone),
+                            comment_entity.claim_id.clone(),
+                            Some(comment_entity.id.clone()),
+                            reply_channel.clone(),
+                        )
+                        .await
+                        .map_err(|err| err.to_string()),
+                    Err(err) => Err(err),
+                };
+
+                if let Err(err) = reply {
+                    error!("Unable to reply to {}: {}", &comment_entity.id, err);
+                }
+            }
+            None => error!("MILESTONE_REPLY_CHANNEL is required to reply"),
+        }
+    }
+
+    if milestones.has_action(MilestoneAction::Webhook) {
+        let payload = json!({
+            "event": "milestone",
+            "milestone": milestone,
+            "commenter_id": comment_entity.commenter_id,
+            "commenter_name": comment_entity.commenter_name,
+            "comment_id": comment_entity.id,
+            "claim_id": comment_entity.claim_id,
+        });
+
+        for url in &milestones.webhook_urls {
+            if let Err(err) = context.webhooks.post_json(url, &payload).await {
+                error!("Unable to send milestone webhook to {}: {}", url, err);
+            }
+        }
+    }
+}
+
+fn save_comment_cursors(storage: &Storage, cursors: &CommentCursors) {
+    for cursor in cursors.take_updates() {
+        if cursor.is_complete() {
+            storage
+                .delete_comment_cursor(cursor.claim_id)
+                .expect("Could not delete comment cursor");
+        } else {
+            storage
+                .save_comment_cursor(cursor)
+                .expect("Could not save comment cursor");
+        }
+    }
+}
+
+fn save_new_comments<'a>(
+    comments: impl Stream<Item = (Account, Claim, Comment)> + 'a,
+    context: &'a Context,
+    store: &'a dyn CommentStore,
+    retention: &'a RetentionPolicy,
+    claim_metadata: &'a Mutex<HashMap<String, ClaimMetadata>>,
+    cursors: &'a CommentCursors,
+) -> impl Stream<Item = CommentEntity> + 'a {
+    let now = Utc::now().naive_utc();
+
+    comments.filter_map(move |(account, claim, comment)| async move {
+        let comment_id = comment.id.to_owned();
+
+        save_comment_cursors(&context.storage, cursors);
+
+        if retention.is_expired(&comment.claim_id, comment.timestamp.naive_utc(), now) {
+            debug!("Skipping comment {} past its retention", &comment_id);
+
+            None
+        } else if comment.is_hidden && context.hidden_comments == HiddenComments::Ignore {
+            debug!("Skipping hidden comment {}", &comment_id);
+
+            None
+        } else if context.signature_check.drops(&comment) {
+            warn!(
+                "Skipping comment {} with an unverified signature from {}",
+                &comment_id, &comment.commenter_name
+            );
+
+            None
+        } else if let Some(comment_entity) = store.get_comment_by_id(comment_id.clone()) {
+            if &comment_entity.comment != &comment.comment {
+                info!("Comment {} is updated", &comment_id);
+
+                store
+                    .delete_comment_by_id(comment_id)
+                    .expect("Could not delete comment");
+
+                let claim = resolve_claim_metadata(context, claim_metadata, claim).await;
+                let new_comment_entity = store
+                    .save_comment_with_deliveries(
+                        account,
+                        claim,
+                        comment,
+                        delivery_targets(context),
+                    )
+                    .expect("Could not save comment");
+
+                Some(new_comment_entity)
+            } else {
+                None
+            }
+        } else {
+            info!("Logging new comment {}", &comment_id);
+
+            let claim = resolve_claim_metadata(context, claim_metadata, claim).await;
+            let new_comment_entity = store
+                .save_comment_with_deliveries(account, claim, comment, delivery_targets(context))
+                .expect("Could not save comment");
+
+            Some(new_comment_entity)
+        }
+    })
+}
+
+#[cfg(feature = "email")]
+fn email_delivery_targets(context: &Context) -> Vec<String> {
+    context
+        .emails
+        .recipient_addresses()
+        .iter()
+        .map(|address| email_delivery_target(address))
+        .collect()
+}
+
+#[cfg(not(feature = "email"))]
+fn email_delivery_targets(_context: &Context) -> Vec<String> {
+    vec![]
+}
+
+fn delivery_targets(context: &Context) -> Vec<String> {
+    let mut targets = email_delivery_targets(context);
+
+    targets.extend(
+        context
+            .webhooks
+            .webhooks()
+            .into_iter()
+            .map(|webhook| webhook.url),
+    );
+
+    targets
+}
+
+fn record_delivery(context: &Context, comment_id: &str, target: &str, result: Result<(), String>) {
+    let delivery = context
+        .storage
+        .record_delivery_attempt(
+            comment_id.to_string(),
+            target.to_string(),
+            result.err(),
+            DELIVERY_MAX_ATTEMPTS,
+        )
+        .expect("Could not record delivery");
+
+    if let Some(delivery) = delivery {
+        if delivery.status == DELIVERY_DEAD {
+            error!(
+                "Giving up on delivering comment {} to {} after {} attempts",
+                comment_id, target, delivery.attempts
+            );
+        }
+    }
+}
+
+fn should_deliver(context: &Context, comment_entity: &CommentEntity, deliver: bool) -> bool {
+    if !deliver {
+        context
+            .storage
+            .skip_deliveries(comment_entity.id.clone())
+            .expect("Could not skip deliveries");
+    }
+
+    deliver
+}
+
+#[cfg(feature = "email")]
+fn try_send_email(mailer: &Mutex<SmtpTransport>, email: SendableEmail) -> Result<(), String> {
+    mailer
+        .lock()
+        .expect("Unable to get lock")
+        .send(email)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "email")]
+fn send_email(mailer: &Mutex<SmtpTransport>, email: SendableEmail) {
+    try_send_email(mailer, email).expect("Unable to send mail");
+}
+
+#[cfg(feature = "email")]
+fn send_emails<E: Into<SendableEmail>>(mailer: &Mutex<SmtpTransport>, emails: Vec<E>) {
+    emails
+        .into_iter()
+        .for_each(|email| send_email(mailer, email.into()));
+}
+
+#[cfg(feature = "email")]
+fn is_email_delivered(
+    context: &Context,
+    comment_entities: &[CommentEntity],
+    targets: &[String],
+) -> bool {
+    !comment_entities.is_empty()
+        && comment_entities.iter().all(|comment_entity| {
+            targets.iter().all(|target| {
+                context
+                    .storage
+                    .get_delivery(comment_entity.id.clone(), target.clone())
+                    .expect("Could not get delivery")
+                    .map(|delivery| delivery.status != DELIVERY_PENDING)
+                    .unwrap_or(false)
+            })
+        })
+}
+
+#[cfg(feature = "email")]
+fn send_comment_emails<E: Into<SendableEmail>>(
+    context: &Context,
+    emails: Vec<E>,
+    comment_entities: &[CommentEntity],
+) -> Result<(), String> {
+    let results = emails
+        .into_iter()
+        .map(|email| {
+            let email: SendableEmail = email.into();
+            let targets = email
+                .envelope()
+                .to()
+                .iter()
+                .map(|address| email_delivery_target(address.as_ref()))
+                .collect::<Vec<_>>();
+
+            if is_email_delivered(context, comment_entities, &targets) {
+                return Ok(());
+            }
+
+            let result = try_send_email(&context.mailer, email);
+
+            if let Err(err) = &result {
+                error!("Unable to send mail to {}: {}", targets.join(", "), err);
+            }
+
+            for comment_entity in comment_entities {
+                for target in &targets {
+                    record_delivery(context, &comment_entity.id, target, result.clone());
+                }
+            }
+
+            result
+        })
+        .collect::<Vec<_>>();
+    let result = results.into_iter().collect::<Result<(), String>>();
+
+    for comment_entity in comment_entities {
+        record_delivery(context, &comment_entity.id, DELIVERY_EMAIL, result.clone());
+    }
+
+    result
+}
+
+fn own_reply_parent(context: &Context, comment_entity: &CommentEntity) -> Option<CommentEntity> {
+    let parent_id = comment_entity.parent_id.clone()?;
+    let own_channel_ids = context.own_channel_ids.lock().expect("Unable to get lock");
+
+    context
+        .storage
+        .get_comment_by_id(parent_id)
+        .filter(|parent| own_channel_ids.contains(&parent.commenter_id))
+}
+
+async fn translate_comment(
+    context: &Context,
+    comment_entity: &CommentEntity,
+) -> Option<Translation> {
+    let translator = context.translator.as_ref()?;
+
+    match translator.translate(comment_entity.comment.clone()).await {
+        Ok(translation) => translation,
+        Err(err) => {
+            error!(
+                "Unable to translate comment {}: {}",
+                &comment_entity.id, err
+            );
+
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "email"))]
+async fn send_notification(
+    _context: &Context,
+    _comment_entity: CommentEntity,
+) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(feature = "email")]
+async fn send_notification(context: &Context, comment_entity: CommentEntity) -> Result<(), String> {
+    if let Some(template) = context.source_templates.get(&comment_entity.source) {
+        info!(
+            "Sending {} email for {}",
+            &comment_entity.source, &comment_entity.commenter_name
+        );
+
+        return send_comment_emails(
+            context,
+            vec![context
+                .emails
+                .source_notification_email(template, &comment_entity)],
+            &[comment_entity],
+        );
+    }
+
+    match &*context.template_split {
+        Some(template_split) => {
+            let (variant, template) = template_split.choose();
+
+            info!(
+                "Sending variant {} email for {}",
+                variant, &comment_entity.commenter_name
+            );
+
+            send_comment_emails(
+                context,
+                vec![context.emails.template_notification_email(
+                    template,
+                    variant,
+                    &comment_entity,
+                )],
+                slice::from_ref(&comment_entity),
+            )?;
+
+            context
+                .storage
+                .save_notification_variant(NotificationVariant {
+                    comment_id: comment_entity.id,
+                    variant: variant.to_string(),
+                    sent_at: Utc::now().naive_utc(),
+                })
+                .expect("Could not save notification variant");
+
+            Ok(())
+        }
+        None => {
+            info!("Sending email for {}", &comment_entity.commenter_name);
+
+            let claim = context
+                .storage
+                .get_claim(comment_entity.claim_id.clone())
+                .expect("Could not get claim");
+            let reply_to = own_reply_parent(context, &comment_entity);
+            let translation = translate_comment(context, &comment_entity).await;
+
+            let emails = context.emails.notification_emails(
+                &comment_entity,
+                claim.as_ref(),
+                reply_to.as_ref(),
+                translation.as_ref(),
+            );
+
+            send_comment_emails(context, emails, &[comment_entity])
+        }
+    }
+}
+
+async fn send_webhook(context: &Context, webhook: &Webhook, comment_entity: &CommentEntity) {
+    info!(
+        "Sending v{} webhook for {} to {}",
+        webhook.version.number(),
+        &comment_entity.id,
+        &webhook.url
+    );
+
+    let result = context
+        .webhooks
+        .send(webhook, comment_entity)
+        .await
+        .map_err(|err| err.to_string());
+
+    if let Err(err) = &result {
+        error!("Unable to send webhook to {}: {}", &webhook.url, err);
+    }
+
+    record_delivery(context, &comment_entity.id, &webhook.url, result);
+}
+
+async fn notify_webhooks(context: &Context, comment_entity: &CommentEntity) {
+    for webhook in context.webhooks.webhooks() {
+        send_webhook(context, &webhook, comment_entity).await;
+    }
+}
+
+fn away_since(context: &Context) -> Option<NaiveDateTime> {
+    let threshold = context.downtime_threshold?;
+    let last_run = context
+        .storage
+        .get_last_sync_run("watcher".to_string(), "ok".to_string())
+        .expect("Could not get last sync run")?;
+
+    if Utc::now().naive_utc() - last_run.finished_at > threshold {
+        Some(last_run.finished_at)
+    } else {
+        None
+    }
+}
+
+fn is_catch_up(context: &Context, comment_entity: &CommentEntity) -> bool {
+    context
+        .notify_max_age
+        .map(|max_age| comment_entity.timestamp < Utc::now().naive_utc() - max_age)
+        .unwrap_or(false)
+}
+
+async fn deliver_notifications(
+    context: &Context,
+    notify_mode: NotifyMode,
+    comments: impl Stream<Item = CommentEntity>,
+) {
+    match notify_mode {
+        NotifyMode::Instant => {
+            let claim_locks = ClaimLocks::new();
+            let catch_up_comments = Mutex::new(Vec::new());
+
+            comments
+                .for_each_concurrent(None, |comment_entity| {
+                    let claim_locks = claim_locks.clone();
+                    let claim_id = comment_entity.claim_id.clone();
+                    let catch_up_comments = &catch_up_comments;
+
+                    async move {
+                        claim_locks
+                            .run_in_order(&claim_id, async {
+                                notify_webhooks(context, &comment_entity).await;
+
+                                if is_catch_up(context, &comment_entity) {
+                                    catch_up_comments
+                                        .lock()
+                                        .expect("Unable to get lock")
+                                        .push(comment_entity);
+
+                                    return;
+                                }
+
+                                let is_allowed = context
+                                    .throttle
+                                    .lock()
+                                    .expect("Unable to get lock")
+                                    .allow(&comment_entity);
+
+                                if is_allowed {
+                                    send_notification(context, comment_entity).await.ok();
+                                } else {
+                                    info!(
+                                        "Throttling notification for {}",
+                                        &comment_entity.commenter_name
+                                    );
+
+                                    for target in email_delivery_targets(context) {
+                                        record_delivery(
+                                            context,
+                                            &comment_entity.id,
+                                            &target,
+                                            Ok(()),
+                                        );
+                                    }
+                                }
+                            })
+                            .await;
+                    }
+                })
+                .await;
+
+            notify_throttled_commenters(context);
+
+            let catch_up_comments = catch_up_comments.into_inner().expect("Unable to get lock");
+
+            if !catch_up_comments.is_empty() {
+                info!(
+                    "Sending catch-up digest email for {} older comments",
+                    catch_up_comments.len()
+                );
+
+                mail_comments!(
+                    context,
+                    catch_up_emails(
+                        catch_up_comments.clone(),
+                        context.digest_order,
+                        context.digest_max_bytes
+                    ),
+                    &catch_up_comments
+                )
+                .ok();
+            }
+        }
+        NotifyMode::Digest => {
+            let comment_entities = comments
+                .then(|comment_entity| async {
+                    notify_webhooks(context, &comment_entity).await;
+
+                    comment_entity
+                })
+                .collect::<Vec<CommentEntity>>()
+                .await;
+
+            if !comment_entities.is_empty() {
+                info!(
+                    "Sending digest email for {} comments",
+                    comment_entities.len()
+                );
+
+                mail_comments!(
+                    context,
+                    digest_emails(
+                        comment_entities.clone(),
+                        context.digest_order,
+                        context.digest_max_bytes
+                    ),
+                    &comment_entities
+                )
+                .ok();
+            }
+        }
+        NotifyMode::Away(since) => {
+            let comment_entities = comments
+                .then(|comment_entity| async {
+                    notify_webhooks(context, &comment_entity).await;
+
+                    comment_entity
+                })
+                .collect::<Vec<CommentEntity>>()
+                .await;
+
+            if !comment_entities.is_empty() {
+                info!(
+                    "Sending away summary email for {} comments",
+                    comment_entities.len()
+                );
+
+                mail_comments!(
+                    context,
+                    away_emails(&comment_entities, since),
+                    &comment_entities
+                )
+                .ok();
+            }
+        }
+    }
+}
+
+fn give_up_delivery(context: &Context, delivery: &Delivery, reason: &str) {
+    error!(
+        "Giving up on delivering comment {} to {}: {}",
+        &delivery.comment_id, &delivery.target, reason
+    );
+
+    context
+        .storage
+        .record_delivery_attempt(
+            delivery.comment_id.clone(),
+            delivery.target.clone(),
+            Some(reason.to_string()),
+            0,
+        )
+        .expect("Could not record delivery");
+}
+
+async fn resume_delivery(context: &Context, delivery: &Delivery, webhooks: &[Webhook]) {
+    let comment_entity = match context
+        .storage
+        .get_comment_by_id(delivery.comment_id.clone())
+    {
+        Some(comment_entity) => comment_entity,
+        None => return give_up_delivery(context, delivery, "Comment is no longer stored"),
+    };
+
+    if delivery.is_email() {
+        send_notification(context, comment_entity).await.ok();
+
+        return;
+    }
+
+    match webhooks
+        .iter()
+        .find(|webhook| webhook.url == delivery.target)
+    {
+        Some(webhook) => send_webhook(context, webhook, &comment_entity).await,
+        None => give_up_delivery(context, delivery, "Webhook is no longer configured"),
+    }
+}
+
+fn count_deliveries(context: &Context, status: &str) -> usize {
+    context
+        .storage
+        .get_deliveries(status.to_string())
+        .expect("Could not get deliveries")
+        .len()
+}
+
+fn recover_deliveries(context: &Context) {
+    let pending_deliveries = context
+        .storage
+        .get_deliveries(DELIVERY_PENDING.to_string())
+        .expect("Could not get deliveries");
+    let dead_before = count_deliveries(context, DELIVERY_DEAD);
+
+    if pending_deliveries.is_empty() {
+        info!(
+            "No pending deliveries to recover, {} dead letters",
+            dead_before
+        );
+
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .threaded_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    refresh_notification_channels(context);
+
+    info!("Recovering {} pending deliveries", pending_deliveries.len());
+
+    let total = pending_deliveries.len();
+    let webhooks = context.webhooks.webhooks();
+
+    rt.block_on(async {
+        let mut resumed_emails = HashSet::new();
+
+        for delivery in pending_deliveries {
+            if delivery.is_email() && !resumed_emails.insert(delivery.comment_id.clone()) {
+                continue;
+            }
+
+            info!(
+                "Resuming delivery of comment {} to {}",
+                &delivery.comment_id, &delivery.target
+            );
+
+            resume_delivery(context, &delivery, &webhooks).await;
+        }
+    });
+
+    let pending = count_deliveries(context, DELIVERY_PENDING);
+    let dead = count_deliveries(context, DELIVERY_DEAD);
+
+    info!(
+        "Recovered {} of {} pending deliveries, {} still pending, {} newly dead, {} dead letters",
+        total.saturating_sub(pending + dead - dead_before),
+        total,
+        pending,
+        dead - dead_before,
+        dead
+    );
+}
+
+fn notify_throttled_commenters(context: &Context) {
+    let throttled_commenters = context
+        .throttle
+        .lock()
+        .expect("Unable to get lock")
+        .subside(Utc::now().naive_utc());
+
+    throttled_commenters.into_iter().for_each(|throttled| {
+        info!(
+            "Sending email for {} throttled comments from {}",
+            throttled.comments.len(),
+            &throttled.commenter_name
+        );
+
+        mail!(context, throttled_emails(throttled, context.digest_order));
+    });
+}
+
+fn load_saved_searches(storage: &Storage) -> Vec<(SavedSearch, SearchQuery)> {
+    storage
+        .get_saved_searches()
+        .expect("Could not get saved searches")
+        .into_iter()
+        .filter_map(|search| match search.query.parse::<SearchQuery>() {
+            Ok(query) => Some((search, query)),
+            Err(err) => {
+                error!("Invalid saved search {}: {}", &search.name, err);
+
+                None
+            }
+        })
+        .collect::<Vec<(SavedSearch, SearchQuery)>>()
+}
+
+fn notify_saved_searches(
+    context: &Context,
+    searches: &[(SavedSearch, SearchQuery)],
+    comment_entity: &CommentEntity,
+) {
+    searches
+        .iter()
+        .filter(|(_, query)| query.matches(comment_entity))
+        .for_each(|(search, _)| {
+            info!(
+                "Sending saved search {} email for {}",
+                &search.name, &comment_entity.commenter_name
+            );
+
+            mail!(context, search_emails(search, comment_entity));
+        });
+}
+
+fn load_active_snoozes(storage: &Storage) -> Vec<Snooze> {
+    let now = Utc::now().naive_utc();
+
+    let expired_count = storage
+        .delete_expired_snoozes(now)
+        .expect("Could not delete expired snoozes");
+
+    if expired_count > 0 {
+        info!("Removed {} expired snoozes", expired_count);
+    }
+
+    storage
+        .get_active_snoozes(now)
+        .expect("Could not get active snoozes")
+}
+
+fn is_source_enabled(notify_sources: &[String], comment_entity: &CommentEntity) -> bool {
+    let enabled = notify_sources.is_empty() || notify_sources.contains(&comment_entity.source);
+
+    if !enabled {
+        info!(
+            "Skipping notification for {} from disabled source {}",
+            &comment_entity.id, &comment_entity.source
+        );
+    }
+
+    enabled
+}
+
+fn is_snoozed(snoozes: &[Snooze], comment_entity: &CommentEntity) -> bool {
+    let snoozed = snoozes.iter().any(|snooze| snooze.matches(comment_entity));
+
+    if snoozed {
+        info!("Skipping notification for snoozed {}", &comment_entity.id);
+    }
+
+    snoozed
+}
+
+fn load_retention_policy(context: &Context) -> RetentionPolicy {
+    let settings = context
+        .storage
+        .get_claim_settings()
+        .expect("Could not get claim settings");
+
+    RetentionPolicy::new(context.retention_days, &settings)
+}
+
+struct RunRules {
+    retention: RetentionPolicy,
+    snoozes: Vec<Snooze>,
+    searches: Vec<(SavedSearch, SearchQuery)>,
+}
+
+#[cfg(feature = "email")]
+async fn refresh_collections(context: &Context) {
+    let collections = context
+        .api
+        .try_stream_collections(*context.page_size)
+        .try_collect::<Vec<Collection>>()
+        .await;
+
+    match collections {
+        Ok(collections) => {
+            debug!("Grouping digests by {} collections", collections.len());
+
+            context
+                .emails
+                .set_collections(ClaimCollections::new(&collections));
+        }
+        Err(err) => error!("Unable to list collections: {}", err),
+    }
+}
+
+async fn refresh_own_channels(context: &Context) {
+    let accounts = context
+        .api
+        .stream_accounts(*context.page_size)
+        .collect::<Vec<Account>>()
+        .await;
+    let mut own_channel_ids = HashSet::new();
+
+    for account in accounts {
+        let channels = context
+            .api
+            .try_stream_channels(account.id.clone(), *context.page_size)
+            .try_collect::<Vec<Channel>>()
+            .await;
+
+        match channels {
+            Ok(channels) => own_channel_ids.extend(channels.into_iter().map(|channel| channel.id)),
+            Err(err) => error!(
+                "Unable to list channels of account {}: {}",
+                &account.id, err
+            ),
+        }
+    }
+
+    debug!("Marking replies to {} own channels", own_channel_ids.len());
+
+    *context.own_channel_ids.lock().expect("Unable to get lock") = own_channel_ids;
+}
+
+fn refresh_notification_channels(context: &Context) {
+    let mut recipients = Vec::new();
+    let mut webhooks = Vec::new();
+
+    context
+        .storage
+        .get_notification_channels()
+        .expect("Could not get notification channels")
+        .into_iter()
+        .for_each(|channel| match channel.target() {
+            Ok(ChannelTarget::Email(recipient)) => recipients.push(recipient),
+            Ok(ChannelTarget::Webhook(webhook)) => {
+                if let (Some(token_env), None) = (&channel.token_env, &webhook.bearer_token) {
+                    warn!(
+                        "Sending webhook channel {} without a token since {} is not set",
+                        &channel.name, token_env
+                    );
+                }
+
+                webhooks.push(webhook);
+            }
+            Err(err) => error!("Skipping notification channel {}: {}", &channel.name, err),
+        });
+
+    #[cfg(feature = "email")]
+    context.emails.set_channel_recipients(recipients);
+    #[cfg(not(feature = "email"))]
+    recipients.iter().for_each(|recipient: &Recipient| {
+        warn!(
+            "Skipping email channel to {} without the email feature",
+            &recipient.address
+        )
+    });
+    context.webhooks.set_channel_webhooks(webhooks);
+}
+
+fn load_run_rules(context: &Context) -> RunRules {
+    RunRules {
+        retention: load_retention_policy(context),
+        snoozes: load_active_snoozes(&context.storage),
+        searches: load_saved_searches(&context.storage),
+    }
+}
+
+async fn notify_comments(
+    context: &Context,
+    notify_mode: NotifyMode,
+    comments: impl Stream<Item = (Account, Claim, Comment)>,
+    rules: &RunRules,
+    claim_metadata: &Mutex<HashMap<String, ClaimMetadata>>,
+    cursors: &CommentCursors,
+) -> usize {
+    let notified = AtomicUsize::new(0);
+    let new_comments = save_new_comments(
+        comments,
+        context,
+        &*context.storage,
+        &rules.retention,
+        claim_metadata,
+        cursors,
+    )
+    .filter(|comment_entity| {
+        future::ready(should_deliver(
+            context,
+            comment_entity,
+            !is_snoozed(&rules.snoozes, comment_entity),
+        ))
+    })
+    .filter(|comment_entity| {
+        future::ready(should_deliver(
+            context,
+            comment_entity,
+            is_source_enabled(&context.notify_sources, comment_entity),
+        ))
+    })
+    .inspect(|comment_entity| {
+        if context.notify_ping
+            && !matches!(notify_mode, NotifyMode::Away(_))
+            && !is_catch_up(context, comment_entity)
+        {
+            info!("Sending ping email for {}", &comment_entity.id);
+
+            mail!(context, ping_emails(comment_entity));
+        }
+    })
+    .inspect(|comment_entity| notify_saved_searches(context, &rules.searches, comment_entity))
+    .inspect(|_| {
+        notified.fetch_add(1, Ordering::SeqCst);
+    })
+    .then(|comment_entity| async {
+        celebrate_milestone(context, &comment_entity).await;
+
+        comment_entity
+    });
+
+    deliver_notifications(context, notify_mode, new_comments).await;
+
+    notified.into_inner()
+}
+
+async fn is_sdk_ready(context: &Context) -> bool {
+    if !context.preflight && !context.sdk_fallback {
+        return true;
+    }
+
+    let started_at = Utc::now().naive_utc();
+    let problems = match context.api.status().await {
+        Ok(status) => status.problems(context.channel_urls.is_empty()),
+        Err(err) => vec![format!("Unable to get SDK status: {}", err)],
+    };
+
+    if problems.is_empty() {
+        if context.sdk_unready.swap(false, Ordering::SeqCst) {
+            info!("SDK is ready again");
+        }
+
+        return true;
+    }
+
+    if context.sdk_fallback {
+        problems.iter().for_each(|problem| {
+            warn!(
+                "Falling back to the comment API since the SDK is not ready: {}",
+                problem
+            )
+        });
+    } else {
+        problems
+            .iter()
+            .for_each(|problem| error!("Skipping run since the SDK is not ready: {}", problem));
+    }
+
+    if !context.sdk_unready.swap(true, Ordering::SeqCst) && context.preflight_notify {
+        info!("Sending preflight email");
+
+        mail!(context, preflight_emails(&problems));
+    }
+
+    if !context.sdk_fallback {
+        context
+            .storage
+            .save_sync_run(NewSyncRun {
+                kind: "watcher".to_string(),
+                status: "skipped".to_string(),
+                details: problems.join("; "),
+                started_at,
+                finished_at: Utc::now().naive_utc(),
+            })
+            .expect("Could not save sync run");
+    }
+
+    false
+}
+
+fn has_disk_space(context: &Context, task: &str) -> bool {
+    let problems = match &context.disk_guard {
+        Some(disk_guard) => disk_guard.problems(),
+        None => return true,
+    };
+
+    if problems.is_empty() {
+        if context.disk_low.swap(false, Ordering::SeqCst) {
+            info!("Disk space is available again");
+        }
+
+        return true;
+    }
+
+    problems
+        .iter()
+        .for_each(|problem| error!("Pausing {} since disk space is low: {}", task, problem));
+
+    if !context.disk_low.swap(true, Ordering::SeqCst) {
+        info!("Sending disk space email");
+
+        mail!(context, disk_space_emails(&problems));
+    }
+
+    false
+}
+
+fn comment_stops(
+    storage: &Storage,
+    incremental_comments: IncrementalComments,
+) -> HashMap<String, CommentStop> {
+    match incremental_comments {
+        IncrementalComments::Off => HashMap::new(),
+        IncrementalComments::Timestamp => storage
+            .get_latest_claim_comment_timestamps()
+            .expect("Could not get latest comment timestamps")
+            .into_iter()
+            .map(|(claim_id, timestamp)| {
+                (
+                    claim_id,
+                    CommentStop::Since(DateTime::from_utc(timestamp, Utc)),
+                )
+            })
+            .collect(),
+        IncrementalComments::Known => {
+            let mut known_ids: HashMap<String, HashSet<String>> = HashMap::new();
+
+            storage
+                .get_claim_comment_ids()
+                .expect("Could not get known comment ids")
+                .into_iter()
+                .for_each(|(claim_id, id)| {
+                    known_ids.entry(claim_id).or_default().insert(id);
+                });
+
+            known_ids
+                .into_iter()
+                .map(|(claim_id, ids)| (claim_id, CommentStop::Known(ids)))
+                .collect()
+        }
+    }
+}
+
+fn fallback_comments(
+    context: &Context,
+    claim_metadata: &Mutex<HashMap<String, ClaimMetadata>>,
+    cursors: CommentCursors,
+    comment_stops: Arc<HashMap<String, CommentStop>>,
+    failures: Arc<AtomicUsize>,
+) -> impl Stream<Item = (Account, Claim, Comment)> {
+    let known_claims = context
+        .storage
+        .get_known_claims()
+        .expect("Could not get known claims");
+
+    info!(
+        "Fetching comments of {} known claims from the comment API",
+        known_claims.len()
+    );
+
+    claim_metadata.lock().expect("Unable to get lock").extend(
+        known_claims
+            .iter()
+            .map(|(_, claim)| (claim.id.clone(), claim.metadata.clone())),
+    );
+
+    let comment_sources = CommentSources {
+        api: context.api.clone(),
+        commentron: context.commentron.clone(),
+        comment_backend: CommentBackend::Commentron,
+        page_size: *context.page_size,
+        cursors,
+        comment_stops,
+        failures,
+    };
+
+    let claim_comments = stream::iter(known_claims).map(move |(account, claim)| {
+        claim_comments(&comment_sources, claim.id.clone())
+            .zip(stream::repeat((account, claim)))
+            .map(|(comment, (account, claim))| (account, claim, comment))
+    });
+
+    interleave_streams(claim_comments, context.claim_concurrency)
+}
+
+fn notify_new_comments(context: &Context) {
+    if !has_disk_space(context, "comment checks") {
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .threaded_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    refresh_notification_channels(context);
+
+    rt.block_on(async {
+        let sdk_ready = is_sdk_ready(context).await;
+
+        if !sdk_ready && !context.sdk_fallback {
+            return;
+        }
+
+        if sdk_ready {
+            refresh_own_channels(context).await;
+        }
+
+        #[cfg(feature = "email")]
+        if sdk_ready && context.digest_order == DigestOrder::Collection {
+            refresh_collections(context).await;
+        }
+
+        info!("Finding new comments");
+
+        let notify_mode = match away_since(context) {
+            Some(since) => {
+                info!(
+                    "Summarizing comments since the last successful run at {}",
+                    since
+                );
+
+                NotifyMode::Away(since)
+            }
+            None => context.notify_mode,
+        };
+        let rules = load_run_rules(context);
+        let claim_metadata = Mutex::new(HashMap::new());
+        let cursors = CommentCursors::new(
+            context
+                .storage
+                .get_comment_cursors()
+                .expect("Could not get comment cursors"),
+        );
+        let comment_stops = Arc::new(comment_stops(
+            &context.storage,
+            context.incremental_comments,
+        ));
+        let failures = Arc::new(AtomicUsize::new(0));
+        let started_at = Utc::now().naive_utc();
+        let deadline = context
+            .run_deadline
+            .map(|run_deadline| time::Instant::now() + run_deadline.to_std().unwrap_or_default());
+        let expired = Arc::new(AtomicBool::new(false));
+        let channel_sources = CommentSources {
+            api: context.api.clone(),
+            commentron: context.commentron.clone(),
+            comment_backend: context
+                .comment_backend
+                .unwrap_or(CommentBackend::Commentron),
+            page_size: *context.page_size,
+            cursors: cursors.clone(),
+            comment_stops: comment_stops.clone(),
+            failures: failures.clone(),
+        };
+
+        context.spans.take();
+
+        let comments = if !sdk_ready {
+            fallback_comments(
+                context,
+                &claim_metadata,
+                cursors.clone(),
+                comment_stops.clone(),
+                failures.clone(),
+            )
+            .boxed()
+        } else if context.channel_urls.is_empty() {
+            let feed_claims = feed_claims(&*context.api, &context.claim_feeds, &failures).await;
+
+            all_comments(
+                CommentSources {
+                    api: context.api.clone(),
+                    commentron: context.commentron.clone(),
+                    comment_backend: context.comment_backend.unwrap_or(CommentBackend::Sdk),
+                    page_size: *context.page_size,
+                    cursors: cursors.clone(),
+                    comment_stops: comment_stops.clone(),
+                    failures: failures.clone(),
+                },
+                context.watch_channels.clone(),
+                feed_claims,
+                context.claim_concurrency,
+            )
+            .boxed()
+        } else {
+            all_channel_comments(
+                channel_sources.clone(),
+                context.channel_urls.clone(),
+                context.claim_concurrency,
+            )
+            .boxed()
+        };
+        let comments = until_deadline(comments, deadline, expired.clone());
+
+        let new_comments = notify_comments(
+            context,
+            notify_mode,
+            comments,
+            &rules,
+            &claim_metadata,
+            &cursors,
+        )
+        .await;
+
+        if sdk_ready && !context.monitor_urls.is_empty() && !expired.load(Ordering::SeqCst) {
+            info!("Archiving comments of monitored channels");
+
+            let monitored_comments = all_channel_comments(
+                channel_sources.clone(),
+                context.monitor_urls.clone(),
+                context.claim_concurrency,
+            )
+            .boxed();
+            let monitored_comments = until_deadline(monitored_comments, deadline, expired.clone());
+
+            let exported_comments = save_new_comments(
+                monitored_comments,
+                context,
+                &*context.storage,
+                &rules.retention,
+                &claim_metadata,
+                &cursors,
+            )
+            .inspect(|comment_entity| {
+                context
+                    .exporter
+                    .append_comment(comment_entity)
+                    .expect("Unable to export comment");
+            })
+            .filter(|comment_entity| {
+                future::ready(should_deliver(
+                    context,
+                    comment_entity,
+                    !is_snoozed(&rules.snoozes, comment_entity),
+                ))
+            })
+            .filter(|comment_entity| {
+                future::ready(should_deliver(
+                    context,
+                    comment_entity,
+                    is_source_enabled(&context.notify_sources, comment_entity),
+                ))
+            })
+            .inspect(|comment_entity| {
+                notify_saved_searches(context, &rules.searches, comment_entity)
+            })
+            .filter(|comment_entity| {
+                future::ready(should_deliver(
+                    context,
+                    comment_entity,
+                    context.monitor_notify,
+                ))
+            });
+
+            deliver_notifications(context, notify_mode, exported_comments).await;
+        }
+
+        save_comment_cursors(&context.storage, &cursors);
+
+        let failed_requests = failures.load(Ordering::SeqCst);
+
+        if failed_requests > 0 {
+            error!(
+                "Run was incomplete since {} requests failed",
+                failed_requests
+            );
+        }
+
+        let finished_at = Utc::now();
+        let (status, details) = if expired.load(Ordering::SeqCst) {
+            (
+                "partial",
+                format!("Run deadline reached, {} failed requests", failed_requests),
+            )
+        } else if failed_requests > 0 {
+            ("incomplete", format!("{} failed requests", failed_requests))
+        } else {
+            ("ok", format!("{} failed requests", failed_requests))
+        };
+        let details = if sdk_ready {
+            details
+        } else {
+            format!("Used the comment API fallback, {}", details)
+        };
+
+        context
+            .storage
+            .save_sync_run(NewSyncRun {
+                kind: "watcher".to_string(),
+                status: status.to_string(),
+                details: details.clone(),
+                started_at,
+                finished_at: finished_at.naive_utc(),
+            })
+            .expect("Could not save sync run");
+
+        if let Some(otlp) = &context.otlp {
+            otlp.export(&RunSpans {
+                name: "sync_run".to_string(),
+                started_at: DateTime::from_utc(started_at, Utc),
+                finished_at,
+                attributes: vec![
+                    ("run.kind".to_string(), json!("watcher")),
+                    ("new_comments".to_string(), json!(new_comments)),
+                    ("failed_requests".to_string(), json!(failed_requests)),
+                ],
+                error: if status != "ok" { Some(details) } else { None },
+                calls: context.spans.take(),
+            })
+            .await;
+        }
+
+        if context.metrics.is_enabled() {
+            context
+                .metrics
+                .push(&RunMetrics {
+                    new_comments,
+                    failed_requests,
+                    duration: (finished_at.naive_utc() - started_at)
+                        .to_std()
+                        .unwrap_or_default(),
+                    finished_at,
+                })
+                .await;
+        }
+
+        info!("Done reading comments");
+    });
+}
+
+fn watch_events(
+    api: Arc<Api>,
+    storage: Arc<Storage>,
+    incremental_comments: IncrementalComments,
+    subscription: EventSubscription,
+    page_size: usize,
+) -> Receiver<(Account, Claim, Comment)> {
+    let incremental_comments = match incremental_comments {
+        IncrementalComments::Off => IncrementalComments::Timestamp,
+        incremental_comments => incremental_comments,
+    };
+
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || {
+        let mut rt = Builder::new()
+            .basic_scheduler()
+            .enable_io()
+            .enable_time()
+            .build()
+            .expect("Unable to create runtime");
+
+        loop {
+            rt.block_on(async {
+                match subscription.connect().await {
+                    Ok(events) => {
+                        stream_event_comments(&*api, events, page_size, || {
+                            comment_stops(&storage, incremental_comments)
+                        })
+                        .for_each(|item| {
+                            sender.send(item).ok();
+
+                            future::ready(())
+                        })
+                        .await;
+
+                        warn!("Event subscription to {} closed", subscription.url());
+                    }
+                    Err(err) => error!(
+                        "Unable to subscribe to {} events: {}",
+                        subscription.url(),
+                        err
+                    ),
+                }
+            });
+
+            thread::sleep(time::Duration::from_secs(EVENT_RECONNECT_SECS));
+        }
+    });
+
+    receiver
+}
+
+#[cfg(feature = "read-links")]
+fn serve_read_links(read_links: ReadLinks, address: SocketAddr, storage: Arc<Storage>) {
+    info!("Serving read links on {}", address);
+
+    thread::spawn(move || {
+        let mut rt = Builder::new()
+            .basic_scheduler()
+            .enable_io()
+            .enable_time()
+            .build()
+            .expect("Unable to create runtime");
+
+        if let Err(err) = rt.block_on(read_links.serve(address, storage)) {
+            error!("Unable to serve read links on {}: {}", address, err);
+        }
+    });
+}
+
+fn notify_event_comments(context: &Context, comments: Vec<(Account, Claim, Comment)>) {
+    if !has_disk_space(context, "event comments") {
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .threaded_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    refresh_notification_channels(context);
+
+    rt.block_on(async {
+        info!("Checking {} comments from events", comments.len());
+
+        let rules = load_run_rules(context);
+        let claim_metadata = Mutex::new(HashMap::new());
+
+        notify_comments(
+            context,
+            context.notify_mode,
+            stream::iter(comments),
+            &rules,
+            &claim_metadata,
+            &CommentCursors::default(),
+        )
+        .await;
+    });
+}
+
+fn notify_comment_anomalies(context: &Context, window: usize, threshold: f64) {
+    let day = Utc::now().naive_utc().date() - Duration::days(1);
+    let since = (day - Duration::days(window as i64)).and_hms(0, 0, 0);
+
+    info!("Detecting comment anomalies on {}", day);
+
+    let timestamps = context
+        .storage
+        .get_comment_timestamps_since(since)
+        .expect("Could not get comment timestamps");
+
+    detect_anomalies(&timestamps, day, window, threshold)
+        .iter()
+        .for_each(|anomaly| {
+            info!("Sending anomaly email for {}", &anomaly.account_id);
+
+            mail!(context, anomaly_emails(anomaly));
+        });
+}
+
+fn prune_comments(context: &Context) {
+    let now = Utc::now().naive_utc();
+    let retention = load_retention_policy(context);
+
+    let claim_count = retention
+        .claim_cutoffs(now)
+        .into_iter()
+        .map(|(claim_id, cutoff)| {
+            context
+                .storage
+                .delete_comments_by_claim_id_before(claim_id, cutoff)
+                .expect("Could not prune comments")
+        })
+        .sum::<usize>();
+
+    let default_count = retention
+        .default_cutoff(now)
+        .map(|cutoff| {
+            context
+                .storage
+                .delete_comments_before_except_claim_ids(retention.configured_claim_ids(), cutoff)
+                .expect("Could not prune comments")
+        })
+        .unwrap_or(0);
+
+    info!("Pruned {} comments", claim_count + default_count);
+}
+
+fn archive_comments(context: &Context, archive_days: i64) {
+    if !has_disk_space(context, "comment archival") {
+        return;
+    }
+
+    let cutoff = Utc::now().naive_utc() - Duration::days(archive_days);
+
+    let count = context
+        .storage
+        .archive_comments_before(cutoff)
+        .expect("Could not archive comments");
+
+    info!("Archived {} comments older than {}", count, cutoff);
+}
+
+fn reconcile_backend(context: &Context) -> CommentBackend {
+    context
+        .comment_backend
+        .unwrap_or(if context.channel_urls.is_empty() {
+            CommentBackend::Sdk
+        } else {
+            CommentBackend::Commentron
+        })
+}
+
+async fn count_remote_comments(
+    context: &Context,
+    comment_backend: CommentBackend,
+    claim_id: &str,
+) -> Result<usize, ApiError> {
+    match comment_backend {
+        CommentBackend::Sdk => context.api.count_comments_by_claim_id(claim_id).await,
+        CommentBackend::Commentron => {
+            context
+                .commentron
+                .count_comments_by_claim_id(claim_id)
+                .await
+        }
+    }
+}
+
+async fn resync_claim(
+    context: &Context,
+    comment_backend: CommentBackend,
+    account: Account,
+    claim: Claim,
+    since: Option<NaiveDateTime>,
+) -> Result<(usize, usize, usize), ApiError> {
+    let page_size = *context.page_size;
+    let comments = match (comment_backend, since) {
+        (CommentBackend::Sdk, None) => {
+            context
+                .api
+                .try_stream_comments_by_claim_id(claim.id.clone(), page_size)
+                .try_collect::<Vec<Comment>>()
+                .await?
+        }
+        (CommentBackend::Sdk, Some(since)) => {
+            context
+                .api
+                .try_stream_comments_by_claim_id_since(
+                    claim.id.clone(),
+                    page_size,
+                    DateTime::from_utc(since, Utc),
+                )
+                .try_collect::<Vec<Comment>>()
+                .await?
+        }
+        (CommentBackend::Commentron, None) => {
+            context
+                .commentron
+                .try_stream_comments_by_claim_id(claim.id.clone(), page_size)
+                .try_collect::<Vec<Comment>>()
+                .await?
+        }
+        (CommentBackend::Commentron, Some(since)) => {
+            context
+                .commentron
+                .try_stream_comments_by_claim_id_since(
+                    claim.id.clone(),
+                    page_size,
+                    DateTime::from_utc(since, Utc),
+                )
+                .try_collect::<Vec<Comment>>()
+                .await?
+        }
+    };
+    let comments = comments
+        .into_iter()
+        .filter(|comment| {
+            let ignored = comment.is_hidden && context.hidden_comments == HiddenComments::Ignore;
+            let dropped = context.signature_check.drops(comment);
+
+            !ignored && !dropped
+        })
+        .collect::<Vec<Comment>>();
+
+    let store: &dyn CommentStore = &*context.storage;
+    let stored = store
+        .get_comments_by_claim_id(claim.id.clone())
+        .expect("Could not get comments")
+        .into_iter()
+        .filter(|comment_entity| {
+            since
+                .map(|since| comment_entity.timestamp >= since)
+                .unwrap_or(true)
+        })
+        .collect::<Vec<CommentEntity>>();
+    let delta = diff_comments(stored, comments);
+    let counts = (delta.new.len(), delta.edited.len(), delta.deleted.len());
+
+    info!(
+        "Resyncing claim {} with {} new, {} edited and {} deleted comments",
+        &claim.id,
+        delta.new.len(),
+        delta.edited.len(),
+        delta.deleted.len()
+    );
+
+    context
+        .storage
+        .delete_comment_cursor(claim.id.clone())
+        .expect("Could not delete comment cursor");
+
+    apply_comment_delta(store, account, claim, delta);
+
+    Ok(counts)
+}
+
+fn apply_comment_delta(
+    store: &dyn CommentStore,
+    account: Account,
+    claim: Claim,
+    delta: CommentDelta,
+) {
+    for comment in delta.new {
+        store
+            .save_comment(account.clone(), claim.clone(), comment)
+            .expect("Could not save comment");
+    }
+
+    for (comment_entity, comment) in delta.edited {
+        store
+            .update_comment(comment_entity, &comment)
+            .expect("Could not update comment");
+    }
+
+    for comment_entity in delta.deleted {
+        store
+            .delete_comment_by_id(comment_entity.id)
+            .expect("Could not delete comment");
+    }
+}
+
+fn reconcile_comments(context: &Context, resync: bool) {
+    if !has_disk_space(context, "comment reconciliation") {
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .threaded_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let started_at = Utc::now().naive_utc();
+    let comment_backend = reconcile_backend(context);
+    let retention = load_retention_policy(context);
+    let known_claims = context
+        .storage
+        .get_known_claims()
+        .expect("Could not get known claims")
+        .into_iter()
+        .filter(|(_, claim)| retention.retention_days(&claim.id).is_none())
+        .collect::<Vec<(Account, Claim)>>();
+
+    let counts = rt.block_on(
+        stream::iter(known_claims.iter())
+            .map(|(_, claim)| async move {
+                match count_remote_comments(context, comment_backend, &claim.id).await {
+                    Ok(remote) => Some(ClaimCount {
+                        claim_id: claim.id.clone(),
+                        claim_name: claim.name.clone(),
+                        remote,
+                        local: context
+                            .storage
+                            .count_comments_by_claim_id(claim.id.clone())
+                            .expect("Could not count comments")
+                            as usize,
+                    }),
+                    Err(err) => {
+                        error!("Unable to count comments of claim {}: {}", &claim.id, err);
+
+                        None
+                    }
+                }
+            })
+            .buffer_unordered(context.claim_concurrency.max(1))
+            .filter_map(future::ready)
+            .collect::<Vec<ClaimCount>>(),
+    );
+    let report = ReconcileReport::new(counts);
+
+    info!(
+        "Comment reconciliation finished with {}: {}",
+        report.status(),
+        report.details()
+    );
+
+    context
+        .storage
+        .save_sync_run(NewSyncRun {
+            kind: "reconcile".to_string(),
+            status: report.status().to_string(),
+            details: report.details(),
+            started_at,
+            finished_at: Utc::now().naive_utc(),
+        })
+        .expect("Could not save sync run");
+
+    if !resync {
+        return;
+    }
+
+    known_claims
+        .into_iter()
+        .filter(|(_, claim)| {
+            report
+                .mismatches
+                .iter()
+                .any(|mismatch| mismatch.claim_id == claim.id)
+        })
+        .for_each(|(account, claim)| {
+            let claim_id = claim.id.clone();
+
+            if let Err(err) =
+                rt.block_on(resync_claim(context, comment_backend, account, claim, None))
+            {
+                error!("Unable to resync claim {}: {}", claim_id, err);
+            }
+        });
+}
+
+fn resync_comments(context: &Context, matches: &ArgMatches) {
+    let claim_filter = matches.value_of("claim");
+
+    if claim_filter.is_none() && !matches.is_present("all") {
+        println!("Either --claim or --all is needed");
+
+        return;
+    }
+
+    let since = match matches.value_of("since") {
+        Some(since) => match NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+            Ok(date) => Some(date.and_hms(0, 0, 0)),
+            Err(err) => {
+                println!("Invalid date {}: {}", since, err);
+
+                return;
+            }
+        },
+        None => None,
+    };
+
+    let known_claims = context
+        .storage
+        .get_known_claims()
+        .expect("Could not get known claims")
+        .into_iter()
+        .filter(|(_, claim)| {
+            claim_filter
+                .map(|claim_filter| claim.id == claim_filter || claim.name == claim_filter)
+                .unwrap_or(true)
+        })
+        .collect::<Vec<(Account, Claim)>>();
+
+    if known_claims.is_empty() {
+        println!("No stored claims to resync");
+
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let comment_backend = reconcile_backend(context);
+
+    for (account, claim) in known_claims {
+        let claim_id = claim.id.clone();
+        let claim_name = claim.name.clone();
+
+        match rt.block_on(resync_claim(
+            context,
+            comment_backend,
+            account,
+            claim,
+            since,
+        )) {
+            Ok((new, edited, deleted)) => println!(
+                "Resynced {} ({}): {} new, {} edited, {} deleted",
+                claim_name, claim_id, new, edited, deleted
+            ),
+            Err(err) => println!("Unable to resync {} ({}): {}", claim_name, claim_id, err),
+        }
+    }
+}
+
+fn maintain_storage(context: &Context) {
+    if !has_disk_space(context, "database maintenance") {
+        return;
+    }
+
+    let started_at = Utc::now().naive_utc();
+
+    let (status, details) = match context.storage.run_maintenance() {
+        Ok(report) => (report.status().to_string(), report.details()),
+        Err(err) => ("error".to_string(), err.to_string()),
+    };
+
+    info!("Database maintenance finished with {}: {}", status, details);
+
+    context
+        .storage
+        .save_sync_run(NewSyncRun {
+            kind: "maintenance".to_string(),
+            status: status.clone(),
+            details: details.clone(),
+            started_at,
+            finished_at: Utc::now().naive_utc(),
+        })
+        .expect("Could not save sync run");
+
+    if status != "ok" {
+        error!("Sending maintenance alert email");
+
+        mail!(context, maintenance_emails(&status, &details));
+    }
+}
+
+fn send_heartbeat(context: &Context, days: i64) {
+    let latest = context
+        .storage
+        .get_latest_comment_timestamp()
+        .expect("Could not get latest comment timestamp");
+
+    let cutoff = Utc::now().naive_utc() - Duration::days(days);
+
+    if latest.map(|latest| latest < cutoff).unwrap_or(true) {
+        info!("Sending heartbeat email");
+
+        mail!(context, heartbeat_emails(latest));
+    }
+}
+
+#[cfg(feature = "email")]
+fn send_monthly_report(context: &Context, recipients: &[String], template: &str, limit: usize) {
+    let (start, end) = previous_month(Utc::now().naive_utc().date());
+
+    let comment_entities = context
+        .storage
+        .get_comments_between(start.and_hms(0, 0, 0), end.and_hms(0, 0, 0))
+        .expect("Could not get comments");
+
+    let report = MonthlyReport::new(start, &comment_entities, limit);
+
+    info!(
+        "Sending monthly report for {} with {} comments",
+        start,
+        comment_entities.len()
+    );
+
+    send_email(
+        &context.mailer,
+        context
+            .emails
+            .report_email(recipients, &report, template)
+            .into(),
+    );
+}
+
+fn notify_comment_reactions(context: &Context, days: i64) {
+    let since = Utc::now().naive_utc() - Duration::days(days);
+
+    let comment_entities = context
+        .storage
+        .get_comments_since(Some(since))
+        .expect("Could not get comments")
+        .into_iter()
+        .map(|comment_entity| (comment_entity.id.clone(), comment_entity))
+        .collect::<HashMap<String, CommentEntity>>();
+
+    if comment_entities.is_empty() {
+        return;
+    }
+
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let comment_ids = comment_entities.keys().cloned().collect::<Vec<String>>();
+
+    let reactions = comment_ids
+        .chunks((*context.page_size).max(1))
+        .flat_map(|chunk| {
+            rt.block_on(context.api.list_reactions(chunk.to_vec()))
+                .map_err(|err| {
+                    error!("Unable to list reactions: {}", err);
+                })
+                .unwrap_or_default()
+        })
+        .map(|(comment_id, counts)| Reaction {
+            comment_id,
+            likes: counts.like,
+            dislikes: counts.dislike,
+        })
+        .collect::<Vec<Reaction>>();
+
+    let previous_reactions = context
+        .storage
+        .get_reactions_by_comment_ids(comment_ids)
+        .expect("Could not get reactions");
+
+    let changes = reaction_changes(&previous_reactions, &reactions)
+        .into_iter()
+        .filter_map(|change| {
+            comment_entities
+                .get(&change.comment_id)
+                .cloned()
+                .map(|comment_entity| (change, comment_entity))
+        })
+        .collect::<Vec<(ReactionChange, CommentEntity)>>();
+
+    context
+        .storage
+        .save_reactions(reactions)
+        .expect("Could not save reactions");
+
+    if !changes.is_empty() {
+        info!("Sending reaction email for {} comments", changes.len());
+
+        mail!(context, reaction_emails(&changes));
+    }
+}
+
+fn notify_new_supports(context: &Context) {
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let api_ref = context.api.clone();
+    let page_size = *context.page_size;
+
+    let supports = rt.block_on(
+        context
+            .api
+            .stream_accounts(page_size)
+            .map(move |account| {
+                api_ref
+                    .stream_supports(account.id.clone(), page_size)
+                    .zip(stream::repeat(account))
+            })
+            .flatten()
+            .collect::<Vec<(Support, Account)>>(),
+    );
+
+    let new_supports = supports
+        .into_iter()
+        .filter_map(|(support, account)| {
+            context
+                .storage
+                .save_support(&account, support)
+                .expect("Could not save support")
+        })
+        .collect::<Vec<SupportEntity>>();
+
+    info!("Found {} new supports", new_supports.len());
+
+    new_supports.iter().for_each(|support| {
+        info!(
+            "Sending support email for {} on {}",
+            support.id, support.claim_name
+        );
+
+        mail!(context, support_emails(support));
+    });
+}
+
+fn notify_new_transactions(context: &Context) {
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let page_size = *context.page_size;
+
+    let accounts = rt.block_on(
+        context
+            .api
+            .stream_accounts(page_size)
+            .collect::<Vec<Account>>(),
+    );
+
+    accounts.into_iter().for_each(|account| {
+        let is_first_run = !context
+            .storage
+            .has_transactions(&account.id)
+            .expect("Could not check transactions");
+
+        let transactions = rt.block_on(
+            context
+                .api
+                .stream_transactions(account.id.clone(), page_size)
+                .filter(|transaction| future::ready(transaction.value > 0.0))
+                .collect::<Vec<Transaction>>(),
+        );
+
+        let new_transactions = transactions
+            .into_iter()
+            .filter_map(|transaction| {
+                context
+                    .storage
+                    .save_transaction(&account, transaction)
+                    .expect("Could not save transaction")
+            })
+            .collect::<Vec<TransactionEntity>>();
+
+        if is_first_run {
+            info!(
+                "Recorded {} existing transactions of account {}",
+                new_transactions.len(),
+                account.id
+            );
+
+            return;
+        }
+
+        new_transactions.iter().for_each(|transaction| {
+            info!("Sending transaction email for {}", transaction.txid);
+
+            mail!(context, transaction_emails(transaction));
+        });
+    });
+}
+
+async fn monitored_channel_ids(context: &Context) -> HashSet<String> {
+    stream::iter(context.monitor_urls.iter())
+        .filter_map(|channel_url| async move {
+            context
+                .api
+                .resolve_channel(channel_url)
+                .await
+                .map_err(|err| error!("Unable to resolve channel {}: {}", channel_url, err))
+                .ok()
+        })
+        .map(|channel| channel.id)
+        .collect::<HashSet<String>>()
+        .await
+}
+
+fn is_awaiting_reply(
+    comment_entity: &CommentEntity,
+    own_channel_ids: &HashSet<String>,
+    replied_ids: &HashSet<String>,
+    monitored_ids: &HashSet<String>,
+) -> bool {
+    !own_channel_ids.contains(&comment_entity.commenter_id)
+        && !replied_ids.contains(&comment_entity.id)
+        && !monitored_ids.contains(&comment_entity.account_id)
+}
+
+fn remind_unread_comments(context: &Context) {
+    let mut rt = Builder::new()
+        .threaded_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    rt.block_on(refresh_own_channels(context));
+
+    let monitored_ids = rt.block_on(monitored_channel_ids(context));
+    let own_channel_ids = context
+        .own_channel_ids
+        .lock()
+        .expect("Unable to get lock")
+        .clone();
+    let replied_ids = context
+        .storage
+        .get_replied_comment_ids(own_channel_ids.iter().cloned().collect())
+        .expect("Could not get replied comments");
+
+    let comment_entities = context
+        .storage
+        .get_unread_comments()
+        .expect("Could not get unread comments")
+        .into_iter()
+        .filter(|comment_entity| {
+            is_awaiting_reply(
+                comment_entity,
+                &own_channel_ids,
+                &replied_ids,
+                &monitored_ids,
+            )
+        })
+        .collect::<Vec<CommentEntity>>();
+
+    if comment_entities.is_empty() {
+        return;
+    }
+
+    info!(
+        "Sending reminder email for {} unread comments",
+        comment_entities.len()
+    );
+
+    mail!(
+        context,
+        reminder_emails(
+            comment_entities,
+            context.digest_order,
+            context.digest_max_bytes
+        )
+    );
+}
+
+fn mark_comments_as_read(context: &Context, matches: &ArgMatches) {
+    let comment_ids = matches
+        .values_of("COMMENT_ID")
+        .map(|values| values.map(String::from).collect::<Vec<String>>())
+        .unwrap_or_default();
+
+    let count = context
+        .storage
+        .mark_comments_as_read(comment_ids)
+        .expect("Could not mark comments as read");
+
+    println!("Marked {} comments as read", count);
+}
+
+fn filter_comments(context: &Context, matches: &ArgMatches) -> Option<Vec<CommentEntity>> {
+    let terms = vec!["commenter", "claim", "contains"]
+        .into_iter()
+        .filter_map(|key| {
+            matches
+                .value_of(key)
+                .map(|value| SearchTerm::parse(&format!("{}:{}", key, value)))
+        })
+        .collect::<Result<Vec<SearchTerm>, String>>();
+
+    let terms = match terms {
+        Ok(terms) if !terms.is_empty() => terms,
+        Ok(_) => {
+            println!("At least one of --commenter, --claim or --contains is required");
+
+            return None;
+        }
+        Err(err) => {
+            println!("Invalid filter: {}", err);
+
+            return None;
+        }
+    };
+
+    let since = match matches.value_of("since") {
+        Some(since) => match NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+            Ok(date) => Some(date.and_hms(0, 0, 0)),
+            Err(err) => {
+                println!("Invalid date {}: {}", since, err);
+
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    let comment_entities = context
+        .storage
+        .get_comments_since(since)
+        .expect("Could not get comments")
+        .into_iter()
+        .filter(|comment_entity| terms.iter().all(|term| term.matches(comment_entity)))
+        .collect::<Vec<CommentEntity>>();
+
+    comment_entities.iter().for_each(|comment_entity| {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            comment_entity.id,
+            comment_entity.timestamp,
+            comment_entity.claim_name,
+            comment_entity.commenter_name,
+            comment_entity.comment.replace('\n', " ")
+        );
+    });
+
+    if matches.is_present("dry-run") {
+        println!("Dry run matched {} comments", comment_entities.len());
+
+        return None;
+    }
+
+    Some(comment_entities)
+}
+
+fn hide_comments(context: &Context, matches: &ArgMatches) {
+    let comment_entities = match filter_comments(context, matches) {
+        Some(comment_entities) => comment_entities,
+        None => return,
+    };
+
+    if comment_entities.is_empty() {
+        println!("No comments to hide");
+
+        return;
+    }
+
+    let mut claim_ids = comment_entities
+        .iter()
+        .map(|comment_entity| comment_entity.claim_id.clone())
+        .collect::<Vec<String>>();
+    claim_ids.sort();
+    claim_ids.dedup();
+
+    let comment_ids = comment_entities
+        .into_iter()
+        .map(|comment_entity| comment_entity.id)
+        .collect::<Vec<String>>();
+
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let hidden_ids = match rt.block_on(context.api.hide_owned_comments(&claim_ids, comment_ids)) {
+        Ok(hidden) => hidden.into_keys().collect::<Vec<String>>(),
+        Err(ModerationError::Api(err)) => {
+            println!("Unable to hide comments: {}", err);
+
+            return;
+        }
+        Err(err) => {
+            println!("Refusing to hide comments: {}", err);
+
+            return;
+        }
+    };
+
+    let count = context
+        .storage
+        .hide_comments(hidden_ids)
+        .expect("Could not hide comments");
+
+    println!("Hid {} comments", count);
+}
+
+fn tag_comments(context: &Context, matches: &ArgMatches) {
+    let tag = matches.value_of("TAG").unwrap_or_default().to_string();
+
+    let comment_ids = match filter_comments(context, matches) {
+        Some(comment_entities) => comment_entities
+            .into_iter()
+            .map(|comment_entity| comment_entity.id)
+            .collect::<Vec<String>>(),
+        None => return,
+    };
+
+    let count = context
+        .storage
+        .tag_comments(comment_ids, tag.clone())
+        .expect("Could not tag comments");
+
+    println!("Tagged {} comments as {}", count, tag);
+}
+
+fn manage_notification_channels(context: &Context, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(add_matches)) => {
+            let name = add_matches.value_of("NAME").unwrap_or_default().to_string();
+            let (kind, target) = match add_matches.value_of("email") {
+                Some(email) => ("email", email),
+                None => (
+                    "webhook",
+                    add_matches.value_of("webhook").unwrap_or_default(),
+                ),
+            };
+            let channel = NotificationChannel {
+                name: name.clone(),
+                kind: kind.to_string(),
+                target: target.to_string(),
+                token_env: add_matches.value_of("token-env").map(String::from),
+            };
+
+            if let Err(err) = channel.target() {
+                println!("Invalid notification channel: {}", err);
+
+                return;
+            }
+
+            context
+                .storage
+                .save_notification_channel(channel)
+                .expect("Could not save notification channel");
+
+            println!("Saved {} channel {}", kind, name);
+        }
+        ("remove", Some(remove_matches)) => {
+            let name = remove_matches
+                .value_of("NAME")
+                .unwrap_or_default()
+                .to_string();
+
+            let count = context
+                .storage
+                .delete_notification_channel_by_name(name.clone())
+                .expect("Could not delete notification channel");
+
+            println!("Removed {} notification channel named {}", count, name);
+        }
+        _ => {
+            context
+                .storage
+                .get_notification_channels()
+                .expect("Could not get notification channels")
+                .into_iter()
+                .for_each(|channel| {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        channel.name,
+                        channel.kind,
+                        channel.target,
+                        channel.token_env.unwrap_or("".to_string())
+                    );
+                });
+        }
+    }
+}
+
+fn manage_saved_searches(context: &Context, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(add_matches)) => {
+            let name = add_matches.value_of("NAME").unwrap_or_default().to_string();
+            let query = add_matches
+                .value_of("QUERY")
+                .unwrap_or_default()
+                .to_string();
+
+            if let Err(err) = query.parse::<SearchQuery>() {
+                println!("Invalid search query: {}", err);
+
+                return;
+            }
+
+            context
+                .storage
+                .save_search(SavedSearch {
+                    name: name.clone(),
+                    query,
+                    recipient: add_matches.value_of("to").map(String::from),
+                })
+                .expect("Could not save search");
+
+            println!("Saved search {}", name);
+        }
+        ("remove", Some(remove_matches)) => {
+            let name = remove_matches
+                .value_of("NAME")
+                .unwrap_or_default()
+                .to_string();
+
+            let count = context
+                .storage
+                .delete_saved_search_by_name(name.clone())
+                .expect("Could not delete saved search");
+
+            println!("Removed {} saved search named {}", count, name);
+        }
+        _ => {
+            context
+                .storage
+                .get_saved_searches()
+                .expect("Could not get saved searches")
+                .into_iter()
+                .for_each(|search| {
+                    println!(
+                        "{}\t{}\t{}",
+                        search.name,
+                        search.query,
+                        search.recipient.unwrap_or("".to_string())
+                    );
+                });
+        }
+    }
+}
+
+fn export_commenters(context: &Context, matches: &ArgMatches) {
+    let comment_entities = context
+        .storage
+        .get_comments_since(None)
+        .expect("Could not get comments");
+
+    let excluded_ids = if matches.is_present("include-blocked") {
+        HashSet::new()
+    } else {
+        let snoozes = load_active_snoozes(&context.storage);
+        let tagged_ids = matches
+            .values_of("exclude-tag")
+            .map(|tags| tags.collect::<Vec<&str>>())
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|tag| {
+                context
+                    .storage
+                    .get_comment_ids_by_tag(tag.to_string())
+                    .expect("Could not get tagged comments")
+            })
+            .collect::<HashSet<String>>();
+
+        comment_entities
+            .iter()
+            .filter(|comment_entity| {
+                comment_entity.is_hidden
+                    || tagged_ids.contains(&comment_entity.id)
+                    || is_snoozed(&snoozes, comment_entity)
+            })
+            .map(|comment_entity| comment_entity.commenter_id.clone())
+            .collect::<HashSet<String>>()
+    };
+
+    let contacts = commenter_contacts(&comment_entities)
+        .into_iter()
+        .filter(|contact| !excluded_ids.contains(&contact.commenter_id))
+        .collect::<Vec<CommenterContact>>();
+
+    let export = match matches.value_of("format").unwrap_or("csv") {
+        "json" => format!(
+            "{}\n",
+            serde_json::to_string_pretty(&contacts).expect("Unable to serialize commenters")
+        ),
+        _ => render_commenters_csv(&contacts),
+    };
+
+    match matches.value_of("output") {
+        Some(output) => {
+            fs::write(output, export).expect("Unable to write export");
+
+            println!("Exported {} commenters to {}", contacts.len(), output);
+        }
+        None => print!("{}", export),
+    }
+}
+
+fn export_comments(context: &Context, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("markdown", Some(markdown_matches)) => {
+            let claim_id = markdown_matches
+                .value_of("CLAIM_ID")
+                .unwrap_or_default()
+                .to_string();
+
+            let comment_entities = context
+                .storage
+                .get_comments_by_claim_id(claim_id.clone())
+                .expect("Could not get comments");
+
+            let claim_name = comment_entities
+                .first()
+                .map(|comment_entity| comment_entity.claim_name.clone())
+                .unwrap_or(claim_id);
+
+            let markdown = render_markdown_thread(&claim_name, &comment_entities);
+
+            match markdown_matches.value_of("output") {
+                Some(output) => {
+                    fs::write(output, markdown).expect("Unable to write export");
+                }
+                None => print!("{}", markdown),
+            }
+        }
+        ("commenters", Some(commenters_matches)) => {
+            export_commenters(context, commenters_matches);
+        }
+        _ => {}
+    }
+}
+
+fn manage_snoozes(context: &Context, matches: &ArgMatches) {
+    let kind_target = |kind_matches: &ArgMatches| {
+        let kind = kind_matches
+            .value_of("KIND")
+            .unwrap_or_default()
+            .parse::<SnoozeKind>()
+            .expect("Invalid snooze kind");
+        let target = kind_matches
+            .value_of("TARGET")
+            .unwrap_or_default()
+            .to_string();
+
+        (kind, target)
+    };
+
+    match matches.subcommand() {
+        ("add", Some(add_matches)) => {
+            let (kind, target) = kind_target(add_matches);
+
+            let duration = match parse_duration(add_matches.value_of("for").unwrap_or("24h")) {
+                Ok(duration) => duration,
+                Err(err) => {
+                    println!("Invalid snooze duration: {}", err);
+
+                    return;
+                }
+            };
+
+            let snooze = context
+                .storage
+                .save_snooze(Snooze {
+                    kind: kind.as_str().to_string(),
+                    target,
+                    until: Utc::now().naive_utc() + duration,
+                })
+                .expect("Could not save snooze");
+
+            println!(
+                "Snoozed {} {} until {}",
+                snooze.kind, snooze.target, snooze.until
+            );
+        }
+        ("remove", Some(remove_matches)) => {
+            let (kind, target) = kind_target(remove_matches);
+
+            let count = context
+                .storage
+                .delete_snooze(kind, target.clone())
+                .expect("Could not delete snooze");
+
+            println!("Removed {} snooze for {} {}", count, kind.as_str(), target);
+        }
+        _ => {
+            context
+                .storage
+                .get_active_snoozes(Utc::now().naive_utc())
+                .expect("Could not get active snoozes")
+                .into_iter()
+                .for_each(|snooze| {
+                    println!("{}\t{}\t{}", snooze.kind, snooze.target, snooze.until);
+                });
+        }
+    }
+}
+
+fn import_comments(context: &Context, matches: &ArgMatches) {
+    let path = matches.value_of("FILE").unwrap_or_default();
+    let format = matches
+        .value_of("format")
+        .unwrap_or_default()
+        .parse::<ImportFormat>()
+        .expect("Invalid import format");
+    let text = fs::read_to_string(path).expect("Unable to read import file");
+
+    let comment_entities = match format {
+        ImportFormat::Youtube => import_youtube_csv(&text),
+        ImportFormat::Odysee => import_odysee_export(&text),
+    }
+    .expect("Unable to parse import file");
+    let total = comment_entities.len();
+
+    if !has_disk_space(context, "the import") {
+        process::exit(1);
+    }
+
+    let count = context
+        .storage
+        .import_comments(comment_entities)
+        .expect("Could not import comments");
+
+    println!(
+        "Imported {} of {} {} comments",
+        count,
+        total,
+        format.source()
+    );
+}
+
+fn manage_canned_replies(context: &Context, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(add_matches)) => {
+            let canned_reply = context
+                .storage
+                .save_canned_reply(CannedReply {
+                    name: add_matches.value_of("NAME").unwrap_or_default().to_string(),
+                    body: add_matches.value_of("BODY").unwrap_or_default().to_string(),
+                    channel_name: add_matches.value_of("channel").map(String::from),
+                })
+                .expect("Could not save canned reply");
+
+            println!("Saved canned reply {}", canned_reply.name);
+        }
+        ("remove", Some(remove_matches)) => {
+            let name = remove_matches
+                .value_of("NAME")
+                .unwrap_or_default()
+                .to_string();
+
+            let count = context
+                .storage
+                .delete_canned_reply_by_name(name.clone())
+                .expect("Could not delete canned reply");
+
+            println!("Removed {} canned reply named {}", count, name);
+        }
+        _ => {
+            context
+                .storage
+                .get_canned_replies()
+                .expect("Could not get canned replies")
+                .into_iter()
+                .for_each(|canned_reply| {
+                    println!(
+                        "{}\t{}\t{}",
+                        canned_reply.name,
+                        canned_reply.channel_name.unwrap_or_default(),
+                        canned_reply.body
+                    );
+                });
+        }
+    }
+}
+
+fn reply_to_comment(context: &Context, matches: &ArgMatches) {
+    let comment_id = matches.value_of("COMMENT_ID").unwrap_or_default();
+    let template = matches.value_of("template").unwrap_or_default();
+
+    let comment_entity = match context.storage.get_comment_by_id(comment_id.to_string()) {
+        Some(comment_entity) => comment_entity,
+        None => {
+            println!("Unknown comment {}", comment_id);
+
+            return;
+        }
+    };
+
+    let canned_reply = match context
+        .storage
+        .get_canned_reply_by_name(template.to_string())
+    {
+        Some(canned_reply) => canned_reply,
+        None => {
+            println!("Unknown canned reply {}", template);
+
+            return;
+        }
+    };
+
+    let channel_name = match matches
+        .value_of("channel")
+        .map(String::from)
+        .or_else(|| canned_reply.channel_name.clone())
+    {
+        Some(channel_name) => channel_name,
+        None => {
+            println!("A --channel is required to sign the reply");
+
+            return;
+        }
+    };
+
+    let mut rt = Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("Unable to create runtime");
+
+    let claim_ids = vec![comment_entity.claim_id.clone()];
+    let ownership = context
+        .api
+        .verify_ownership(&claim_ids, Some(&channel_name));
+
+    if let Err(err) = rt.block_on(ownership) {
+        println!("Refusing to reply to {}: {}", comment_entity.id, err);
+
+        return;
+    }
+
+    let reply = rt.block_on(context.api.create_comment(
+        canned_reply.render(&comment_entity),
+        comment_entity.claim_id.clone(),
+        Some(comment_entity.id.clone()),
+        channel_name.clone(),
+    ));
+
+    match reply {
+        Ok(_) => println!(
+            "Replied to {} as {} with {}",
+            comment_entity.id, channel_name, canned_reply.name
+        ),
+        Err(err) => println!("Unable to reply to {}: {}", comment_entity.id, err),
+    }
+}
+
+fn manage_claim_settings(context: &Context, matches: &ArgMatches) {
+    let format_retention = |retention_days: Option<i32>| match retention_days {
+        Some(days) => format!("{} days", days),
+        None => "forever".to_string(),
+    };
+
+    match matches.subcommand() {
+        ("set", Some(set_matches)) => {
+            let claim_id = set_matches.value_of("CLAIM_ID").unwrap_or_default();
+
+            let retention_days =
+                match parse_retention(set_matches.value_of("retention").unwrap_or("forever")) {
+                    Ok(retention_days) => retention_days,
+                    Err(err) => {
+                        println!("Invalid retention: {}", err);
+
+                        return;
+                    }
+                };
+
+            let setting = context
+                .storage
+                .save_claim_setting(ClaimSetting {
+                    claim_id: claim_id.to_string(),
+                    retention_days,
+                })
+                .expect("Could not save claim setting");
+
+            println!(
+                "Retention of {} is {}",
+                setting.claim_id,
+                format_retention(setting.retention_days)
+            );
+        }
+        ("reset", Some(reset_matches)) => {
+            let claim_id = reset_matches.value_of("CLAIM_ID").unwrap_or_default();
+
+            let count = context
+                .storage
+                .delete_claim_setting(claim_id.to_string())
+                .expect("Could not delete claim setting");
+
+            println!("Removed {} settings for {}", count, claim_id);
+        }
+        ("top", Some(top_matches)) => {
+            let limit = top_matches
+                .value_of("limit")
+                .unwrap_or("10")
+                .parse::<i64>()
+                .unwrap_or(10);
+            let format_stat = |stat: Option<String>| stat.unwrap_or("-".to_string());
+
+            context
+                .storage
+                .get_top_claims(limit)
+                .expect("Could not get claims")
+                .into_iter()
+                .for_each(|claim| {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        claim.id,
+                        format_stat(claim.view_count.map(|count| count.to_string())),
+                        format_stat(claim.effective_amount.map(|amount| amount.to_string())),
+                        claim.title.as_deref().unwrap_or(&claim.name)
+                    );
+                });
+        }
+        _ => {
+            println!("default\t{}", format_retention(context.retention_days));
+
+            context
+                .storage
+                .get_claim_settings()
+                .expect("Could not get claim settings")
+                .into_iter()
+                .for_each(|setting| {
+                    println!(
+                        "{}\t{}",
+                        setting.claim_id,
+                        format_retention(setting.retention_days)
+                    );
+                });
+        }
+    }
+}
+
+fn load_rule_bundle(context: &Context) -> RuleBundle {
+    RuleBundle {
+        filters: context
+            .storage
+            .get_saved_searches()
+            .expect("Could not get saved searches"),
+        blocklist: context
+            .storage
+            .get_active_snoozes(Utc::now().naive_utc())
+            .expect("Could not get active snoozes"),
+        claims: context
+            .storage
+            .get_claim_settings()
+            .expect("Could not get claim settings"),
+    }
+}
+
+fn manage_rules(context: &Context, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("export", Some(export_matches)) => {
+            let yaml = load_rule_bundle(context)
+                .to_yaml()
+                .expect("Unable to serialize rules");
+
+            match export_matches.value_of("output") {
+                Some(output) => {
+                    fs::write(output, yaml).expect("Unable to write rules");
+                }
+                None => println!("{}", yaml),
+            }
+        }
+        ("import", Some(import_matches)) => {
+            let path = import_matches.value_of("FILE").unwrap_or_default();
+            let content = fs::read_to_string(path).expect("Unable to read rules");
+
+            let incoming = match RuleBundle::from_yaml(&content) {
+                Ok(bundle) => bundle,
+                Err(err) => {
+                    println!("Invalid rules bundle: {}", err);
+
+                    return;
+                }
+            };
+
+            let strategy = import_matches
+                .value_of("on-conflict")
+                .unwrap_or("keep")
+                .parse::<ConflictStrategy>()
+                .expect("Invalid conflict strategy");
+
+            let existing = load_rule_bundle(context);
+
+            if import_matches.is_present("replace") {
+                existing.filters.iter().for_each(|search| {
+                    context
+                        .storage
+                        .delete_saved_search_by_name(search.name.clone())
+                        .expect("Could not delete saved search");
+                });
+                existing.blocklist.iter().for_each(|snooze| {
+                    let kind = snooze
+                        .kind
+                        .parse::<SnoozeKind>()
+                        .expect("Invalid snooze kind");
+
+                    context
+                        .storage
+                        .delete_snooze(kind, snooze.target.clone())
+                        .expect("Could not delete snooze");
+                });
+                existing.claims.iter().for_each(|setting| {
+                    context
+                        .storage
+                        .delete_claim_setting(setting.claim_id.clone())
+                        .expect("Could not delete claim setting");
+                });
+            }
+
+            let BundleMerge { bundle, conflicts } = if import_matches.is_present("replace") {
+                RuleBundle::default().merge(incoming, strategy)
+            } else {
+                existing.merge(incoming, strategy)
+            };
+
+            conflicts.iter().for_each(|conflict| {
+                let action = match strategy {
+                    ConflictStrategy::Keep => "Kept",
+                    ConflictStrategy::Overwrite => "Overwrote",
+                };
+
+                println!("{} conflicting {}", action, conflict);
+            });
+
+            let counts = (
+                bundle.filters.len(),
+                bundle.blocklist.len(),
+                bundle.claims.len(),
+            );
+
+            bundle.filters.into_iter().for_each(|search| {
+                context
+                    .storage
+                    .save_search(search)
+                    .expect("Could not save search");
+            });
+            bundle.blocklist.into_iter().for_each(|snooze| {
+                context
+                    .storage
+                    .save_snooze(snooze)
+                    .expect("Could not save snooze");
+            });
+            bundle.claims.into_iter().for_each(|setting| {
+                context
+                    .storage
+                    .save_claim_setting(setting)
+                    .expect("Could not save claim setting");
+            });
+
+            println!(
+                "Imported {} filters, {} blocklist entries and {} claim settings",
+                counts.0, counts.1, counts.2
+            );
+        }
+        _ => {}
+    }
+}
+
+fn test_comment() -> CommentEntity {
+    CommentEntity {
+        id: "test-notification".to_string(),
+        account_id: "test-account".to_string(),
+        claim_id: "test-claim".to_string(),
+        claim_name: "test-claim".to_string(),
+        commenter_id: "test-commenter".to_string(),
+        commenter_name: "@lbry-comment-notifier".to_string(),
+        commenter_url: "lbry://@lbry-comment-notifier".to_string(),
+        comment: "This is a test notification.".to_string(),
+        is_hidden: false,
+        timestamp: Utc::now().naive_utc(),
+        support_amount: 0.0,
+        is_read: false,
+        parent_id: None,
+        claim_title: Some("Test Notification".to_string()),
+        claim_thumbnail_url: None,
+        claim_canonical_url: None,
+        source: "lbry".to_string(),
+        is_signature_valid: None,
+    }
+}
+
+#[cfg(feature = "email")]
+fn test_email(context: &Context, comment_entity: &CommentEntity) {
+    for email in context
+        .emails
+        .notification_emails(comment_entity, None, None, None)
+    {
+        let result = context
+            .mailer
+            .lock()
+            .expect("Unable to get lock")
+            .send(email.into());
+
+        match result {
+            Ok(_) => println!("email: ok"),
+            Err(err) => println!("email: failed ({:?})", err),
+        }
+    }
+}
+
+#[cfg(not(feature = "email"))]
+fn test_email(_context: &Context, _comment_entity: &CommentEntity) {
+    println!("email: not built");
+}
+
+fn test_notify(context: &Context, matches: &ArgMatches) {
+    let channel = matches.value_of("channel").unwrap_or("all");
+    let comment_entity = test_comment();
+
+    if channel == "email" || channel == "all" {
+        test_email(context, &comment_entity);
+    }
+
+    if channel == "webhook" || channel == "all" {
+        if context.webhooks.is_empty() {
+            println!("webhook: not configured");
+        }
 
-use core::{Account, Api, Claim, Comment, Emails, Storage};
+        let mut rt = Builder::new()
+            .basic_scheduler()
+            .enable_io()
+            .enable_time()
+            .build()
+            .expect("Unable to create runtime");
 
-fn all_comments(
-    api_ref: Arc<Api>,
-    page_size_ref: Arc<usize>,
-) -> impl Stream<Item = (Account, Claim, Comment)> {
-    let claim_api_ref = api_ref.clone();
-    let claim_page_ref = page_size_ref.clone();
+        context.webhooks.webhooks().iter().for_each(|webhook| {
+            match rt.block_on(context.webhooks.send(webhook, &comment_entity)) {
+                Ok(_) => println!("webhook {}: ok", webhook.url),
+                Err(err) => println!("webhook {}: failed ({})", webhook.url, err),
+            }
+        });
+    }
+}
 
-    let comment_api_ref = api_ref.clone();
-    let comment_page_ref = page_size_ref.clone();
+fn write_schemas(matches: &ArgMatches) {
+    let output_dir = matches.value_of("output-dir").unwrap_or("schemas");
 
-    let buffer = num_cpus::get();
+    fs::create_dir_all(output_dir).expect("Unable to create schema directory");
 
-    api_ref
-        .stream_accounts(*page_size_ref)
-        .map(move |account| {
-            claim_api_ref
-                .stream_claims_by_account_id(account.id.clone(), *claim_page_ref)
-                .zip(stream::repeat(account.clone()))
+    export_schemas().into_iter().for_each(|(name, schema)| {
+        let path = format!("{}/{}.schema.json", output_dir, name);
+        let mut json = serde_json::to_string_pretty(&schema).expect("Unable to serialize schema");
+        json.push('\n');
+
+        fs::write(&path, json).expect("Unable to write schema");
+
+        println!("Wrote {}", path);
+    });
+}
+
+fn parse_list(value: String) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<String>>()
+}
+
+fn parse_headers(value: String) -> Vec<(String, String)> {
+    parse_list(value)
+        .into_iter()
+        .map(|header| {
+            let mut parts = header.splitn(2, ':');
+            let name = parts.next().unwrap_or_default().trim().to_string();
+            let value = parts.next().expect("Invalid header").trim().to_string();
+
+            (name, value)
         })
-        .flatten()
-        .map(|res| async { res })
-        .buffer_unordered(buffer)
-        .map(move |(claim, account)| {
-            comment_api_ref
-                .stream_comments_by_claim_id(claim.id.clone(), *comment_page_ref)
-                .zip(stream::repeat((claim, account).clone()))
-                .map(|(comment, (claim, account))| (account, claim, comment))
+        .collect::<Vec<(String, String)>>()
+}
+
+fn parse_channel_urls(value: String) -> Vec<String> {
+    parse_list(value)
+        .into_iter()
+        .map(|url| {
+            url.parse::<LbryUrl>()
+                .ok()
+                .filter(|lbry_url| lbry_url.is_channel())
+                .map(|lbry_url| lbry_url.to_string())
+                .expect("Invalid channel URL")
         })
-        .flatten()
-        .map(|res| async { res })
-        .buffer_unordered(buffer)
+        .collect()
 }
 
-fn notify_new_comments(
-    api_ref: Arc<Api>,
-    storage_ref: Arc<Storage>,
-    emails_ref: Arc<Emails>,
-    mailer_ref: Arc<Mutex<SmtpTransport>>,
-    page_size_ref: Arc<usize>,
-) {
+fn filter_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("commenter")
+            .long("commenter")
+            .takes_value(true)
+            .help("Name or ID of the commenter"),
+        Arg::with_name("claim")
+            .long("claim")
+            .takes_value(true)
+            .help("Name or ID of the claim"),
+        Arg::with_name("contains")
+            .long("contains")
+            .takes_value(true)
+            .help("Text contained in the comment"),
+        Arg::with_name("since")
+            .long("since")
+            .takes_value(true)
+            .help("Date such as 2020-06-01"),
+        Arg::with_name("dry-run")
+            .long("dry-run")
+            .help("Lists the matching comments without changing them"),
+    ]
+}
+
+fn prompt(question: &str, default: &str) -> String {
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} [{}]: ", question, default);
+    }
+
+    io::stdout().flush().expect("Unable to flush stdout");
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("Unable to read answer");
+
+    match answer.trim() {
+        "" => default.to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+fn confirm(question: &str, default: bool) -> bool {
+    prompt(
+        &format!("{} (y/n)", question),
+        if default { "y" } else { "n" },
+    )
+    .to_lowercase()
+    .starts_with('y')
+}
+
+fn prompt_valid<F>(question: &str, default: &str, validate: F) -> String
+where
+    F: Fn(&str) -> Result<(), String>,
+{
+    loop {
+        let answer = prompt(question, default);
+
+        match validate(&answer) {
+            Ok(_) => return answer,
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+#[cfg(not(feature = "email"))]
+fn validate_email(value: &str) -> Result<(), String> {
+    if value.contains('@') {
+        Ok(())
+    } else {
+        Err(format!("Invalid email address {}", value))
+    }
+}
+
+#[cfg(feature = "email")]
+fn validate_email(value: &str) -> Result<(), String> {
+    EmailAddress::new(value.to_string())
+        .map(|_| ())
+        .map_err(|err| format!("Invalid email address {}: {}", value, err))
+}
+
+fn init_config(matches: &ArgMatches) {
+    let output = matches.value_of("output").unwrap_or(".env");
+
+    if Path::new(output).exists() && !matches.is_present("force") {
+        println!("{} already exists, pass --force to replace it", output);
+
+        return;
+    }
+
+    let default_value = |name: &str| {
+        env::var(name)
+            .ok()
+            .or_else(|| {
+                config_key(name).and_then(|config_key| config_key.default.map(String::from))
+            })
+            .unwrap_or_default()
+    };
+
+    let api_url = prompt_valid("LBRY SDK URL", &default_value("API_URL"), |value| {
+        if parse_list(value.to_string()).is_empty() {
+            Err("The SDK URL is required".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let api = Api::new(
+        parse_list(api_url.clone())
+            .into_iter()
+            .next()
+            .unwrap_or_default(),
+    );
+
     let mut rt = Builder::new()
-        .threaded_scheduler()
+        .basic_scheduler()
         .enable_io()
+        .enable_time()
         .build()
         .expect("Unable to create runtime");
 
-    rt.block_on(async {
-        info!("Finding new comments");
+    let reachable = match rt.block_on(api.status()) {
+        Ok(status) => {
+            match status.problems(true).as_slice() {
+                [] => println!("SDK is ready"),
+                problems => problems
+                    .iter()
+                    .for_each(|problem| println!("SDK is not ready: {}", problem)),
+            }
 
-        all_comments(api_ref, page_size_ref)
-            .filter_map(|(account, claim, comment)| async {
-                let comment_id = comment.id.to_owned();
+            true
+        }
+        Err(err) => {
+            println!("Unable to reach the SDK: {}", err);
 
-                if let Some(comment_entity) = storage_ref.get_comment_by_id(comment_id.clone()) {
-                    if &comment_entity.comment != &comment.comment {
-                        info!("Comment {} is updated", &comment_id);
+            if !confirm("Continue anyway?", false) {
+                return;
+            }
 
-                        storage_ref
-                            .delete_comment_by_id(comment_id)
-                            .expect("Could not delete comment");
+            false
+        }
+    };
 
-                        let new_comment_entity = storage_ref
-                            .save_comment(account, claim, comment)
-                            .expect("Could not save comment");
+    if reachable {
+        let page_size = 50;
+        let accounts = rt
+            .block_on(async {
+                api.try_stream_accounts(page_size)
+                    .try_collect::<Vec<Account>>()
+                    .await
+            })
+            .unwrap_or_else(|err| {
+                println!("Unable to list accounts: {}", err);
 
-                        Some(new_comment_entity)
-                    } else {
-                        None
-                    }
-                } else {
-                    info!("Logging new comment {}", &comment_id);
+                Vec::new()
+            });
 
-                    let new_comment_entity = storage_ref
-                        .save_comment(account, claim, comment)
-                        .expect("Could not save comment");
+        for account in accounts {
+            println!("Account {} ({})", account.name, account.id);
 
-                    Some(new_comment_entity)
-                }
-            })
-            .for_each_concurrent(None, |comment_entity| async {
-                info!("Sending email for {}", &comment_entity.commenter_name);
+            match rt.block_on(async {
+                api.try_stream_claims_by_account_id(account.id.clone(), page_size)
+                    .try_collect::<Vec<Claim>>()
+                    .await
+            }) {
+                Ok(claims) if claims.is_empty() => println!("    No claims"),
+                Ok(claims) => claims
+                    .iter()
+                    .for_each(|claim| println!("    {} ({})", claim.name, claim.id)),
+                Err(err) => println!("    Unable to list claims: {}", err),
+            }
+        }
+    }
 
-                let email: SendableEmail = emails_ref.notification_email(comment_entity).into();
+    let smtp_address = prompt("SMTP server address", &default_value("SMTP_ADDRESS"));
+    let smtp_from = prompt_valid(
+        "Sender email address",
+        &default_value("SMTP_FROM"),
+        validate_email,
+    );
+    let smtp_to = prompt_valid(
+        "Email address receiving the notifications",
+        &default_value("SMTP_TO"),
+        validate_email,
+    );
+    let webhook_urls = prompt_valid(
+        "Comma separated webhook URLs, none if empty",
+        &default_value("WEBHOOK_URLS"),
+        |value| {
+            parse_list(value.to_string())
+                .into_iter()
+                .try_for_each(|webhook_url| webhook_url.parse::<Webhook>().map(|_| ()))
+        },
+    );
 
-                mailer_ref
-                    .lock()
-                    .expect("Unable to get lock")
-                    .send(email)
-                    .expect("Unable to send mail");
-            })
-            .await;
+    #[cfg(feature = "email")]
+    if confirm(&format!("Send a test email to {}?", smtp_to), true) {
+        let sent = match SmtpClient::new(smtp_address.as_str(), ClientSecurity::None) {
+            Ok(client) => {
+                let mut mailer = client
+                    .hello_name(ClientId::Domain("localhost".to_string()))
+                    .smtp_utf8(true)
+                    .transport();
 
-        info!("Done reading comments");
+                Emails::new(smtp_from.clone(), smtp_to.clone())
+                    .notification_emails(&test_comment(), None, None, None)
+                    .into_iter()
+                    .all(|email| match mailer.send(email.into()) {
+                        Ok(_) => true,
+                        Err(err) => {
+                            println!("Unable to send test email: {:?}", err);
+
+                            false
+                        }
+                    })
+            }
+            Err(err) => {
+                println!("Unable to connect to {}: {:?}", smtp_address, err);
+
+                false
+            }
+        };
+
+        if sent {
+            println!("Test email sent to {}", smtp_to);
+        } else if !confirm("Write the configuration anyway?", false) {
+            return;
+        }
+    }
+
+    let contents = render_env_file(&[
+        ("API_URL", api_url),
+        ("SMTP_ADDRESS", smtp_address),
+        ("SMTP_FROM", smtp_from),
+        ("SMTP_TO", smtp_to),
+        ("WEBHOOK_URLS", webhook_urls),
+    ])
+    .expect("Invalid configuration");
+
+    fs::write(output, contents).expect("Unable to write configuration");
+
+    println!("Wrote {}", output);
+}
+
+fn explain_config() {
+    CONFIG_KEYS.iter().for_each(|config_key| {
+        let value = match env::var(config_key.name) {
+            Ok(value) => config_key.redact(&value),
+            Err(_) => match config_key.default {
+                Some(default) => format!("{} (default)", default),
+                None => "unset".to_string(),
+            },
+        };
+
+        println!(
+            "{} ({}, default {})",
+            config_key.name,
+            config_key.kind,
+            config_key.default.unwrap_or("unset")
+        );
+        println!("    {}", config_key.description);
+        println!("    = {}", value);
     });
 }
 
+fn apply_profile(profile: &str) {
+    let profiles_path = env::var("PROFILES_PATH").unwrap_or("profiles.toml".to_string());
+    let profiles = ConfigProfiles::parse(
+        &fs::read_to_string(&profiles_path).expect("Unable to read config profiles"),
+    )
+    .expect("Invalid config profiles");
+    let vars = profiles
+        .vars(profile)
+        .ok_or_else(|| format!("{} is not one of {}", profile, profiles.names().join(", ")))
+        .expect("Unknown config profile");
+
+    info!("Using profile {} from {}", profile, &profiles_path);
+
+    vars.iter()
+        .for_each(|(key, value)| env::set_var(key, value));
+}
+
 fn main() {
     env_logger::init();
     dotenv().ok();
 
+    let matches = App::new("runner")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Notifies new LBRY comments via email")
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .takes_value(true)
+                .help("Config profile overriding the environment, PROFILE by default"),
+        )
+        .arg(
+            Arg::with_name("allow-mismatched-env")
+                .long("allow-mismatched-env")
+                .help("Starts even if the API and notification targets look like different environments"),
+        )
+        .subcommand(
+            SubCommand::with_name("search")
+                .about("Manages saved searches notified on matching comments")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Adds or replaces a saved search")
+                        .arg(Arg::with_name("NAME").required(true))
+                        .arg(Arg::with_name("QUERY").required(true))
+                        .arg(
+                            Arg::with_name("to")
+                                .long("to")
+                                .takes_value(true)
+                                .help("Recipient of the search notifications"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Removes a saved search")
+                        .arg(Arg::with_name("NAME").required(true)),
+                )
+                .subcommand(SubCommand::with_name("list").about("Lists the saved searches")),
+        )
+        .subcommand(
+            SubCommand::with_name("channels")
+                .about("Manages notification channels stored in the database")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Adds or replaces a notification channel")
+                        .arg(Arg::with_name("NAME").required(true))
+                        .arg(
+                            Arg::with_name("email")
+                                .long("email")
+                                .takes_value(true)
+                                .required_unless("webhook")
+                                .conflicts_with("webhook")
+                                .help("Recipient to email, such as me@lbry.local|es|-05:00"),
+                        )
+                        .arg(
+                            Arg::with_name("webhook")
+                                .long("webhook")
+                                .takes_value(true)
+                                .help("Webhook URL to post to, such as https://example.com/hook|v2"),
+                        )
+                        .arg(
+                            Arg::with_name("token-env")
+                                .long("token-env")
+                                .takes_value(true)
+                                .requires("webhook")
+                                .help("Environment variable holding the webhook bearer token"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Removes a notification channel")
+                        .arg(Arg::with_name("NAME").required(true)),
+                )
+                .subcommand(
+                    SubCommand::with_name("list").about("Lists the notification channels"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Exports stored comments")
+                .subcommand(
+                    SubCommand::with_name("markdown")
+                        .about("Exports the comment thread of a claim as nested Markdown")
+                        .arg(Arg::with_name("CLAIM_ID").required(true))
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true)
+                                .help("File to write instead of the standard output"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("commenters")
+                        .about("Exports the unique commenters with their activity")
+                        .arg(
+                            Arg::with_name("format")
+                                .long("format")
+                                .takes_value(true)
+                                .possible_values(&["csv", "json"])
+                                .default_value("csv")
+                                .help("Format of the export"),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true)
+                                .help("File to write instead of the standard output"),
+                        )
+                        .arg(
+                            Arg::with_name("exclude-tag")
+                                .long("exclude-tag")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .default_value("spam")
+                                .help("Skips commenters with a comment of this tag"),
+                        )
+                        .arg(
+                            Arg::with_name("include-blocked")
+                                .long("include-blocked")
+                                .help("Keeps snoozed, hidden and tagged commenters"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("snooze")
+                .about("Manages snoozed notifications of claims or commenters")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Snoozes the notifications of a claim or commenter")
+                        .arg(
+                            Arg::with_name("KIND")
+                                .required(true)
+                                .possible_values(&["claim", "commenter"]),
+                        )
+                        .arg(
+                            Arg::with_name("TARGET")
+                                .required(true)
+                                .help("ID or name of the claim or commenter"),
+                        )
+                        .arg(
+                            Arg::with_name("for")
+                                .long("for")
+                                .takes_value(true)
+                                .default_value("24h")
+                                .help("Duration such as 30m, 6h, 2d or 1w"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Removes a snooze before it expires")
+                        .arg(
+                            Arg::with_name("KIND")
+                                .required(true)
+                                .possible_values(&["claim", "commenter"]),
+                        )
+                        .arg(Arg::with_name("TARGET").required(true)),
+                )
+                .subcommand(SubCommand::with_name("list").about("Lists the active snoozes")),
+        )
+        .subcommand(
+            SubCommand::with_name("claim")
+                .about("Manages the settings of claims")
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Sets how long the comments of a claim are kept")
+                        .arg(Arg::with_name("CLAIM_ID").required(true))
+                        .arg(
+                            Arg::with_name("retention")
+                                .long("retention")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Duration such as 7d or 4w, or forever"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("reset")
+                        .about("Resets a claim to the default retention")
+                        .arg(Arg::with_name("CLAIM_ID").required(true)),
+                )
+                .subcommand(SubCommand::with_name("list").about("Lists the claim settings"))
+                .subcommand(
+                    SubCommand::with_name("top")
+                        .about("Lists the claims with the most views")
+                        .arg(
+                            Arg::with_name("limit")
+                                .long("limit")
+                                .takes_value(true)
+                                .help("Number of claims to list, 10 by default"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("rules")
+                .about("Shares filters, blocklists and claim settings as YAML bundles")
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about("Exports the rules as a bundle")
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .takes_value(true)
+                                .help("Writes to a file instead of stdout"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about("Imports the rules of a bundle")
+                        .arg(Arg::with_name("FILE").required(true))
+                        .arg(
+                            Arg::with_name("on-conflict")
+                                .long("on-conflict")
+                                .takes_value(true)
+                                .possible_values(&["keep", "overwrite"])
+                                .default_value("keep")
+                                .help("Whether existing or imported rules win on conflicts"),
+                        )
+                        .arg(
+                            Arg::with_name("replace")
+                                .long("replace")
+                                .help("Removes the current rules before importing"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("Imports comments exported from YouTube Studio or Odysee")
+                .arg(Arg::with_name("FILE").required(true))
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["youtube", "odysee"])
+                        .required(true)
+                        .help("CSV comment export of YouTube or JSON comment export of Odysee"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("canned")
+                .about("Manages canned replies")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Adds or replaces a canned reply")
+                        .arg(Arg::with_name("NAME").required(true))
+                        .arg(
+                            Arg::with_name("BODY")
+                                .required(true)
+                                .help("Reply text with placeholders such as {commenter_name}"),
+                        )
+                        .arg(
+                            Arg::with_name("channel")
+                                .long("channel")
+                                .takes_value(true)
+                                .help("Channel name used to sign the reply by default"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Removes a canned reply")
+                        .arg(Arg::with_name("NAME").required(true)),
+                )
+                .subcommand(SubCommand::with_name("list").about("Lists the canned replies")),
+        )
+        .subcommand(
+            SubCommand::with_name("reply")
+                .about("Replies to a stored comment with a canned reply")
+                .arg(Arg::with_name("COMMENT_ID").required(true))
+                .arg(
+                    Arg::with_name("template")
+                        .long("template")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("channel")
+                        .long("channel")
+                        .takes_value(true)
+                        .help("Channel name used to sign the reply"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("test-notify")
+                .about("Sends a test notification through the configured channels")
+                .arg(
+                    Arg::with_name("channel")
+                        .long("channel")
+                        .takes_value(true)
+                        .possible_values(&["email", "webhook", "all"])
+                        .default_value("all"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("schema")
+                .about("Writes the JSON Schemas of the export formats")
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .takes_value(true)
+                        .default_value("schemas"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("maintenance")
+                .about("Checks the integrity of the database and vacuums it"),
+        )
+        .subcommand(
+            SubCommand::with_name("hide")
+                .about("Hides the comments matching a filter")
+                .args(&filter_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("tag")
+                .about("Tags the comments matching a filter")
+                .arg(Arg::with_name("TAG").required(true))
+                .args(&filter_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("init")
+                .about("Walks through a first-run setup and writes the configuration")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .default_value(".env"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Replaces an existing configuration file"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Inspects the configuration")
+                .subcommand(SubCommand::with_name("explain").about(
+                    "Lists every configuration key with its type, default and effective value",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("resync")
+                .about("Refetches every comment of stored claims without notifying")
+                .arg(
+                    Arg::with_name("claim")
+                        .long("claim")
+                        .takes_value(true)
+                        .help("Claim id or name to resync"),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("Resyncs every stored claim"),
+                )
+                .arg(
+                    Arg::with_name("since")
+                        .long("since")
+                        .takes_value(true)
+                        .help("Date such as 2020-06-01"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("read")
+                .about("Marks comments as read")
+                .arg(Arg::with_name("COMMENT_ID").required(true).multiple(true)),
+        )
+        .get_matches();
+
     info!("Loading config");
 
-    let keys = vec![
-        "API_URL".to_string(),
-        "DATABASE_URL".to_string(),
-        "PAGE_SIZE".to_string(),
-        "SMTP_ADDRESS".to_string(),
-        "SMTP_FROM".to_string(),
-        "SMTP_TO".to_string(),
-        "WATCHER_CRON".to_string(),
-    ];
+    if let Some(profile) = matches
+        .value_of("profile")
+        .map(String::from)
+        .or(env::var("PROFILE").ok())
+        .filter(|profile| !profile.is_empty())
+    {
+        apply_profile(&profile);
+    }
 
     dotenv::vars()
-        .filter(|(key, _)| keys.contains(key))
-        .for_each(|(key, value)| {
-            info!("{} = {}", key, value);
+        .filter_map(|(key, value)| config_key(&key).map(|config_key| (config_key, value)))
+        .for_each(|(config_key, value)| {
+            info!("{} = {}", config_key.name, config_key.redact(&value));
         });
 
-    let api_url = env::var("API_URL").unwrap_or("http://127.0.0.1:5279".to_string());
+    if let ("init", Some(init_matches)) = matches.subcommand() {
+        init_config(init_matches);
+
+        return;
+    }
+
+    if let ("config", Some(config_matches)) = matches.subcommand() {
+        if let ("explain", Some(_)) = config_matches.subcommand() {
+            explain_config();
+        } else {
+            println!("{}", config_matches.usage());
+        }
+
+        return;
+    }
+
+    #[cfg(feature = "scheduler")]
+    let anomaly_cron = env::var("ANOMALY_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let anomaly_threshold = env::var("ANOMALY_THRESHOLD")
+        .unwrap_or("3.0".to_string())
+        .parse::<f64>()
+        .unwrap_or(3.0);
+    #[cfg(feature = "scheduler")]
+    let anomaly_window = env::var("ANOMALY_WINDOW")
+        .unwrap_or("14".to_string())
+        .parse::<usize>()
+        .unwrap_or(14);
+    #[cfg(feature = "scheduler")]
+    let archive_cron = env::var("ARCHIVE_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let archive_days = env::var("ARCHIVE_DAYS")
+        .unwrap_or("365".to_string())
+        .parse::<i64>()
+        .unwrap_or(365);
+    let api_basic_auth = env::var("API_BASIC_AUTH").ok().map(|value| {
+        let mut parts = value.splitn(2, ':');
+        let username = parts.next().unwrap_or_default().to_string();
+        let password = parts.next().map(String::from);
+
+        (username, password)
+    });
+    let api_accept_invalid_certs = env::var("API_ACCEPT_INVALID_CERTS")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let api_batch_size = env::var("API_BATCH_SIZE")
+        .unwrap_or("1".to_string())
+        .parse::<usize>()
+        .unwrap_or(1);
+    let api_batch_window_ms = env::var("API_BATCH_WINDOW_MS")
+        .unwrap_or("10".to_string())
+        .parse::<u64>()
+        .unwrap_or(10);
+    let api_bearer_token = env::var("API_BEARER_TOKEN").ok();
+    let api_ca_cert = env::var("API_CA_CERT")
+        .ok()
+        .map(|path| fs::read(path).expect("Unable to read CA certificate"));
+    let api_compression = env::var("API_COMPRESSION")
+        .unwrap_or("true".to_string())
+        .parse::<bool>()
+        .unwrap_or(true);
+    let api_connect_timeout_secs = env::var("API_CONNECT_TIMEOUT_SECS")
+        .unwrap_or("10".to_string())
+        .parse::<u64>()
+        .unwrap_or(10);
+    let api_events = parse_list(env::var("API_EVENTS").unwrap_or("blockchain.block".to_string()));
+    let api_events_url = env::var("API_EVENTS_URL").ok();
+    let api_failover_cooldown_secs = env::var("API_FAILOVER_COOLDOWN_SECS")
+        .unwrap_or("60".to_string())
+        .parse::<u64>()
+        .unwrap_or(60);
+    let api_headers = parse_headers(env::var("API_HEADERS").unwrap_or("".to_string()));
+    let api_http2 = env::var("API_HTTP2")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let api_max_attempts = env::var("API_MAX_ATTEMPTS")
+        .unwrap_or("3".to_string())
+        .parse::<usize>()
+        .unwrap_or(3);
+    let api_max_in_flight = env::var("API_MAX_IN_FLIGHT")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok());
+    let api_page_concurrency = env::var("API_PAGE_CONCURRENCY")
+        .unwrap_or("4".to_string())
+        .parse::<usize>()
+        .unwrap_or(4);
+    let api_page_deadline_secs = env::var("API_PAGE_DEADLINE_SECS")
+        .unwrap_or("120".to_string())
+        .parse::<u64>()
+        .unwrap_or(120);
+    let api_pool_max_idle = env::var("API_POOL_MAX_IDLE")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok());
+    let api_proxy = env::var("API_PROXY").ok();
+    let api_rate_limit = env::var("API_RATE_LIMIT")
+        .ok()
+        .and_then(|value| value.parse::<f64>().ok());
+    let api_request_timeout_secs = env::var("API_REQUEST_TIMEOUT_SECS")
+        .unwrap_or("30".to_string())
+        .parse::<u64>()
+        .unwrap_or(30);
+    let api_retry_backoff_ms = env::var("API_RETRY_BACKOFF_MS")
+        .unwrap_or("500".to_string())
+        .parse::<u64>()
+        .unwrap_or(500);
+    let api_trace_slow_ms = env::var("API_TRACE_SLOW_MS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok());
+    let api_urls = parse_list(env::var("API_URL").unwrap_or("http://127.0.0.1:5279".to_string()));
+    let api_url = api_urls.first().cloned().expect("Missing API URL");
+    let api_user_agent = env::var("API_USER_AGENT").unwrap_or(DEFAULT_USER_AGENT.to_string());
+    let channel_urls = parse_channel_urls(env::var("CHANNEL_URLS").unwrap_or("".to_string()));
+    let claim_concurrency = env::var("CLAIM_CONCURRENCY")
+        .unwrap_or("4".to_string())
+        .parse::<usize>()
+        .unwrap_or(4);
+    let claim_feed_urls = parse_list(env::var("CLAIM_FEED_URLS").unwrap_or("".to_string()));
+    let claim_stats = env::var("CLAIM_STATS")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let comment_api_headers =
+        parse_headers(env::var("COMMENT_API_HEADERS").unwrap_or("".to_string()));
+    let comment_api_url =
+        env::var("COMMENT_API_URL").unwrap_or("https://comments.odysee.com/api/v2".to_string());
+    let comment_backend = env::var("COMMENT_BACKEND").ok().map(|value| {
+        value
+            .parse::<CommentBackend>()
+            .expect("Invalid comment backend")
+    });
+    let comment_sort = env::var("COMMENT_SORT")
+        .unwrap_or("newest".to_string())
+        .parse::<CommentSort>()
+        .expect("Invalid comment sort");
+    let database_pool_size = env::var("DATABASE_POOL_SIZE")
+        .unwrap_or(DEFAULT_POOL_SIZE.to_string())
+        .parse::<u32>()
+        .unwrap_or(DEFAULT_POOL_SIZE);
     let database_url = env::var("DATABASE_URL").unwrap_or("data.db".to_string());
+    let digest_max_bytes = env::var("DIGEST_MAX_BYTES")
+        .unwrap_or("1000000".to_string())
+        .parse::<usize>()
+        .unwrap_or(1000000);
+    let digest_order = env::var("DIGEST_ORDER")
+        .unwrap_or("chronological".to_string())
+        .parse::<DigestOrder>()
+        .unwrap_or(DigestOrder::Chronological);
+    #[cfg(feature = "email")]
+    let digest_similarity = env::var("DIGEST_SIMILARITY")
+        .ok()
+        .and_then(|value| value.parse::<f64>().ok());
+    let disk_min_free_mb = env::var("DISK_MIN_FREE_MB").ok().map(|value| {
+        value
+            .parse::<u64>()
+            .expect("Invalid minimum free disk space")
+    });
+    let downtime_threshold = env::var("DOWNTIME_THRESHOLD")
+        .ok()
+        .map(|value| parse_duration(&value).expect("Invalid downtime threshold"));
+    let export_path = env::var("EXPORT_PATH").unwrap_or("export.jsonl".to_string());
+    #[cfg(feature = "scheduler")]
+    let heartbeat_cron = env::var("HEARTBEAT_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let heartbeat_days = env::var("HEARTBEAT_DAYS")
+        .unwrap_or("7".to_string())
+        .parse::<i64>()
+        .unwrap_or(7);
+    let hidden_comments = env::var("HIDDEN_COMMENTS")
+        .unwrap_or("fetch".to_string())
+        .parse::<HiddenComments>()
+        .expect("Invalid hidden comments mode");
+    let incremental_comments = env::var("INCREMENTAL_COMMENTS")
+        .unwrap_or("false".to_string())
+        .parse::<IncrementalComments>()
+        .expect("Invalid incremental comments mode");
+    #[cfg(feature = "scheduler")]
+    let maintenance_cron = env::var("MAINTENANCE_CRON").ok();
+    let metrics_job = env::var("METRICS_JOB").unwrap_or("lbry_comment_notifier".to_string());
+    let metrics_pushgateway_url = env::var("METRICS_PUSHGATEWAY_URL").ok();
+    let metrics_statsd_address = env::var("METRICS_STATSD_ADDRESS").ok();
+    let milestone_actions =
+        parse_list(env::var("MILESTONE_ACTIONS").unwrap_or("email".to_string()))
+            .into_iter()
+            .map(|action| action.parse::<MilestoneAction>())
+            .collect::<Result<Vec<MilestoneAction>, String>>()
+            .expect("Invalid milestone action");
+    let milestone_reply_channel = env::var("MILESTONE_REPLY_CHANNEL").ok();
+    let milestone_reply_template = env::var("MILESTONE_REPLY_TEMPLATE")
+        .unwrap_or("Thank you for your {milestone} comments, {commenter_name}!".to_string());
+    let milestone_webhook_urls =
+        parse_list(env::var("MILESTONE_WEBHOOK_URLS").unwrap_or("".to_string()));
+    let milestone_counts = parse_list(env::var("MILESTONES").unwrap_or("".to_string()))
+        .into_iter()
+        .map(|count| count.parse::<i64>())
+        .collect::<Result<Vec<i64>, _>>()
+        .expect("Invalid milestone");
+    let monitor_notify = env::var("MONITOR_NOTIFY")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let monitor_urls = parse_channel_urls(env::var("MONITOR_URLS").unwrap_or("".to_string()));
+    let notify_max_age = env::var("NOTIFY_MAX_AGE")
+        .ok()
+        .map(|value| parse_duration(&value).expect("Invalid notify max age"));
+    let notify_mode = env::var("NOTIFY_MODE")
+        .unwrap_or("instant".to_string())
+        .parse::<NotifyMode>()
+        .unwrap_or(NotifyMode::Instant);
+    let notify_ping = env::var("NOTIFY_PING")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let notify_source_templates =
+        parse_list(env::var("NOTIFY_SOURCE_TEMPLATES").unwrap_or("".to_string()));
+    let notify_sources = parse_list(env::var("NOTIFY_SOURCES").unwrap_or("".to_string()));
+    let notify_template_a = env::var("NOTIFY_TEMPLATE_A").ok();
+    let notify_template_b = env::var("NOTIFY_TEMPLATE_B").ok();
+    let notify_template_split = env::var("NOTIFY_TEMPLATE_SPLIT")
+        .unwrap_or("50".to_string())
+        .parse::<u8>()
+        .unwrap_or(50)
+        .min(100);
+    let otel_exporter_otlp_endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
+    let otel_service_name =
+        env::var("OTEL_SERVICE_NAME").unwrap_or("lbry-comment-notifier".to_string());
     let page_size = env::var("PAGE_SIZE")
         .unwrap_or("50".to_string())
         .parse::<usize>()
         .unwrap_or(50);
+    let preflight = env::var("PREFLIGHT")
+        .unwrap_or("true".to_string())
+        .parse::<bool>()
+        .unwrap_or(true);
+    let preflight_notify = env::var("PREFLIGHT_NOTIFY")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    #[cfg(feature = "scheduler")]
+    let prune_cron = env::var("PRUNE_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let reaction_cron = env::var("REACTION_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let reaction_days = env::var("REACTION_DAYS")
+        .unwrap_or("30".to_string())
+        .parse::<i64>()
+        .unwrap_or(30);
+    #[cfg(feature = "scheduler")]
+    let reconcile_cron = env::var("RECONCILE_CRON").ok();
+    #[cfg(feature = "scheduler")]
+    let reconcile_resync = env::var("RECONCILE_RESYNC")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    #[cfg(feature = "read-links")]
+    let read_link_address = env::var("READ_LINK_ADDRESS")
+        .unwrap_or("127.0.0.1:8742".to_string())
+        .parse::<SocketAddr>()
+        .expect("Invalid READ_LINK_ADDRESS");
+    #[cfg(feature = "read-links")]
+    let read_links = match (env::var("READ_LINK_URL"), env::var("READ_LINK_SECRET")) {
+        (Ok(url), Ok(secret)) => Some(ReadLinks::new(url, secret)),
+        _ => None,
+    };
+    #[cfg(feature = "scheduler")]
+    let reminder_cron = env::var("REMINDER_CRON").ok();
+    #[cfg(all(feature = "scheduler", feature = "email"))]
+    let report_cron = env::var("REPORT_CRON").ok();
+    #[cfg(all(feature = "scheduler", feature = "email"))]
+    let report_recipients = parse_list(env::var("REPORT_RECIPIENTS").unwrap_or("".to_string()));
+    #[cfg(all(feature = "scheduler", feature = "email"))]
+    let report_template = env::var("REPORT_TEMPLATE")
+        .ok()
+        .map(|path| fs::read_to_string(path).expect("Unable to read report template"))
+        .unwrap_or(DEFAULT_REPORT_TEMPLATE.to_string());
+    #[cfg(all(feature = "scheduler", feature = "email"))]
+    let report_top = env::var("REPORT_TOP")
+        .unwrap_or("10".to_string())
+        .parse::<usize>()
+        .unwrap_or(10);
+    let retention_days = env::var("RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i32>().ok());
+    let run_deadline = env::var("RUN_DEADLINE")
+        .ok()
+        .map(|value| parse_duration(&value).expect("Invalid run deadline"));
+    let scrub_patterns = parse_list(env::var("SCRUB_PATTERNS").unwrap_or("".to_string()))
+        .into_iter()
+        .map(|pattern| pattern.parse::<ScrubPattern>())
+        .collect::<Result<Vec<ScrubPattern>, String>>()
+        .expect("Invalid scrub pattern");
+    let scrub_regex = env::var("SCRUB_REGEX").ok();
+    let sdk_fallback = env::var("SDK_FALLBACK")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let signature_check = env::var("SIGNATURE_CHECK")
+        .unwrap_or("off".to_string())
+        .parse::<SignatureCheck>()
+        .expect("Invalid signature check mode");
+    #[cfg(feature = "email")]
     let smtp_address = env::var("SMTP_ADDRESS").unwrap_or("127.0.0.1:1025".to_string());
+    #[cfg(feature = "email")]
     let smtp_from = env::var("SMTP_FROM").unwrap_or("notifier@lbry.local".to_string());
+    #[cfg(feature = "email")]
+    let smtp_recipients = parse_list(env::var("SMTP_RECIPIENTS").unwrap_or("".to_string()))
+        .into_iter()
+        .map(|recipient| recipient.parse::<Recipient>())
+        .collect::<Result<Vec<Recipient>, String>>()
+        .expect("Invalid recipient");
+    #[cfg(feature = "email")]
     let smtp_to = env::var("SMTP_TO").unwrap_or("user@lbry.local".to_string());
+    #[cfg(feature = "scheduler")]
+    let support_cron = env::var("SUPPORT_CRON").ok();
+    let test_api_patterns = parse_list(env::var("TEST_API_PATTERNS").unwrap_or("".to_string()));
+    let test_target_patterns =
+        parse_list(env::var("TEST_TARGET_PATTERNS").unwrap_or("".to_string()));
+    let throttle_limit = env::var("THROTTLE_LIMIT")
+        .unwrap_or("0".to_string())
+        .parse::<usize>()
+        .unwrap_or(0);
+    let throttle_window_mins = env::var("THROTTLE_WINDOW_MINS")
+        .unwrap_or("60".to_string())
+        .parse::<i64>()
+        .unwrap_or(60);
+    #[cfg(feature = "scheduler")]
+    let transaction_cron = env::var("TRANSACTION_CRON").ok();
+    let translate_api_key = env::var("TRANSLATE_API_KEY").ok();
+    let translate_provider = env::var("TRANSLATE_PROVIDER").ok().map(|value| {
+        value
+            .parse::<TranslationProvider>()
+            .expect("Invalid translation provider")
+    });
+    let translate_to = env::var("TRANSLATE_TO").unwrap_or("en".to_string());
+    let translate_url = env::var("TRANSLATE_URL").ok();
+    let verify_links = env::var("VERIFY_LINKS")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let view_count_url = env::var("VIEW_COUNT_URL").unwrap_or(DEFAULT_VIEW_COUNT_URL.to_string());
+    let watch_channels = parse_list(env::var("WATCH_CHANNELS").unwrap_or("".to_string()));
+    #[cfg(feature = "scheduler")]
     let watcher_cron = env::var("WATCHER_CRON").unwrap_or("* 0 * * * *".to_string());
+    let webhook_urls = parse_list(env::var("WEBHOOK_URLS").unwrap_or("".to_string()))
+        .into_iter()
+        .map(|webhook_url| webhook_url.parse::<Webhook>())
+        .collect::<Result<Vec<Webhook>, String>>()
+        .expect("Invalid webhook URL");
+
+    #[cfg(feature = "email")]
+    let email_targets = if smtp_recipients.is_empty() {
+        vec![smtp_to.clone()]
+    } else {
+        smtp_recipients
+            .iter()
+            .map(|recipient| recipient.address.clone())
+            .collect()
+    };
+    #[cfg(not(feature = "email"))]
+    let email_targets = Vec::<String>::new();
+    let notification_targets = email_targets
+        .into_iter()
+        .chain(webhook_urls.iter().map(|webhook| webhook.url.clone()))
+        .collect::<Vec<String>>();
+
+    let interlock = EnvInterlock::new(test_api_patterns, test_target_patterns);
+
+    if let Err(mismatch) = api_urls
+        .iter()
+        .try_for_each(|api_url| interlock.check(api_url, &notification_targets))
+    {
+        if matches.is_present("allow-mismatched-env") {
+            warn!("Starting with a mismatched environment: {}", mismatch);
+        } else {
+            error!(
+                "Refusing to start, pass --allow-mismatched-env to override: {}",
+                mismatch
+            );
+
+            process::exit(1);
+        }
+    }
+
+    let storage = Storage::open_with_pool_size(database_url.clone(), database_pool_size)
+        .expect("Unable to connect to database");
+    let retry_policy = RetryPolicy {
+        max_attempts: api_max_attempts.max(1),
+        initial_backoff: time::Duration::from_millis(api_retry_backoff_ms),
+        ..RetryPolicy::default()
+    };
+    let api_timeouts = ApiTimeouts {
+        connect_timeout: time::Duration::from_secs(api_connect_timeout_secs),
+        request_timeout: time::Duration::from_secs(api_request_timeout_secs),
+        page_deadline: time::Duration::from_secs(api_page_deadline_secs),
+    };
+    let client_config = ClientConfig {
+        max_idle_per_host: api_pool_max_idle,
+        http2_prior_knowledge: api_http2,
+        proxy: api_proxy,
+        root_certificate: api_ca_cert,
+        accept_invalid_certs: api_accept_invalid_certs,
+        compression: api_compression,
+        user_agent: api_user_agent.clone(),
+    };
+
+    if api_accept_invalid_certs {
+        warn!("Accepting invalid TLS certificates of the SDK");
+    }
+
+    let api = Api::with_client_config(api_url, api_timeouts.clone(), client_config.clone())
+        .with_endpoints(
+            Endpoints::new(api_urls)
+                .with_cooldown(time::Duration::from_secs(api_failover_cooldown_secs)),
+        )
+        .with_retry_policy(retry_policy.clone())
+        .with_page_concurrency(api_page_concurrency)
+        .with_comment_visibility(hidden_comments.visibility())
+        .with_comment_sort(comment_sort)
+        .with_rate_limiter(RateLimiter::new(api_rate_limit, api_max_in_flight))
+        .with_batcher(RequestBatcher::new(
+            api_batch_size,
+            time::Duration::from_millis(api_batch_window_ms),
+        ));
+    let spans = SpanCollector::new();
+    let tracer = match api_trace_slow_ms {
+        Some(api_trace_slow_ms) => ApiTracer::default().with_hook(move |trace| {
+            if trace.duration >= time::Duration::from_millis(api_trace_slow_ms) {
+                info!("API call {}", trace);
+            }
+        }),
+        None => ApiTracer::default(),
+    };
+    let tracer = match otel_exporter_otlp_endpoint {
+        Some(_) => {
+            let spans = spans.clone();
+
+            tracer.with_hook(move |trace| spans.record(trace))
+        }
+        None => tracer,
+    };
+    let api = api.with_tracer(tracer.clone());
+    let api = match api_basic_auth {
+        Some((username, password)) => api.with_basic_auth(username, password),
+        None => api,
+    };
+    let api = match api_bearer_token {
+        Some(token) => api.with_bearer_token(token),
+        None => api,
+    };
+    let api = api_headers
+        .into_iter()
+        .fold(api, |api, (name, value)| api.with_header(name, value));
+    let commentron = Commentron::with_client_config(
+        comment_api_url.clone(),
+        api_timeouts.clone(),
+        ClientConfig {
+            max_idle_per_host: api_pool_max_idle,
+            compression: api_compression,
+            user_agent: api_user_agent,
+            ..ClientConfig::default()
+        },
+    )
+    .with_retry_policy(retry_policy.clone())
+    .with_page_concurrency(api_page_concurrency)
+    .with_comment_visibility(hidden_comments.visibility())
+    .with_comment_sort(comment_sort)
+    .with_tracer(tracer);
+    let commentron = comment_api_headers
+        .into_iter()
+        .fold(commentron, |commentron, (name, value)| {
+            commentron.with_header(name, value)
+        });
+    #[cfg(feature = "email")]
+    let emails = match digest_similarity {
+        Some(threshold) => Emails::new(smtp_from, smtp_to).with_similarity(threshold),
+        None => Emails::new(smtp_from, smtp_to),
+    }
+    .with_recipients(smtp_recipients)
+    .with_hidden_flag(hidden_comments == HiddenComments::Flag)
+    .with_unverified_flag(signature_check == SignatureCheck::Flag);
+    #[cfg(feature = "read-links")]
+    let emails = emails.with_read_links(read_links.clone());
+    let disk_guard = disk_min_free_mb.map(|min_free_mb| {
+        let paths = if is_server_url(&database_url) {
+            vec![PathBuf::from(&export_path)]
+        } else {
+            vec![PathBuf::from(&database_url), PathBuf::from(&export_path)]
+        };
+
+        DiskGuard::new(paths, min_free_mb)
+    });
+    let exporter = Exporter::new(export_path);
+    let scrubber = match scrub_regex {
+        Some(pattern) => Scrubber::new(&scrub_patterns)
+            .with_regex(&pattern)
+            .expect("Invalid scrub regex"),
+        None => Scrubber::new(&scrub_patterns),
+    };
+    let scrubber = Some(scrubber).filter(|it| !it.is_empty());
+    let webhooks =
+        Webhooks::with_timeouts(webhook_urls, api_timeouts.clone()).with_scrubber(scrubber.clone());
+    let metrics = MetricsPusher::new(metrics_job);
+    let metrics = match metrics_pushgateway_url {
+        Some(url) => metrics.with_pushgateway(url),
+        None => metrics,
+    };
+    let metrics = match metrics_statsd_address {
+        Some(address) => metrics.with_statsd(address),
+        None => metrics,
+    };
+    let template_split = match (notify_template_a, notify_template_b) {
+        (Some(template_a), Some(template_b)) => {
+            let load_template = |path: &str| {
+                fs::read_to_string(path)
+                    .expect("Unable to read template")
+                    .parse::<Template>()
+                    .expect("Invalid template")
+            };
 
-    let storage = Storage::open(database_url.clone()).expect("Unable to connect to database");
-    let api = Api::new(api_url.clone());
-    let emails = Emails::new(smtp_from, smtp_to);
+            Some(TemplateSplit {
+                variant_a: load_template(&template_a),
+                variant_b: load_template(&template_b),
+                percent_a: notify_template_split,
+            })
+        }
+        _ => None,
+    };
+    let source_templates = notify_source_templates
+        .into_iter()
+        .map(|source_template| {
+            let mut parts = source_template.splitn(2, ':');
+            let source = parts.next().unwrap_or_default().to_string();
+            let path = parts.next().expect("Invalid source template");
+            let template = fs::read_to_string(path)
+                .expect("Unable to read template")
+                .parse::<Template>()
+                .expect("Invalid template");
+
+            (source, template)
+        })
+        .collect::<HashMap<String, Template>>();
+    let milestones = Milestones {
+        counts: milestone_counts,
+        actions: milestone_actions,
+        reply_channel: milestone_reply_channel,
+        reply_template: milestone_reply_template,
+        webhook_urls: milestone_webhook_urls,
+    };
+    let throttle = Throttle::new(throttle_limit, Duration::minutes(throttle_window_mins));
 
+    #[cfg(feature = "email")]
     let mailer = SmtpClient::new(smtp_address, ClientSecurity::None)
         .expect("Unable to connect to SMTP client")
         .hello_name(ClientId::Domain("localhost".to_string()))
@@ -157,45 +4948,580 @@ fn main() {
         .connection_reuse(ConnectionReuseParameters::ReuseUnlimited)
         .transport();
 
-    let storage_ref = Arc::new(storage);
-    let api_ref = Arc::new(api);
-    let emails_ref = Arc::new(emails);
-    let mailer_ref = Arc::new(Mutex::new(mailer));
-    let page_size_ref = Arc::new(page_size);
+    let context = Context {
+        api: Arc::new(api),
+        commentron: Arc::new(commentron),
+        comment_backend,
+        claim_feeds: Arc::new(ClaimFeeds::new(claim_feed_urls)),
+        view_counts: if claim_stats {
+            Some(Arc::new(ViewCounts::new(view_count_url)))
+        } else {
+            None
+        },
+        verify_links,
+        incremental_comments,
+        hidden_comments,
+        signature_check,
+        metrics: Arc::new(metrics),
+        otlp: otel_exporter_otlp_endpoint
+            .map(|endpoint| Arc::new(OtlpExporter::new(endpoint, otel_service_name))),
+        spans,
+        storage: Arc::new(storage),
+        #[cfg(feature = "email")]
+        emails: Arc::new(emails),
+        #[cfg(feature = "email")]
+        mailer: Arc::new(Mutex::new(mailer)),
+        exporter: Arc::new(exporter),
+        webhooks: Arc::new(webhooks),
+        throttle: Arc::new(Mutex::new(throttle)),
+        template_split: Arc::new(template_split),
+        milestones: Arc::new(milestones),
+        channel_urls: Arc::new(channel_urls),
+        monitor_urls: Arc::new(monitor_urls),
+        watch_channels: Arc::new(watch_channels),
+        monitor_notify,
+        notify_max_age,
+        run_deadline,
+        notify_mode,
+        downtime_threshold,
+        notify_ping,
+        notify_sources: Arc::new(notify_sources),
+        source_templates: Arc::new(source_templates),
+        digest_order,
+        digest_max_bytes,
+        retention_days,
+        page_size: Arc::new(page_size),
+        claim_concurrency,
+        preflight,
+        preflight_notify,
+        sdk_fallback,
+        sdk_unready: Arc::new(AtomicBool::new(false)),
+        disk_guard,
+        disk_low: Arc::new(AtomicBool::new(false)),
+        own_channel_ids: Arc::new(Mutex::new(HashSet::new())),
+        translator: translate_provider.map(|provider| {
+            let translator = Translator::new(provider, translate_to)
+                .with_api_key(translate_api_key)
+                .with_scrubber(scrubber);
+
+            Arc::new(match translate_url {
+                Some(url) => translator.with_url(url),
+                None => translator,
+            })
+        }),
+    };
+
+    refresh_notification_channels(&context);
+
+    match matches.subcommand() {
+        ("import", Some(import_matches)) => {
+            import_comments(&context, import_matches);
+
+            return;
+        }
+        ("canned", Some(canned_matches)) => {
+            manage_canned_replies(&context, canned_matches);
+
+            return;
+        }
+        ("reply", Some(reply_matches)) => {
+            reply_to_comment(&context, reply_matches);
+
+            return;
+        }
+        ("claim", Some(claim_matches)) => {
+            manage_claim_settings(&context, claim_matches);
+
+            return;
+        }
+        ("export", Some(export_matches)) => {
+            export_comments(&context, export_matches);
+
+            return;
+        }
+        ("rules", Some(rules_matches)) => {
+            manage_rules(&context, rules_matches);
+
+            return;
+        }
+        ("test-notify", Some(test_notify_matches)) => {
+            test_notify(&context, test_notify_matches);
+
+            return;
+        }
+        ("schema", Some(schema_matches)) => {
+            write_schemas(schema_matches);
+
+            return;
+        }
+        ("hide", Some(hide_matches)) => {
+            hide_comments(&context, hide_matches);
+
+            return;
+        }
+        ("tag", Some(tag_matches)) => {
+            tag_comments(&context, tag_matches);
+
+            return;
+        }
+        ("maintenance", Some(_)) => {
+            maintain_storage(&context);
+
+            return;
+        }
+        ("read", Some(read_matches)) => {
+            mark_comments_as_read(&context, read_matches);
+
+            return;
+        }
+        ("resync", Some(resync_matches)) => {
+            resync_comments(&context, resync_matches);
+
+            return;
+        }
+        ("search", Some(search_matches)) => {
+            manage_saved_searches(&context, search_matches);
+
+            return;
+        }
+        ("channels", Some(channels_matches)) => {
+            manage_notification_channels(&context, channels_matches);
+
+            return;
+        }
+        ("snooze", Some(snooze_matches)) => {
+            manage_snoozes(&context, snooze_matches);
+
+            return;
+        }
+        _ => {}
+    }
 
     info!("Starting application");
 
-    let mut sched = JobScheduler::new();
-    let watcher_job = Job::new(
-        watcher_cron.parse().expect("Unable to create watcher job"),
-        || {
-            info!("Starting task to notify new comments");
+    recover_deliveries(&context);
+
+    #[cfg(feature = "read-links")]
+    if let Some(read_links) = read_links {
+        serve_read_links(read_links, read_link_address, context.storage.clone());
+    }
+
+    notify_new_comments(&context);
+
+    let event_comments = api_events_url.map(|api_events_url| {
+        info!("Watching events from {}", &api_events_url);
+
+        watch_events(
+            context.api.clone(),
+            context.storage.clone(),
+            context.incremental_comments,
+            EventSubscription::new(api_events_url, api_events),
+            *context.page_size,
+        )
+    });
+
+    #[cfg(not(feature = "scheduler"))]
+    if let Some(event_comments) = event_comments {
+        for comment in event_comments.iter() {
+            let comments = std::iter::once(comment)
+                .chain(event_comments.try_iter())
+                .collect::<Vec<(Account, Claim, Comment)>>();
+
+            notify_event_comments(&context, comments);
+        }
+    }
+
+    #[cfg(feature = "scheduler")]
+    {
+        let mut sched = JobScheduler::new();
+        let watcher_job = Job::new(
+            watcher_cron.parse().expect("Unable to create watcher job"),
+            || {
+                info!("Starting task to notify new comments");
+
+                notify_new_comments(&context);
+
+                info!("Done task for notifying new comments");
+            },
+        );
+
+        sched.add(watcher_job);
+
+        if let Some(anomaly_cron) = anomaly_cron {
+            let anomaly_job = Job::new(
+                anomaly_cron.parse().expect("Unable to create anomaly job"),
+                || {
+                    info!("Starting task to detect comment anomalies");
+
+                    notify_comment_anomalies(&context, anomaly_window, anomaly_threshold);
+
+                    info!("Done task for detecting comment anomalies");
+                },
+            );
+
+            sched.add(anomaly_job);
+        }
+
+        if let Some(archive_cron) = archive_cron {
+            let archive_job = Job::new(
+                archive_cron.parse().expect("Unable to create archive job"),
+                || {
+                    info!("Starting task to archive old comments");
+
+                    archive_comments(&context, archive_days);
+
+                    info!("Done task for archiving old comments");
+                },
+            );
+
+            sched.add(archive_job);
+        }
+
+        if let Some(heartbeat_cron) = heartbeat_cron {
+            let heartbeat_job = Job::new(
+                heartbeat_cron
+                    .parse()
+                    .expect("Unable to create heartbeat job"),
+                || {
+                    info!("Starting task to send heartbeat");
+
+                    send_heartbeat(&context, heartbeat_days);
+
+                    info!("Done task for sending heartbeat");
+                },
+            );
+
+            sched.add(heartbeat_job);
+        }
+
+        if let Some(maintenance_cron) = maintenance_cron {
+            let maintenance_job = Job::new(
+                maintenance_cron
+                    .parse()
+                    .expect("Unable to create maintenance job"),
+                || {
+                    info!("Starting task to maintain storage");
+
+                    maintain_storage(&context);
+
+                    info!("Done task for maintaining storage");
+                },
+            );
+
+            sched.add(maintenance_job);
+        }
+
+        if let Some(prune_cron) = prune_cron {
+            let prune_job = Job::new(
+                prune_cron.parse().expect("Unable to create prune job"),
+                || {
+                    info!("Starting task to prune expired comments");
+
+                    prune_comments(&context);
+
+                    info!("Done task for pruning expired comments");
+                },
+            );
+
+            sched.add(prune_job);
+        }
+
+        #[cfg(feature = "email")]
+        if let Some(report_cron) = report_cron {
+            let report_job = Job::new(
+                report_cron.parse().expect("Unable to create report job"),
+                || {
+                    info!("Starting task to send monthly report");
+
+                    send_monthly_report(&context, &report_recipients, &report_template, report_top);
+
+                    info!("Done task for sending monthly report");
+                },
+            );
+
+            sched.add(report_job);
+        }
+
+        if let Some(reaction_cron) = reaction_cron {
+            let reaction_job = Job::new(
+                reaction_cron
+                    .parse()
+                    .expect("Unable to create reaction job"),
+                || {
+                    info!("Starting task to notify comment reactions");
+
+                    notify_comment_reactions(&context, reaction_days);
+
+                    info!("Done task for notifying comment reactions");
+                },
+            );
+
+            sched.add(reaction_job);
+        }
+
+        if let Some(reconcile_cron) = reconcile_cron {
+            let reconcile_job = Job::new(
+                reconcile_cron
+                    .parse()
+                    .expect("Unable to create reconcile job"),
+                || {
+                    info!("Starting task to reconcile comment counts");
+
+                    reconcile_comments(&context, reconcile_resync);
+
+                    info!("Done task for reconciling comment counts");
+                },
+            );
+
+            sched.add(reconcile_job);
+        }
+
+        if let Some(support_cron) = support_cron {
+            let support_job = Job::new(
+                support_cron.parse().expect("Unable to create support job"),
+                || {
+                    info!("Starting task to notify new supports");
+
+                    notify_new_supports(&context);
+
+                    info!("Done task for notifying new supports");
+                },
+            );
+
+            sched.add(support_job);
+        }
+
+        if let Some(transaction_cron) = transaction_cron {
+            let transaction_job = Job::new(
+                transaction_cron
+                    .parse()
+                    .expect("Unable to create transaction job"),
+                || {
+                    info!("Starting task to notify new transactions");
+
+                    notify_new_transactions(&context);
+
+                    info!("Done task for notifying new transactions");
+                },
+            );
+
+            sched.add(transaction_job);
+        }
 
-            notify_new_comments(
-                api_ref.clone(),
-                storage_ref.clone(),
-                emails_ref.clone(),
-                mailer_ref.clone(),
-                page_size_ref.clone(),
+        if let Some(reminder_cron) = reminder_cron {
+            let reminder_job = Job::new(
+                reminder_cron
+                    .parse()
+                    .expect("Unable to create reminder job"),
+                || {
+                    info!("Starting task to remind unread comments");
+
+                    remind_unread_comments(&context);
+
+                    info!("Done task for reminding unread comments");
+                },
             );
 
-            info!("Done task for notifying new comments");
+            sched.add(reminder_job);
+        }
+
+        loop {
+            sched.tick();
+
+            match &event_comments {
+                Some(event_comments) => {
+                    if let Ok(comment) = event_comments.recv_timeout(sched.time_till_next_job()) {
+                        let comments = std::iter::once(comment)
+                            .chain(event_comments.try_iter())
+                            .collect::<Vec<(Account, Claim, Comment)>>();
+
+                        notify_event_comments(&context, comments);
+                    }
+                }
+                None => std::thread::sleep(sched.time_till_next_job()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::StreamExt;
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
         },
-    );
+    };
 
-    notify_new_comments(
-        api_ref.clone(),
-        storage_ref.clone(),
-        emails_ref.clone(),
-        mailer_ref.clone(),
-        page_size_ref.clone(),
-    );
+    use super::{
+        all_comments, apply_comment_delta, is_awaiting_reply, CommentSources, SignatureCheck,
+    };
+    use core::{
+        diff_comments,
+        testing::{account, claim, comment, FakeApi},
+        Account, Channel, Comment, CommentBackend, CommentCursors, CommentEntity, CommentStop,
+        CommentStore, Commentron, MemoryStore,
+    };
 
-    sched.add(watcher_job);
+    fn signed_comment(is_signature_valid: Option<bool>) -> Comment {
+        Comment {
+            is_signature_valid,
+            ..comment("id", "claim_id")
+        }
+    }
 
-    loop {
-        sched.tick();
+    #[test]
+    fn signature_check_should_drop_unverified_comments() {
+        let drop = "drop".parse::<SignatureCheck>().expect("Invalid mode");
+
+        assert!(!drop.drops(&signed_comment(Some(true))));
+        assert!(drop.drops(&signed_comment(Some(false))));
+        assert!(drop.drops(&signed_comment(None)));
+        assert!(!SignatureCheck::Flag.drops(&signed_comment(None)));
+        assert!(!SignatureCheck::Off.drops(&signed_comment(Some(false))));
+    }
+
+    #[test]
+    fn all_comments_should_walk_fake_api() {
+        let api = FakeApi::new()
+            .with_account(account("account"))
+            .with_channel(
+                "account",
+                Channel {
+                    id: "channel".to_string(),
+                    name: "@channel".to_string(),
+                    has_signing_key: true,
+                },
+            )
+            .with_channel(
+                "account",
+                Channel {
+                    id: "other".to_string(),
+                    name: "@other".to_string(),
+                    has_signing_key: true,
+                },
+            )
+            .with_claim("channel", claim("first"))
+            .with_claim("channel", claim("second"))
+            .with_claim("other", claim("unwatched"))
+            .with_comment(comment("a", "first"))
+            .with_comment(comment("b", "second"))
+            .with_comment(comment("c", "second"))
+            .with_comment(comment("d", "unwatched"));
+        let comment_stops = vec![(
+            "second".to_string(),
+            CommentStop::Known(vec!["c".to_string()].into_iter().collect::<HashSet<_>>()),
+        )]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+        let failures = Arc::new(AtomicUsize::new(0));
+
+        let mut comments = futures::executor::block_on(
+            all_comments(
+                CommentSources {
+                    api: Arc::new(api),
+                    commentron: Arc::new(Commentron::new("http://127.0.0.1:9".to_string())),
+                    comment_backend: CommentBackend::Sdk,
+                    page_size: 50,
+                    cursors: CommentCursors::default(),
+                    comment_stops: Arc::new(comment_stops),
+                    failures: failures.clone(),
+                },
+                Arc::new(vec!["@channel".to_string()]),
+                vec![],
+                2,
+            )
+            .map(|(account, claim, comment)| (account.id, claim.id, comment.id))
+            .collect::<Vec<(String, String, String)>>(),
+        );
+
+        comments.sort();
+
+        assert_eq!(
+            comments,
+            vec![
+                ("account".to_string(), "first".to_string(), "a".to_string()),
+                ("account".to_string(), "second".to_string(), "b".to_string()),
+            ]
+        );
+        assert_eq!(failures.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn apply_comment_delta_should_sync_memory_store() {
+        let store = MemoryStore::new();
+        let account = account("account");
+
+        for id in &["a", "b"] {
+            store
+                .save_comment(account.clone(), claim("claim"), comment(id, "claim"))
+                .expect("Unable to save");
+        }
+
+        let mut edited = comment("a", "claim");
+        edited.comment = "Edited".to_string();
+
+        let stored = store
+            .get_comments_by_claim_id("claim".to_string())
+            .expect("Unable to fetch");
+        let delta = diff_comments(stored, vec![edited, comment("c", "claim")]);
+
+        apply_comment_delta(&store, account, claim("claim"), delta);
+
+        let mut comments = store
+            .get_comments_by_claim_id("claim".to_string())
+            .expect("Unable to fetch")
+            .into_iter()
+            .map(|comment_entity| (comment_entity.id, comment_entity.comment))
+            .collect::<Vec<(String, String)>>();
+
+        comments.sort();
+
+        assert_eq!(
+            comments,
+            vec![
+                ("a".to_string(), "Edited".to_string()),
+                ("c".to_string(), "Hello".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn is_awaiting_reply_should_skip_answered_and_monitored_comments() {
+        let account = account("account");
+        let entity = |id: &str, commenter_id: &str, account: Account| {
+            let mut comment = comment(id, "claim");
+            comment.commenter_id = commenter_id.to_string();
+
+            CommentEntity::new(account, claim("claim"), comment)
+        };
+        let ids = |ids: &[&str]| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<HashSet<String>>()
+        };
+        let own_channel_ids = ids(&["own"]);
+        let replied_ids = ids(&["answered"]);
+        let monitored_ids = ids(&["monitored"]);
+        let monitored = Account {
+            id: "monitored".to_string(),
+            name: "@monitored".to_string(),
+            is_default: false,
+        };
+        let awaiting = |comment_entity: CommentEntity| {
+            is_awaiting_reply(
+                &comment_entity,
+                &own_channel_ids,
+                &replied_ids,
+                &monitored_ids,
+            )
+        };
 
-        std::thread::sleep(sched.time_till_next_job());
+        assert!(awaiting(entity("open", "commenter", account.clone())));
+        assert!(!awaiting(entity("answered", "commenter", account.clone())));
+        assert!(!awaiting(entity("reply", "own", account)));
+        assert!(!awaiting(entity("third_party", "commenter", monitored)));
     }
 }